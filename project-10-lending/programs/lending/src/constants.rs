@@ -1,8 +1,37 @@
 use anchor_lang::prelude::*;
 
-#[constant]
+// 每个银行在 init_bank 时传入自己的 Pyth 价格源 ID，例如：
+//     SOL/USD  0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d
+//     USDC/USD 0xeaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a
 // https://pyth.network/developers/price-feed-ids#solana-stable
-pub const SOL_USD_FEED_ID: &str = "0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
-pub const USDC_USD_FEED_ID: &str = "0xeaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a";
+// 每个银行可以通过 update_bank 设置自己的最大价格时效（max_price_age），这里是新建银行时的默认值
+#[constant]
 pub const MAXIMUM_AGE: u64 = 100; // allow price feed 100 sec old, to avoid stale price feed errors
 
+
+/// 基点分母（10000 bps = 100%）
+pub const BPS_DENOMINATOR: u128 = 10_000;
+/// 定点数精度（1e18），利率与利用率均以 WAD 表示
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+/// 一年的秒数，用于把年化利率换算为按秒计息
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+/// 健康因子精度（基点），10000 表示 1.0，可以表示 1.2、1.5 这样的小数
+pub const HEALTH_FACTOR_PRECISION: u64 = 10_000;
+/// 健康因子低于该值（1.0）时可以被清算，借款、取款等操作后也不能低于该值
+pub const MIN_HEALTH_FACTOR: u64 = HEALTH_FACTOR_PRECISION;
+/// 每个用户最多同时持有的头寸数量（每个银行一个）
+pub const MAX_POSITIONS: usize = 8;
+/// 新建银行时默认的清算奖励（基点），可通过 update_bank 调整
+pub const DEFAULT_LIQUIDATION_BONUS: u64 = 500;
+/// 新建银行时默认的清算关闭因子（基点），即单次清算最多偿还的债务比例
+pub const DEFAULT_LIQUIDATION_CLOSE_FACTOR: u64 = 5_000;
+/// 新建银行时默认的储备金率（基点），即利息中划入保险基金的比例
+pub const DEFAULT_RESERVE_FACTOR: u64 = 1_000;
+/// 新建银行时默认的闪电贷手续费（基点）
+pub const DEFAULT_FLASH_LOAN_FEE: u64 = 9;
+/// 隔离模式债务以稳定币计价（假设 1 美元），统一换算为 6 位小数
+pub const ISOLATION_DEBT_DECIMALS: u8 = 6;
+/// 利率模型的最大年化借款利率（基点，base_rate + slope1 + slope2，1000%），避免计息溢出使银行无法操作
+pub const MAX_BORROW_RATE: u64 = 100_000;
+/// 新建银行时默认允许的最大置信区间（基点，conf / price）
+pub const DEFAULT_MAX_CONFIDENCE: u64 = 200;
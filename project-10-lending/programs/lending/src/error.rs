@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::math::{BorrowCheck, MathError};
 
 #[error_code]
 pub enum ErrorCode {
@@ -30,5 +31,124 @@ pub enum ErrorCode {
     /// 当尝试清算健康因子大于等于1的头寸时触发
     /// 确保只有真正抵押不足的头寸才能被清算
     #[msg("User is not undercollateralized.")]
-    NotUndercollateralized
-}
\ No newline at end of file
+    NotUndercollateralized,
+    /// 数学运算溢出
+    /// 定点数计算（利息、份额等）溢出时触发
+    #[msg("Math overflow.")]
+    MathOverflow,
+    /// 利率模型参数无效
+    /// 拐点利用率必须在 (0, 100%] 之间，base_rate + slope1 + slope2 不能超过 MAX_BORROW_RATE
+    #[msg("Invalid interest rate configuration.")]
+    InvalidInterestRateConfig,
+    /// 头寸数量已达上限
+    /// 用户同时持有的银行头寸不能超过 MAX_POSITIONS
+    #[msg("Too many positions.")]
+    TooManyPositions,
+    /// 头寸不存在
+    /// 用户在该银行没有存款或借款
+    #[msg("Position not found.")]
+    PositionNotFound,
+    /// 剩余账户与用户头寸不匹配
+    /// remaining_accounts 必须按头寸顺序传入 [bank, price_update(, fallback_price_update)] 账户组
+    #[msg("Remaining accounts do not match the user's positions.")]
+    InvalidRemainingAccounts,
+    /// 价格源与银行配置不匹配，或价格不为正
+    #[msg("Price feed does not match the bank's oracle.")]
+    InvalidOracle,
+    /// 份额为零
+    /// 存入金额太小，按当前兑换比例换不到任何份额
+    #[msg("Amount is too small to mint any shares.")]
+    ZeroShares,
+    /// 风险参数无效
+    /// 需满足 0 < max_ltv <= liquidation_threshold < 100%，清算奖励不能让清算阈值处的抵押品不够支付，
+    /// 清算关闭因子在 (0, 100%] 之间
+    #[msg("Invalid bank risk parameters.")]
+    InvalidRiskParams,
+    /// 无权限
+    /// 只有银行的管理员（或待接收的新管理员）可以执行该操作
+    #[msg("Unauthorized.")]
+    Unauthorized,
+    /// 银行已暂停
+    /// 暂停期间不能存款、借款和提取，还款与清算不受影响
+    #[msg("Bank is paused.")]
+    BankPaused,
+    /// 超过存款上限
+    #[msg("Deposit exceeds the bank's deposit cap.")]
+    DepositCapExceeded,
+    /// 超过借款上限
+    #[msg("Borrow exceeds the bank's borrow cap.")]
+    BorrowCapExceeded,
+    /// 清算数量无效
+    /// 实际偿还数量为零（请求数量为零或借款人在该银行没有债务）
+    #[msg("Liquidation amount must be greater than zero.")]
+    InvalidLiquidationAmount,
+    /// 抵押品银行与借款银行相同
+    #[msg("Collateral and borrowed banks must differ.")]
+    SameCollateralAndBorrowBank,
+    /// 闪电贷未还款
+    /// flash_borrow 之后必须有一条指向它、同金额、同银行的 flash_repay
+    #[msg("Flash loan is not repaid in this transaction.")]
+    FlashLoanNotRepaid,
+    /// 闪电贷无效
+    /// 金额为零、通过 CPI 调用，或 flash_repay 与对应的 flash_borrow 不匹配
+    #[msg("Invalid flash loan.")]
+    InvalidFlashLoan,
+    /// 价格置信区间过宽
+    /// conf / price 超过银行设置的 max_confidence
+    #[msg("Oracle confidence interval is too wide.")]
+    OracleConfidenceTooWide,
+    /// 隔离资产不能与其他抵押品混合
+    /// 隔离资产只能作为用户唯一的抵押品
+    #[msg("Isolated collateral cannot be combined with other collateral.")]
+    IsolatedCollateralMix,
+    /// 隔离模式下不能借出该资产
+    #[msg("Asset is not borrowable in isolation mode.")]
+    NotBorrowableInIsolation,
+    /// 超过隔离资产的债务上限
+    #[msg("Isolation mode debt ceiling exceeded.")]
+    IsolationDebtCeilingExceeded,
+    /// 缺少或传错了隔离资产银行账户
+    #[msg("Isolated bank account is missing or does not match the user's isolated bank.")]
+    IsolatedBankMismatch,
+    /// 资产不属于用户选择的 e-mode 分组
+    #[msg("Asset is not in the user's e-mode group.")]
+    EModeMismatch,
+    /// 借款人仍有抵押品
+    /// 只有所有存款都已清零、仍有借款的头寸才能作为坏账核销
+    #[msg("Borrower still has collateral.")]
+    BorrowerHasCollateral,
+    /// 银行已有存款时不能切换隔离模式
+    #[msg("Isolation mode cannot be changed while the bank has deposits.")]
+    BankHasDeposits,
+    /// 资金池已耗尽
+    /// 坏账核销后总存款（或总借款）归零但仍有份额，无法按比例发行新份额，拒绝存款和借款
+    #[msg("Bank has outstanding shares but no assets.")]
+    DepletedPool,
+}
+
+impl From<MathError> for anchor_lang::error::Error {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::Overflow => ErrorCode::MathOverflow.into(),
+            MathError::InvalidPrice => ErrorCode::InvalidOracle.into(),
+            MathError::InvalidInterestRateConfig => ErrorCode::InvalidInterestRateConfig.into(),
+            MathError::InvalidPosition => ErrorCode::PositionNotFound.into(),
+            MathError::DepletedPool => ErrorCode::DepletedPool.into(),
+        }
+    }
+}
+
+impl BorrowCheck {
+    /// 转换为链上 borrow 返回的错误，Allowed 时为 Ok
+    pub fn into_result(self) -> Result<()> {
+        match self {
+            BorrowCheck::Allowed => Ok(()),
+            BorrowCheck::BankPaused => err!(ErrorCode::BankPaused),
+            BorrowCheck::BorrowCapExceeded => err!(ErrorCode::BorrowCapExceeded),
+            BorrowCheck::EModeMismatch => err!(ErrorCode::EModeMismatch),
+            BorrowCheck::NotBorrowableInIsolation => err!(ErrorCode::NotBorrowableInIsolation),
+            BorrowCheck::IsolationDebtCeilingExceeded => err!(ErrorCode::IsolationDebtCeilingExceeded),
+            BorrowCheck::OverLTV => err!(ErrorCode::OverLTV),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use crate::error::ErrorCode;
+use crate::math::{value_usd, PositionValue};
+use crate::oracle::{load_price, OraclePrice};
+use crate::state::*;
+
+// 用户的所有头寸通过 remaining_accounts 传入，每个头寸按顺序对应一组账户：
+//     [bank, price_update]，银行配置了备用价格源时再追加一个 fallback_price_update
+// 备用价格源只在主价格源过期或无效时使用，见 oracle::load_price。
+// 本指令中已经作为可变账户加载的银行（可能已被修改）通过 loaded_banks 传入，
+// 估值时优先使用内存中的最新状态，而不是 remaining_accounts 中尚未写回的数据。
+// 如果本指令新开了一个头寸，它排在 positions 的末尾，对应的账户组也要放在最后。
+// 估值结果的汇总（健康因子等）见 math.rs。
+pub fn value_positions(
+    user: &User,
+    remaining_accounts: &[AccountInfo],
+    loaded_banks: &[(Pubkey, &Bank)],
+) -> Result<Vec<PositionValue>> {
+    let clock = Clock::get()?;
+    let mut accounts = remaining_accounts;
+    let mut values = Vec::with_capacity(user.positions.len());
+
+    for position in user.positions.iter() {
+        let (key, bank, price) = next_bank_and_price(&mut accounts, loaded_banks, &clock)?;
+        require_keys_eq!(key, position.bank, ErrorCode::InvalidRemainingAccounts);
+        values.push(position_value(user, position, &bank, &price)?);
+    }
+    require!(accounts.is_empty(), ErrorCode::InvalidRemainingAccounts);
+
+    Ok(values)
+}
+
+/// 从 accounts 开头读取一个银行的账户组并把 accounts 前移到下一组：银行优先使用本指令中已加载的状态，
+/// 否则从账户数据反序列化并计息到当前时间（只影响内存中的副本）
+pub fn next_bank_and_price(
+    accounts: &mut &[AccountInfo],
+    loaded_banks: &[(Pubkey, &Bank)],
+    clock: &Clock,
+) -> Result<(Pubkey, Bank, OraclePrice)> {
+    let [bank_info, price_info, rest @ ..] = *accounts else {
+        return err!(ErrorCode::InvalidRemainingAccounts);
+    };
+    let bank = match loaded_banks.iter().find(|(key, _)| *key == bank_info.key()) {
+        Some((_, bank)) => (*bank).clone(),
+        None => {
+            require_keys_eq!(*bank_info.owner, crate::ID, ErrorCode::InvalidRemainingAccounts);
+            let mut bank = Bank::try_deserialize(&mut &bank_info.try_borrow_data()?[..])?;
+            bank.accrue_interest(clock.unix_timestamp)?;
+            bank
+        }
+    };
+
+    let (fallback_info, rest) = match rest {
+        [fallback_info, rest @ ..] if bank.has_fallback_oracle() => (Some(fallback_info), rest),
+        [] if bank.has_fallback_oracle() => return err!(ErrorCode::InvalidRemainingAccounts),
+        _ => (None, rest),
+    };
+    *accounts = rest;
+
+    let price_update = price_update_from(price_info)?;
+    let fallback_price_update = fallback_info.map(price_update_from).transpose()?;
+    let price = load_price(&bank, &price_update, fallback_price_update.as_ref(), clock)?;
+    Ok((bank_info.key(), bank, price))
+}
+
+fn price_update_from(info: &AccountInfo) -> Result<PriceUpdateV2> {
+    require_keys_eq!(*info.owner, PriceUpdateV2::owner(), ErrorCode::InvalidOracle);
+    PriceUpdateV2::try_deserialize(&mut &info.try_borrow_data()?[..])
+}
+
+/// 按份额换算出当前的存款和债务数量（已包含利息），再按保守价格估值
+pub fn position_value(user: &User, position: &Position, bank: &Bank, price: &OraclePrice) -> Result<PositionValue> {
+    let deposited = bank.deposit_amount(position.deposited_shares)?;
+    let borrowed = bank.debt_amount(position.borrowed_shares)?;
+    let (max_ltv, liquidation_threshold) = bank.ltv_and_threshold(user.emode_group);
+    Ok(PositionValue {
+        collateral_value: value_usd(deposited, bank.mint_decimals, price.collateral_price())?,
+        debt_value: value_usd(borrowed, bank.mint_decimals, price.debt_price())?,
+        liquidation_threshold,
+        max_ltv,
+        emode_group: bank.emode_group,
+    })
+}
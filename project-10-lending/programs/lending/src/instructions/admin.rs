@@ -1,5 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{ Mint, TokenAccount, TokenInterface };
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
+use crate::constants::{
+    DEFAULT_FLASH_LOAN_FEE, DEFAULT_LIQUIDATION_BONUS, DEFAULT_LIQUIDATION_CLOSE_FACTOR,
+    DEFAULT_MAX_CONFIDENCE, DEFAULT_RESERVE_FACTOR, MAXIMUM_AGE,
+};
+use crate::error::ErrorCode;
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -24,10 +30,64 @@ pub struct InitBank<'info> {
         bump,
     )]
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init, 
+        token::mint = mint, 
+        token::authority = insurance_fund_token_account,
+        payer = signer,
+        seeds = [b"insurance", mint.key().as_ref()],
+        bump,
+    )]
+    pub insurance_fund_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        mint::decimals = mint.decimals,
+        mint::authority = bank,
+        mint::token_program = token_program,
+        payer = signer,
+        seeds = [b"receipt", mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        token::mint = receipt_mint,
+        token::authority = bank,
+        payer = signer,
+        seeds = [b"receipt_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     pub token_program: Interface<'info, TokenInterface>, 
     pub system_program: Program <'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateBank<'info> {
+    pub authority: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub bank: Account<'info, Bank>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptBankAuthority<'info> {
+    pub pending_authority: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump,
+        constraint = bank.pending_authority == pending_authority.key() @ ErrorCode::Unauthorized,
+    )]
+    pub bank: Account<'info, Bank>,
+}
+
 #[derive(Accounts)]
 pub struct InitUser<'info> {
     #[account(mut)]
@@ -43,19 +103,127 @@ pub struct InitUser<'info> {
     pub system_program: Program <'info, System>,
 }
 
-pub fn process_init_bank(ctx: Context<InitBank>, liquidation_threshold: u64, max_ltv: u64) -> Result<()> {
+pub fn process_init_bank(
+    ctx: Context<InitBank>,
+    oracle_feed_id: String,
+    liquidation_threshold: u64,
+    max_ltv: u64,
+    interest_rate_config: InterestRateConfig,
+) -> Result<()> {
     let bank = &mut ctx.accounts.bank;
     bank.mint_address = ctx.accounts.mint.key();
     bank.authority = ctx.accounts.signer.key();
+    bank.mint_decimals = ctx.accounts.mint.decimals;
+    bank.oracle_feed_id = get_feed_id_from_hex(&oracle_feed_id)?;
+    bank.max_price_age = MAXIMUM_AGE;
+    bank.fallback_max_price_age = MAXIMUM_AGE;
+    bank.max_confidence = DEFAULT_MAX_CONFIDENCE;
     bank.liquidation_threshold = liquidation_threshold;
     bank.max_ltv = max_ltv;
+    bank.liquidation_bonus = DEFAULT_LIQUIDATION_BONUS;
+    bank.liquidation_close_factor = DEFAULT_LIQUIDATION_CLOSE_FACTOR;
+    bank.flash_loan_fee = DEFAULT_FLASH_LOAN_FEE;
+    bank.reserve_factor = DEFAULT_RESERVE_FACTOR;
+    bank.set_interest_rate_config(interest_rate_config)?;
+    bank.validate_risk_params()?;
+    bank.last_updated = Clock::get()?.unix_timestamp;
+    Ok(())
+}
+
+// 管理员更新银行的风险参数、上限、暂停状态、闪电贷手续费、储备金率、预言机参数、隔离模式、e-mode 和利率模型
+// 修改利率模型前先按旧参数计息，保证已经过去的时间不会按新利率计算
+pub fn process_update_bank(ctx: Context<UpdateBank>, params: UpdateBankParams) -> Result<()> {
+    let bank = &mut ctx.accounts.bank;
+    bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+
+    if let Some(liquidation_threshold) = params.liquidation_threshold {
+        bank.liquidation_threshold = liquidation_threshold;
+    }
+    if let Some(max_ltv) = params.max_ltv {
+        bank.max_ltv = max_ltv;
+    }
+    if let Some(liquidation_bonus) = params.liquidation_bonus {
+        bank.liquidation_bonus = liquidation_bonus;
+    }
+    if let Some(liquidation_close_factor) = params.liquidation_close_factor {
+        bank.liquidation_close_factor = liquidation_close_factor;
+    }
+    if let Some(deposit_cap) = params.deposit_cap {
+        bank.deposit_cap = deposit_cap;
+    }
+    if let Some(borrow_cap) = params.borrow_cap {
+        bank.borrow_cap = borrow_cap;
+    }
+    if let Some(paused) = params.paused {
+        bank.paused = paused;
+    }
+    if let Some(flash_loan_fee) = params.flash_loan_fee {
+        bank.flash_loan_fee = flash_loan_fee;
+    }
+    if let Some(reserve_factor) = params.reserve_factor {
+        bank.reserve_factor = reserve_factor;
+    }
+    if let Some(max_price_age) = params.max_price_age {
+        bank.max_price_age = max_price_age;
+    }
+    if let Some(fallback_max_price_age) = params.fallback_max_price_age {
+        bank.fallback_max_price_age = fallback_max_price_age;
+    }
+    if let Some(max_confidence) = params.max_confidence {
+        bank.max_confidence = max_confidence;
+    }
+    if let Some(fallback_oracle_feed_id) = params.fallback_oracle_feed_id {
+        bank.fallback_oracle_feed_id = if fallback_oracle_feed_id.is_empty() {
+            [0u8; 32]
+        } else {
+            get_feed_id_from_hex(&fallback_oracle_feed_id)?
+        };
+    }
+    if let Some(isolated) = params.isolated {
+        // 已有存款的用户没有按隔离模式的规则存入，切换后他们的头寸会违反隔离规则
+        if isolated != bank.isolated {
+            require!(bank.total_deposit_shares == 0, ErrorCode::BankHasDeposits);
+        }
+        bank.isolated = isolated;
+    }
+    if let Some(isolation_debt_ceiling) = params.isolation_debt_ceiling {
+        bank.isolation_debt_ceiling = isolation_debt_ceiling;
+    }
+    if let Some(borrowable_in_isolation) = params.borrowable_in_isolation {
+        bank.borrowable_in_isolation = borrowable_in_isolation;
+    }
+    if let Some(emode_group) = params.emode_group {
+        bank.emode_group = emode_group;
+    }
+    if let Some(emode_max_ltv) = params.emode_max_ltv {
+        bank.emode_max_ltv = emode_max_ltv;
+    }
+    if let Some(emode_liquidation_threshold) = params.emode_liquidation_threshold {
+        bank.emode_liquidation_threshold = emode_liquidation_threshold;
+    }
+    if let Some(interest_rate_config) = params.interest_rate_config {
+        bank.set_interest_rate_config(interest_rate_config)?;
+    }
+    bank.validate_risk_params()
+}
+
+// 管理员权限转移第一步：当前管理员提名新管理员（传入默认公钥即可取消提名）
+pub fn process_transfer_bank_authority(ctx: Context<UpdateBank>, new_authority: Pubkey) -> Result<()> {
+    ctx.accounts.bank.pending_authority = new_authority;
+    Ok(())
+}
+
+// 管理员权限转移第二步：被提名的新管理员签名确认，避免把权限转给无法签名的地址
+pub fn process_accept_bank_authority(ctx: Context<AcceptBankAuthority>) -> Result<()> {
+    let bank = &mut ctx.accounts.bank;
+    bank.authority = bank.pending_authority;
+    bank.pending_authority = Pubkey::default();
     Ok(())
 }
 
-pub fn process_init_user(ctx: Context<InitUser>, usdc_address: Pubkey) -> Result<()> {
+pub fn process_init_user(ctx: Context<InitUser>) -> Result<()> {
     let user = &mut ctx.accounts.user_account;
     user.owner = ctx.accounts.signer.key();
-    user.usdc_address = usdc_address;
     
     let now = Clock::get()?.unix_timestamp; 
     user.last_updated = now;
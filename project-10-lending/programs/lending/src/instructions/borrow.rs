@@ -1,12 +1,11 @@
-use std::f32::consts::E;
-
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{ self, Mint, TokenAccount, TokenInterface, TransferChecked };
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
-use crate::constants::{MAXIMUM_AGE, SOL_USD_FEED_ID, USDC_USD_FEED_ID};
 use crate::state::*;
 use crate::error::ErrorCode;
+use crate::health::value_positions;
+use crate::math::{health_factor, within_max_ltv};
+use crate::math::{amount_to_shares_up, check_borrow_limits, isolation_debt_units};
 
 #[derive(Accounts)]
 pub struct Borrow<'info> {
@@ -39,63 +38,51 @@ pub struct Borrow<'info> {
         associated_token::token_program = token_program,
     )]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>, 
-    pub price_update: Account<'info, PriceUpdateV2>,
+    /// 用户处于隔离模式时传入其隔离资产银行，用于累计隔离债务；否则传 None
+    #[account(mut)]
+    pub isolated_bank: Option<Account<'info, Bank>>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-// 1. Check if user has enough collateral to borrow
-// 2. Warn if borrowing beyond the safe amount but still allow if within the max borrowable amount
-// 3. Make a CPI transfer from the bank's token account to the user's token account
-// 4. Update the user's borrowed amount and total borrowed value
-// 5. Update the bank's total borrows and total borrow shares
-// 1. 检查用户是否有足够的抵押品进行借贷
-// 2. 如果借贷超过安全金额则发出警告，但如果在最大可借贷金额内仍允许借贷
-// 3. 从银行的代币账户向用户的代币账户进行 CPI 转账
-// 4. 更新用户的借贷金额和总借贷价值
-// 5. 更新银行的总借贷和总借贷份额
-// 这个 process_borrow 函数实现了借贷协议的核心借贷逻辑。 process_borrow 函数首先通过
-// Pyth 预言机获取实时价格来计算用户的抵押品价值，然后验证借贷金额是否在允许范围内，最后通过 CPI
-// 调用将代币从银行转移给用户并更新相关状态。该函数还包含了利息计算功能 calculate_accrued_interest 使用复利公式来计算累积利息。
+// 1. Make a CPI transfer from the bank's token account to the user's token account
+// 2. Update the bank's total borrows and total borrow shares
+// 3. Update the user's borrow position for this bank
+// 4. Check the user's health factor across all positions (remaining_accounts)
+// 1. 从银行的代币账户向用户的代币账户进行 CPI 转账
+// 2. 更新银行的总借贷和总借贷份额
+// 3. 更新用户在该银行的借款头寸
+// 4. 通过 remaining_accounts 在所有头寸上检查健康因子
+// 这个 process_borrow 函数实现了借贷协议的核心借贷逻辑。借款后通过 Pyth 预言机为用户的每个头寸估值，
+// 验证抵押品是否足够，不足则整笔交易回滚。利息由 Bank::accrue_interest 统一计入银行总额。
 
 pub fn process_borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
-    // 检查用户是否有足够的抵押品进行借贷
-    // Check if user has enough collateral to borrow
-    let bank = &mut ctx.accounts.bank;
-    let user = &mut ctx.accounts.user_account;
-
-    // 获取价格更新账户的可变引用
-    let price_update = &mut ctx.accounts.price_update;
-
-    let total_collateral: u64;
-
-    // 根据要借贷的代币类型计算总抵押品价值
-    match ctx.accounts.mint.to_account_info().key() {
-        key if key == user.usdc_address => {
-            // 如果借贷 USDC，则使用 SOL 作为抵押品
-            let sol_feed_id = get_feed_id_from_hex(SOL_USD_FEED_ID)?; 
-            let sol_price = price_update.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE, &sol_feed_id)?;
-            // 计算累积利息
-            let accrued_interest = calculate_accrued_interest(user.deposited_sol, bank.interest_rate, user.last_updated)?;
-            // 计算总抵押品价值（SOL 价格 × SOL 存款数量）
-            total_collateral = sol_price.price as u64 * (user.deposited_sol + accrued_interest);
-        },
-        _ => {
-            // 如果借贷 SOL，则使用 USDC 作为抵押品
-            let usdc_feed_id = get_feed_id_from_hex(USDC_USD_FEED_ID)?;
-            let usdc_price = price_update.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE, &usdc_feed_id)?;
-            // 计算总抵押品价值（USDC 价格 × USDC 存款数量）
-            total_collateral = usdc_price.price as u64 * user.deposited_usdc;
-
-        }
-    }
-    // 计算可借贷金额（总抵押品价值 × 清算阈值）
-    let borrowable_amount = total_collateral as u64 *  bank.liquidation_threshold;
-
-    // 检查请求借贷金额是否超过可借贷金额
-    if borrowable_amount < amount {
-        return Err(ErrorCode::OverBorrowableAmount.into());
+    // 先计息，保证后续份额换算使用最新的兑换比例
+    ctx.accounts.bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+
+    // 暂停、借款上限、e-mode 分组和隔离模式的检查与链下模拟使用同一个 math::check_borrow_limits；
+    // 用户处于隔离模式时必须传入其隔离资产银行
+    let bank = &ctx.accounts.bank;
+    let user = &ctx.accounts.user_account;
+    let isolated_bank = if user.is_isolated() {
+        let isolated_bank_key = user.isolated_bank;
+        let isolated_bank = ctx.accounts.isolated_bank
+            .as_ref()
+            .filter(|b| b.key() == isolated_bank_key)
+            .ok_or(ErrorCode::IsolatedBankMismatch)?;
+        Some(&**isolated_bank)
+    } else {
+        None
+    };
+    let limits = bank.borrow_limits(user.emode_group, isolated_bank);
+    check_borrow_limits(&limits, amount, bank.mint_decimals)?.into_result()?;
+
+    // 隔离模式：借款计入隔离资产的累计债务（向上取整）
+    if let Some((isolation_debt, _)) = limits.isolation {
+        let units = isolation_debt_units(amount, bank.mint_decimals, true)?;
+        let isolated_bank = ctx.accounts.isolated_bank.as_mut().ok_or(ErrorCode::IsolatedBankMismatch)?;
+        isolated_bank.isolation_debt = isolation_debt.checked_add(units).ok_or(ErrorCode::MathOverflow)?;
     }
 
     // 设置代币转账的 CPI 账户结构
@@ -125,40 +112,29 @@ pub fn process_borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
     // 执行代币转账，从银行转给用户
     token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
 
-    // 如果这是银行的第一笔借贷，初始化借贷数据
-    if bank.total_borrowed == 0 {
-        bank.total_borrowed = amount;
-        bank.total_borrowed_shares = amount;
-    }
+    let bank = &mut ctx.accounts.bank;
+    let user = &mut ctx.accounts.user_account;
 
-    // 计算借贷比例和用户份额
-    let borrow_ratio = amount.checked_div(bank.total_borrowed).unwrap();
-    let users_shares = bank.total_borrowed_shares.checked_mul(borrow_ratio).unwrap();
+    // 借款份额 = 借款金额 × 总借款份额 / 总借款（向上取整，对池子有利），首笔借款按 1:1
+    let users_shares = amount_to_shares_up(amount, bank.total_borrowed_shares, bank.total_borrowed)?;
 
     // 更新银行的总借贷和总借贷份额
     bank.total_borrowed += amount;
     bank.total_borrowed_shares += users_shares;
 
-    // 根据借贷的代币类型更新用户的借贷余额
-    match ctx.accounts.mint.to_account_info().key() {
-        key if key == user.usdc_address => {
-            // 如果借贷 USDC，更新用户的 USDC 借贷数据
-            user.borrowed_usdc += amount;
-            user.deposited_usdc_shares += users_shares;
-        },
-        _ => {
-            // 如果借贷 SOL，更新用户的 SOL 借贷数据
-            user.borrowed_sol += amount;
-            user.deposited_sol_shares += users_shares;
-        }
+    // 更新用户在该银行的借款头寸（不存在则新建，排在头寸列表末尾）
+    let bank_key = bank.key();
+    let position = user.position_mut(&bank_key)?;
+    position.borrowed_shares += users_shares;
+
+    // 检查用户是否有足够的抵押品进行借贷
+    // 借款使用最大贷款价值比率而不是清算阈值：借款后 Σ(抵押品价值 × max_ltv) 必须覆盖全部借款，
+    // 两者之间的空间留给价格波动，避免刚借完就可以被清算
+    let values = value_positions(user, ctx.remaining_accounts, &[(bank_key, &**bank)])?;
+    if !within_max_ltv(&values) {
+        return Err(ErrorCode::OverLTV.into());
     }
+    user.health_factor = health_factor(&values);
 
     Ok(())
 }
-
-fn calculate_accrued_interest(deposited: u64, interest_rate: u64, last_update: i64) -> Result<u64> {
-    let current_time = Clock::get()?.unix_timestamp;
-    let time_elapsed = current_time - last_update;
-    let new_value = (deposited as f64 * E.powf(interest_rate as f32 * time_elapsed as f32) as f64) as u64;
-    Ok(new_value)
-}
\ No newline at end of file
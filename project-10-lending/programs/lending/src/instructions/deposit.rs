@@ -2,6 +2,9 @@ use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{ self, Mint, TokenAccount, TokenInterface, TransferChecked };
 use crate::state::*;
+use crate::error::ErrorCode;
+use crate::math::amount_to_shares_down;
+use super::receipt::mint_receipts;
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
@@ -20,6 +23,18 @@ pub struct Deposit<'info> {
         bump, 
     )]  
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"receipt", mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"receipt_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut, 
         seeds = [signer.key().as_ref()],
@@ -40,18 +55,30 @@ pub struct Deposit<'info> {
 
 // 1. CPI transfer from user's token account to bank's token account
 // 2. Calculate new shares to be added to the bank
-// 3. Update user's deposited amount and total collateral value
+// 3. Update user's deposited shares
 // 4. Update bank's total deposits and total deposit shares
 // 5. Update users health factor ??
 // 函数执行流程总结
 // 代币转账：通过 CPI 将用户代币安全转移到银行账户
 // 份额计算：根据存款比例计算用户应得的份额
-// 用户状态更新：根据代币类型更新用户的存款记录
+// 用户状态更新：更新用户在该银行的头寸
 // 银行状态更新：更新银行的全局存款和份额数据
 // 时间戳更新：记录最后操作时间
 // 这个函数使用份额系统来跟踪用户存款，允许在不频繁更新每个用户余额的情况下处理利息累积。所有算术运算都使用
 // checked_ 前缀来防止溢出错误，体现了 Solana 程序开发的安全最佳实践。
 pub fn process_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    // ========== 步骤 0: 计息 ==========
+    // 先把利息计入银行总额，保证后续份额换算使用最新的兑换比例
+    ctx.accounts.bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+    ctx.accounts.bank.require_active()?;
+
+    // 存款上限（0 表示不限制）
+    let bank = &ctx.accounts.bank;
+    if bank.deposit_cap > 0 {
+        let total_deposits = bank.total_deposits.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        require!(total_deposits <= bank.deposit_cap, ErrorCode::DepositCapExceeded);
+    }
+
     // ========== 步骤 1: 执行代币转账 ==========
     // 设置跨程序调用(CPI)的账户结构，用于将代币从用户账户转移到银行账户
     let transfer_cpi_accounts = TransferChecked {
@@ -71,9 +98,9 @@ pub fn process_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
 
     // ========== 步骤 2: 计算用户应获得的份额 ==========
-    // 获取银行账户的可变引用
+    // 获取银行账户的引用
     // calculate new shares to be added to the bank
-    let bank = &mut ctx.accounts.bank;
+    let bank = &ctx.accounts.bank;
 
     // Note: The checked_ prefix in Rust is used to perform operations safely by checking for potential 
     // arithmetic overflow or other errors that could occur during the computation. If such an error occurs, 
@@ -81,38 +108,29 @@ pub fn process_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     // 注释：checked_ 前缀用于安全执行算术运算，检查潜在的溢出错误
     // 如果发生错误，这些方法返回 None 而不是引起 panic
 
-    // 检查是否为银行的首次存款
-    if bank.total_deposits == 0 {
-        // 首次存款：直接设置总存款和总份额为存款金额
-        bank.total_deposits = amount;
-        bank.total_deposit_shares = amount;
-    }
-    // 计算存款比例：当前存款金额 / 银行总存款
-    let deposit_ratio = amount.checked_div(bank.total_deposits).unwrap();
-    // 计算用户应获得的份额：银行总份额 * 存款比例
-    let users_shares = bank.total_deposit_shares.checked_mul(deposit_ratio).unwrap();
+    // 份额 = 存款金额 × 总份额 / 总存款（u128 计算，向下取整，对池子有利）
+    // 首次存款时按 1:1 发行份额
+    let users_shares = amount_to_shares_down(amount, bank.total_deposit_shares, bank.total_deposits)?;
+    // 金额太小换不到份额时拒绝，避免用户白白捐给池子
+    require!(users_shares > 0, ErrorCode::ZeroShares);
 
+    // 按新增份额铸造存款凭证，保管在凭证金库中（见 receipt.rs）
+    mint_receipts(
+        &ctx.accounts.token_program,
+        &ctx.accounts.receipt_mint,
+        &ctx.accounts.receipt_vault,
+        &ctx.accounts.bank,
+        ctx.bumps.bank,
+        users_shares,
+    )?;
+    let bank = &mut ctx.accounts.bank;
 
     // ========== 步骤 3: 更新用户账户状态 ==========
     // 获取用户账户的可变引用
     let user = &mut ctx.accounts.user_account;
 
-    // 根据代币类型更新用户的存款记录
-    match ctx.accounts.mint.to_account_info().key() {
-        // 如果是 USDC 代币
-        key if key == user.usdc_address => {
-            user.deposited_usdc += amount; // 增加 USDC 存款金额
-            user.deposited_usdc_shares += users_shares;  // 增加 USDC 存款份额
-        },
-        // 如果是其他代币（默认为 SOL）
-        _ => {
-            user.deposited_sol += amount; // 增加 SOL 存款金额
-            user.deposited_sol_shares += users_shares; // 增加 SOL 存款份额
-        }
-    }
-
-    // 注释：上述 match 语句可以轻松添加新分支来支持更多资产类型
-    // The above match statement can easily have new branches added when additional assets are added to the protocol
+    // 更新用户在该银行的头寸（不存在则新建），隔离资产的限制见 User::add_deposit
+    user.add_deposit(&bank.key(), bank.isolated, users_shares)?;
 
     // ========== 步骤 4: 更新银行全局状态 ==========
     // 更新银行的总存款和总份额
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ErrorCode;
+use crate::health::value_positions;
+use crate::constants::MIN_HEALTH_FACTOR;
+use crate::math::health_factor;
+
+#[derive(Accounts)]
+pub struct SetEMode<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref()],
+        bump,
+    )]
+    pub user_account: Account<'info, User>,
+}
+
+// 用户选择 e-mode 分组（0 表示退出）
+// 进入分组后，同组资产按银行的 emode_max_ltv / emode_liquidation_threshold 估值，
+// 并且只能借出同组资产，因此已有的借款必须全部属于该分组。
+// 切换后通过 remaining_accounts 在所有头寸上重新计算健康因子，不足 1 则拒绝。
+pub fn process_set_emode(ctx: Context<SetEMode>, emode_group: u8) -> Result<()> {
+    let user = &mut ctx.accounts.user_account;
+    user.emode_group = emode_group;
+
+    let values = value_positions(user, ctx.remaining_accounts, &[])?;
+    if emode_group != 0 {
+        require!(
+            user.positions
+                .iter()
+                .zip(values.iter())
+                .all(|(position, value)| position.borrowed_shares == 0 || value.emode_group == emode_group),
+            ErrorCode::EModeMismatch
+        );
+    }
+    user.health_factor = health_factor(&values);
+    require!(user.health_factor >= MIN_HEALTH_FACTOR, ErrorCode::UnderCollateralized);
+
+    user.last_updated = Clock::get()?.unix_timestamp;
+    Ok(())
+}
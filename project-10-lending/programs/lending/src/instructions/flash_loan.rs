@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::Discriminator;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{ self, Mint, TokenAccount, TokenInterface, TransferChecked };
+use crate::state::*;
+use crate::error::ErrorCode;
+use crate::constants::BPS_DENOMINATOR;
+use crate::math::mul_div;
+
+// FlashBorrow 和 FlashRepay 的账户顺序相同，bank 都排在第 3 位，
+// 指令自省时用它确认两条指令操作的是同一个银行
+const BANK_ACCOUNT_INDEX: usize = 2;
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump,
+    )]
+    pub bank: Account<'info, Bank>,
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: instructions sysvar, used to inspect the other instructions in this transaction
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// 闪电贷：在同一笔交易内从银行金库借出代币，交易结束前必须连本带手续费还回。
+// 1. flash_borrow 通过指令 sysvar 查找本交易中排在后面的 flash_repay，
+//    要求它指向本条 flash_borrow（borrow_instruction_index）、金额相同、银行相同
+// 2. flash_repay 反向校验 borrow_instruction_index 处确实是对应的 flash_borrow
+// 3. 两条指令都必须是交易的顶层指令，不能通过 CPI 调用，否则无法保证还款指令一定执行
+// 4. 手续费计入 total_deposits，份额数量不变，由所有存款人按份额分享
+// 闪电贷不改变 total_borrowed，因此不影响利用率和利率
+
+pub fn process_flash_borrow(ctx: Context<FlashLoan>, amount: u64) -> Result<()> {
+    ctx.accounts.bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+    ctx.accounts.bank.require_active()?;
+    require!(amount > 0, ErrorCode::InvalidFlashLoan);
+
+    // 在当前指令之后查找与之配对的 flash_repay
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_index = load_current_index_checked(&instructions)?;
+    require_top_level(&instructions, current_index)?;
+
+    let bank_key = ctx.accounts.bank.key();
+    let mut index = current_index as usize + 1;
+    let repaid = loop {
+        let Ok(ix) = load_instruction_at_checked(index, &instructions) else {
+            break false;
+        };
+        if let Some(repay) = decode::<crate::instruction::FlashRepay>(&ix) {
+            if repay.borrow_instruction_index == current_index
+                && repay.amount == amount
+                && ix.accounts.get(BANK_ACCOUNT_INDEX).map(|a| a.pubkey) == Some(bank_key)
+            {
+                break true;
+            }
+        }
+        index += 1;
+    };
+    require!(repaid, ErrorCode::FlashLoanNotRepaid);
+
+    // 从银行金库转给用户
+    let transfer_cpi_accounts = TransferChecked {
+        from: ctx.accounts.bank_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.bank_token_account.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[
+            b"treasury",
+            mint_key.as_ref(),
+            &[ctx.bumps.bank_token_account],
+        ],
+    ];
+    let cpi_ctx = CpiContext::new(cpi_program, transfer_cpi_accounts).with_signer(signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    Ok(())
+}
+
+pub fn process_flash_repay(ctx: Context<FlashLoan>, amount: u64, borrow_instruction_index: u16) -> Result<()> {
+    // 确认 borrow_instruction_index 处是本银行、同金额的 flash_borrow，且排在当前指令之前
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_index = load_current_index_checked(&instructions)?;
+    require_top_level(&instructions, current_index)?;
+    require!(borrow_instruction_index < current_index, ErrorCode::InvalidFlashLoan);
+
+    let borrow_ix = load_instruction_at_checked(borrow_instruction_index as usize, &instructions)?;
+    let borrow = decode::<crate::instruction::FlashBorrow>(&borrow_ix).ok_or(ErrorCode::InvalidFlashLoan)?;
+    require!(borrow.amount == amount, ErrorCode::InvalidFlashLoan);
+    require!(
+        borrow_ix.accounts.get(BANK_ACCOUNT_INDEX).map(|a| a.pubkey) == Some(ctx.accounts.bank.key()),
+        ErrorCode::InvalidFlashLoan
+    );
+
+    // 手续费向上取整
+    let fee = mul_div(amount, ctx.accounts.bank.flash_loan_fee, BPS_DENOMINATOR as u64, true)?;
+    let total = amount.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+
+    // 用户连本带手续费还给银行金库
+    let transfer_cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.bank_token_account.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, transfer_cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, total, ctx.accounts.mint.decimals)?;
+
+    // 手续费归存款人：总存款增加，存款份额的兑换比例随之上涨
+    let bank = &mut ctx.accounts.bank;
+    bank.total_deposits = bank.total_deposits.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
+
+// 当前指令必须由本程序直接执行（顶层指令），通过 CPI 调用时 sysvar 中记录的是外层程序
+fn require_top_level(instructions: &AccountInfo, current_index: u16) -> Result<()> {
+    let current = load_instruction_at_checked(current_index as usize, instructions)?;
+    require_keys_eq!(current.program_id, crate::ID, ErrorCode::InvalidFlashLoan);
+    Ok(())
+}
+
+// 按 Anchor 指令鉴别器解析本程序的指令参数，不是目标指令时返回 None
+fn decode<T: Discriminator + AnchorDeserialize>(ix: &Instruction) -> Option<T> {
+    if ix.program_id != crate::ID || ix.data.len() < 8 || ix.data[..8] != T::DISCRIMINATOR {
+        return None;
+    }
+    T::try_from_slice(&ix.data[8..]).ok()
+}
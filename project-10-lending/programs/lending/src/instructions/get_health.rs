@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ErrorCode;
+use crate::health::{next_bank_and_price, position_value};
+use crate::math::{
+    amount_for_value, borrowable_collateral, health_factor, max_additional_borrow_value, max_borrow_within_limits,
+    total_debt, weighted_collateral,
+};
+use crate::oracle::OraclePrice;
+
+#[derive(Accounts)]
+pub struct GetHealth<'info> {
+    pub user_account: Account<'info, User>,
+}
+
+/// 单个头寸的估值（美元，WAD）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PositionHealth {
+    pub bank: Pubkey,
+    /// 存款价值，按 price − conf 计算
+    pub collateral_value: u128,
+    /// 借款价值，按 price + conf 计算
+    pub debt_value: u128,
+    /// 当前还能从该银行借出的最大数量（代币最小单位）
+    pub max_additional_borrow: u64,
+}
+
+/// 用户没有头寸的银行还能借出的最大数量（代币最小单位）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AssetBorrowLimit {
+    pub bank: Pubkey,
+    pub max_additional_borrow: u64,
+}
+
+/// get_health 的返回值，金额均为 WAD 美元
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct HealthReport {
+    pub positions: Vec<PositionHealth>,
+    /// Σ 抵押品价值 × 清算阈值
+    pub weighted_collateral: u128,
+    /// Σ 抵押品价值 × max_ltv
+    pub borrowable_collateral: u128,
+    pub total_debt: u128,
+    /// 与链上检查相同的健康因子（基点定点数，10000 = 1.0），没有借款时为 u64::MAX
+    pub health_factor: u64,
+    pub other_assets: Vec<AssetBorrowLimit>,
+}
+
+// 只读查询：按当前价格计算用户的健康状况，通过 return data 返回 HealthReport，不修改任何账户。
+// remaining_accounts 先按头寸顺序传入每个银行的账户组（与 borrow 等指令相同，见 health.rs），
+// 之后可以再追加任意数量的账户组，查询用户在这些银行还能借出多少。
+// 银行按当前时间计息后再估值（只影响内存中的副本）。
+// return data 最多 1024 字节，8 个头寸之外大约还能容纳 9 个额外的银行。
+pub fn process_get_health(ctx: Context<GetHealth>) -> Result<HealthReport> {
+    let user = &ctx.accounts.user_account;
+    let mut accounts = ctx.remaining_accounts;
+    let clock = Clock::get()?;
+
+    let mut values = Vec::with_capacity(user.positions.len());
+    let mut position_banks = Vec::with_capacity(user.positions.len());
+    for position in user.positions.iter() {
+        let (key, bank, price) = next_bank_and_price(&mut accounts, &[], &clock)?;
+        require_keys_eq!(key, position.bank, ErrorCode::InvalidRemainingAccounts);
+        values.push(position_value(user, position, &bank, &price)?);
+        position_banks.push((key, bank, price));
+    }
+    let mut other_banks = Vec::new();
+    while !accounts.is_empty() {
+        other_banks.push(next_bank_and_price(&mut accounts, &[], &clock)?);
+    }
+
+    // 隔离模式下隔离资产银行一定在头寸中，用它的债务上限限制可借数量
+    let isolated_bank = position_banks
+        .iter()
+        .find(|(key, _, _)| user.is_isolated() && *key == user.isolated_bank)
+        .map(|(_, bank, _)| bank);
+    let headroom = max_additional_borrow_value(&values);
+
+    let mut positions = Vec::with_capacity(values.len());
+    for ((key, bank, price), value) in position_banks.iter().zip(values.iter()) {
+        positions.push(PositionHealth {
+            bank: *key,
+            collateral_value: value.collateral_value,
+            debt_value: value.debt_value,
+            max_additional_borrow: max_borrow_amount(user, bank, price, isolated_bank, headroom)?,
+        });
+    }
+    let mut other_assets = Vec::with_capacity(other_banks.len());
+    for (key, bank, price) in other_banks.iter() {
+        other_assets.push(AssetBorrowLimit {
+            bank: *key,
+            max_additional_borrow: max_borrow_amount(user, bank, price, isolated_bank, headroom)?,
+        });
+    }
+
+    Ok(HealthReport {
+        positions,
+        weighted_collateral: weighted_collateral(&values),
+        borrowable_collateral: borrowable_collateral(&values),
+        total_debt: total_debt(&values),
+        health_factor: health_factor(&values),
+        other_assets,
+    })
+}
+
+// 与 borrow 使用同一组 BorrowLimits：剩余的 max_ltv 额度按债务价格换算为数量，
+// 再受银行可用流动性和 math::max_borrow_within_limits（暂停、借款上限、e-mode 分组、隔离模式）的限制
+fn max_borrow_amount(
+    user: &User,
+    bank: &Bank,
+    price: &OraclePrice,
+    isolated_bank: Option<&Bank>,
+    headroom: u128,
+) -> Result<u64> {
+    let limits = bank.borrow_limits(user.emode_group, isolated_bank);
+    let mut amount = max_borrow_within_limits(&limits, bank.mint_decimals)?;
+
+    // 额度换算超出 u64 时视为不受限，流动性上限一定更小
+    amount = amount.min(amount_for_value(headroom, bank.mint_decimals, price.debt_price()).unwrap_or(u64::MAX));
+    amount = amount.min(bank.total_deposits.saturating_sub(bank.total_borrowed));
+    Ok(amount)
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{ self, Mint, TokenAccount, TokenInterface, TransferChecked };
+use crate::state::*;
+use crate::error::ErrorCode;
+use crate::math::isolation_debt_units;
+
+#[derive(Accounts)]
+pub struct CollectReserves<'info> {
+    pub signer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump,
+    )]
+    pub bank: Account<'info, Bank>,
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"insurance", mint.key().as_ref()],
+        bump,
+    )]
+    pub insurance_fund_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SettleBadDebt<'info> {
+    pub signer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump,
+    )]
+    pub bank: Account<'info, Bank>,
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"insurance", mint.key().as_ref()],
+        bump,
+    )]
+    pub insurance_fund_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the borrower whose debt is written off, only used to derive their user account
+    pub borrower: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [borrower.key().as_ref()],
+        bump,
+    )]
+    pub user_account: Account<'info, User>,
+    /// 借款人处于隔离模式且核销的是隔离模式可借资产时传入其隔离资产银行；否则传 None
+    #[account(mut)]
+    pub isolated_bank: Option<Account<'info, Bank>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// 把已计提的储备金从银行金库转入保险基金，任何人都可以调用
+// 金库中可用的流动性不足时只转出一部分，剩余的留到下次
+pub fn process_collect_reserves(ctx: Context<CollectReserves>) -> Result<()> {
+    ctx.accounts.bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+
+    let amount = ctx.accounts.bank.pending_reserves.min(ctx.accounts.bank_token_account.amount);
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let transfer_cpi_accounts = TransferChecked {
+        from: ctx.accounts.bank_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.insurance_fund_token_account.to_account_info(),
+        authority: ctx.accounts.bank_token_account.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[
+            b"treasury",
+            mint_key.as_ref(),
+            &[ctx.bumps.bank_token_account],
+        ],
+    ];
+    let cpi_ctx = CpiContext::new(cpi_program, transfer_cpi_accounts).with_signer(signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.bank.pending_reserves -= amount;
+    Ok(())
+}
+
+// 坏账核销：借款人的存款已经全部清零（抵押品被清算完）但仍有借款时，任何人都可以调用
+// 1. 先用保险基金偿还，保险基金的代币转回银行金库
+// 2. 保险基金不足的部分从总存款中扣除，存款份额的兑换比例随之下降，由所有存款人分摊
+// 3. 清零借款人在该银行的借款份额，并从银行的总借款中移除这笔债务；隔离模式下同时减少隔离资产的累计债务
+pub fn process_settle_bad_debt(ctx: Context<SettleBadDebt>) -> Result<()> {
+    ctx.accounts.bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+
+    let bank_key = ctx.accounts.bank.key();
+    let user = &ctx.accounts.user_account;
+    require!(
+        user.positions.iter().all(|p| p.deposited_shares == 0),
+        ErrorCode::BorrowerHasCollateral
+    );
+    let borrowed_shares = user
+        .position(&bank_key)
+        .ok_or(ErrorCode::PositionNotFound)?
+        .borrowed_shares;
+    let debt = ctx.accounts.bank.debt_amount(borrowed_shares)?;
+
+    // 先用保险基金覆盖
+    let covered = debt.min(ctx.accounts.insurance_fund_token_account.amount);
+    if covered > 0 {
+        let transfer_cpi_accounts = TransferChecked {
+            from: ctx.accounts.insurance_fund_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.bank_token_account.to_account_info(),
+            authority: ctx.accounts.insurance_fund_token_account.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[
+            &[
+                b"insurance",
+                mint_key.as_ref(),
+                &[ctx.bumps.insurance_fund_token_account],
+            ],
+        ];
+        let cpi_ctx = CpiContext::new(cpi_program, transfer_cpi_accounts).with_signer(signer_seeds);
+        token_interface::transfer_checked(cpi_ctx, covered, ctx.accounts.mint.decimals)?;
+    }
+
+    // 隔离模式：核销的债务同样从隔离资产的累计债务中移除
+    let user = &ctx.accounts.user_account;
+    if user.is_isolated() && ctx.accounts.bank.borrowable_in_isolation {
+        let units = isolation_debt_units(debt, ctx.accounts.bank.mint_decimals, false)?;
+        let isolated_bank_key = user.isolated_bank;
+        let isolated_bank = ctx.accounts.isolated_bank
+            .as_mut()
+            .filter(|b| b.key() == isolated_bank_key)
+            .ok_or(ErrorCode::IsolatedBankMismatch)?;
+        isolated_bank.isolation_debt = isolated_bank.isolation_debt.saturating_sub(units);
+    }
+
+    // 剩余部分由存款人分摊：总存款减少，份额不变
+    // 总存款被扣到 0 而仍有存款份额时，银行拒绝新的存款（DepletedPool），避免新资金被旧份额分走
+    let bank = &mut ctx.accounts.bank;
+    let shortfall = debt - covered;
+    bank.total_deposits = bank.total_deposits.saturating_sub(shortfall);
+    bank.total_borrowed = bank.total_borrowed.saturating_sub(debt);
+    bank.total_borrowed_shares -= borrowed_shares;
+
+    let user = &mut ctx.accounts.user_account;
+    user.position_mut(&bank_key)?.borrowed_shares = 0;
+    user.remove_empty_positions();
+    user.last_updated = Clock::get()?.unix_timestamp;
+
+    msg!("Settled bad debt {}: {} covered by insurance, {} socialized", debt, covered, shortfall);
+    Ok(())
+}
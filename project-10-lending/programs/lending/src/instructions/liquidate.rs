@@ -1,16 +1,20 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{ self, Mint, TokenAccount, TokenInterface, TransferChecked };
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
-use crate::constants::{MAXIMUM_AGE, SOL_USD_FEED_ID, USDC_USD_FEED_ID};
 use crate::state::*;
 use crate::error::ErrorCode;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use crate::constants::{BPS_DENOMINATOR, MIN_HEALTH_FACTOR};
+use crate::health::value_positions;
+use crate::math::health_factor;
+use crate::math::{amount_to_shares_down, amount_to_shares_up, collateral_for_repay, isolation_debt_units, mul_div};
+use crate::oracle::load_price;
+use super::receipt::burn_receipts;
 
 #[derive(Accounts)]
 pub struct Liquidate<'info> {
     #[account(mut)]
     pub liquidator: Signer<'info>,
-    pub price_update: Account<'info, PriceUpdateV2>,
     pub collateral_mint: InterfaceAccount<'info, Mint>,
     pub borrowed_mint: InterfaceAccount<'info, Mint>,
     #[account(
@@ -25,6 +29,18 @@ pub struct Liquidate<'info> {
         bump, 
     )]  
     pub collateral_bank_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"receipt", collateral_mint.key().as_ref()],
+        bump,
+    )]
+    pub collateral_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"receipt_vault", collateral_mint.key().as_ref()],
+        bump,
+    )]
+    pub collateral_receipt_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut, 
         seeds = [borrowed_mint.key().as_ref()],
@@ -37,12 +53,20 @@ pub struct Liquidate<'info> {
         bump, 
     )]  
     pub borrowed_bank_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the borrower being liquidated, only used to derive their user account
+    pub borrower: UncheckedAccount<'info>,
     #[account(
         mut, 
-        seeds = [liquidator.key().as_ref()],
+        seeds = [borrower.key().as_ref()],
         bump,
     )]  
     pub user_account: Account<'info, User>,
+    pub collateral_price_update: Account<'info, PriceUpdateV2>,
+    pub borrowed_price_update: Account<'info, PriceUpdateV2>,
+    /// 抵押品银行备用价格源的价格更新，只在主价格源过期或无效时使用；否则传 None
+    pub collateral_fallback_price_update: Option<Box<Account<'info, PriceUpdateV2>>>,
+    /// 借款银行备用价格源的价格更新，只在主价格源过期或无效时使用；否则传 None
+    pub borrowed_fallback_price_update: Option<Box<Account<'info, PriceUpdateV2>>>,
     #[account( 
         init_if_needed, 
         payer = liquidator,
@@ -64,57 +88,123 @@ pub struct Liquidate<'info> {
     pub system_program: Program<'info, System>,
 }
 
-// 1. Check if user is undercollateralized
-// 2. Calculate liquidation amount
-// 3. Make a CPI transfer from the user's token account to the bank's token account
-// 4. Update the user and bank states
-// 5. Handle fees and rewards
-// 1. 检查用户是否抵押不足
-// 2. 计算清算金额
-// 3. 从用户的代币账户向银行的代币账户进行 CPI 转账
-// 4. 更新用户和银行状态
-// 5. 处理费用和奖励
+// 1. Check if the borrower is undercollateralized
+// 2. Calculate the repay amount in borrowed units: min(debt × close factor, requested)
+// 3. Convert it to collateral plus bonus using both oracle prices and mint decimals
+// 4. Liquidator repays into the borrowed bank, collateral bank pays the liquidator
+// 5. Update the borrower's positions and both banks' totals
+// 1. 检查借款人是否抵押不足
+// 2. 计算偿还数量（借款资产单位）：min(债务 × 清算关闭因子, 请求数量)
+// 3. 用两个预言机价格和铸币小数位数换算为抵押品数量，并加上清算奖励
+// 4. 清算人向借款银行还款，抵押品银行向清算人支付抵押品
+// 5. 更新借款人的头寸和两个银行的总额
 /*清算机制核心逻辑
-健康因子检查： liquidate.rs:87-94
-价格获取： liquidate.rs:79-83
-清算金额计算： liquidate.rs:96
-双向代币转账： liquidate.rs:100-133
+健康因子检查：通过 remaining_accounts 在借款人所有头寸上计算
+价格获取：collateral_price_update / borrowed_price_update，主价格源不可用时使用对应的 fallback 价格更新
+清算金额计算：math::collateral_for_repay
+双向代币转账，然后更新账本
 */
 
-pub fn process_liquidate(ctx: Context<Liquidate>) -> Result<()> {
-    // 获取抵押品银行和用户账户的可变引用
-    let collateral_bank = &mut ctx.accounts.collateral_bank;
-    let user = &mut ctx.accounts.user_account;
+pub fn process_liquidate(ctx: Context<Liquidate>, amount: u64) -> Result<()> {
+    require_keys_neq!(
+        ctx.accounts.collateral_bank.key(),
+        ctx.accounts.borrowed_bank.key(),
+        ErrorCode::SameCollateralAndBorrowBank
+    );
 
-    // 获取价格更新账户的可变引用
-    let price_update = &mut ctx.accounts.price_update;
+    // 先对抵押品银行和借款银行计息
+    let clock = Clock::get()?;
+    ctx.accounts.collateral_bank.accrue_interest(clock.unix_timestamp)?;
+    ctx.accounts.borrowed_bank.accrue_interest(clock.unix_timestamp)?;
 
-    // 获取 SOL 和 USDC 的价格预言机 Feed ID
-    let sol_feed_id = get_feed_id_from_hex(SOL_USD_FEED_ID)?; 
-    let usdc_feed_id = get_feed_id_from_hex(USDC_USD_FEED_ID)?;
+    let collateral_bank_key = ctx.accounts.collateral_bank.key();
+    let borrowed_bank_key = ctx.accounts.borrowed_bank.key();
+    let collateral_bank = &ctx.accounts.collateral_bank;
+    let borrowed_bank = &ctx.accounts.borrowed_bank;
+    let user = &ctx.accounts.user_account;
 
-    // 从 Pyth 预言机获取 SOL 和 USDC 的实时价格
-    let sol_price = price_update.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE, &sol_feed_id)?;
-    let usdc_price = price_update.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE, &usdc_feed_id)?;
+    // 通过 remaining_accounts 中每个银行的账户组为借款人的所有头寸估值
+    let values = value_positions(
+        user,
+        ctx.remaining_accounts,
+        &[
+            (collateral_bank_key, &**collateral_bank),
+            (borrowed_bank_key, &**borrowed_bank),
+        ],
+    )?;
 
-    // Note: For simplicity, interest is not being included in these calculations.
-    // 注意：为了简化，这些计算中不包括利息
+    // 计算健康因子 = (Σ 抵押品 × 清算阈值) / 总借贷
+    let health = health_factor(&values);
 
-    // 计算用户的总抵押品价值（SOL 价值 + USDC 价值）
-    let total_collateral = (sol_price.price as u64 * user.deposited_sol) + (usdc_price.price as u64 * user.deposited_usdc);
-    // 计算用户的总借贷价值（SOL 借贷 + USDC 借贷）
-    let total_borrowed = (sol_price.price as u64 * user.borrowed_sol) + (usdc_price.price as u64 * user.borrowed_usdc);
+    // 如果健康因子 >= 1.0，说明抵押充足，不能被清算
+    if health >= MIN_HEALTH_FACTOR {
+        return Err(ErrorCode::NotUndercollateralized.into());
+    }
 
-    // 计算健康因子 = (总抵押品 × 清算阈值) / 总借贷
-    let health_factor = (total_collateral * collateral_bank.liquidation_threshold)/total_borrowed;
+    // 借款人在两个银行中的头寸
+    let borrowed_shares = user
+        .position(&borrowed_bank_key)
+        .ok_or(ErrorCode::PositionNotFound)?
+        .borrowed_shares;
+    let deposited_shares = user
+        .position(&collateral_bank_key)
+        .ok_or(ErrorCode::PositionNotFound)?
+        .deposited_shares;
+    let debt = borrowed_bank.debt_amount(borrowed_shares)?;
+    let deposited = collateral_bank.deposit_amount(deposited_shares)?;
 
-    // 如果健康因子 >= 1，说明抵押充足，不能被清算
-    if health_factor >= 1 {
-        return Err(ErrorCode::NotUndercollateralized.into());
+    // 偿还数量 = min(债务 × 清算关闭因子, 请求数量)，以借款资产的最小单位计
+    let max_repay = mul_div(
+        debt,
+        borrowed_bank.liquidation_close_factor,
+        BPS_DENOMINATOR as u64,
+        false,
+    )?;
+    let mut repay = max_repay.min(amount);
+    require!(repay > 0, ErrorCode::InvalidLiquidationAmount);
+
+    // 用两个资产的价格和小数位数把偿还数量换算为抵押品数量，并加上清算奖励
+    // 健康因子检查已经使用了保守价格，换算时使用中间价，借款人不会因为置信区间多损失抵押品
+    let borrowed_price = load_price(
+        borrowed_bank,
+        &ctx.accounts.borrowed_price_update,
+        ctx.accounts.borrowed_fallback_price_update.as_deref().map(|update| &**update),
+        &clock,
+    )?;
+    let collateral_price = load_price(
+        collateral_bank,
+        &ctx.accounts.collateral_price_update,
+        ctx.accounts.collateral_fallback_price_update.as_deref().map(|update| &**update),
+        &clock,
+    )?;
+    let mut seized = collateral_for_repay(
+        repay,
+        borrowed_bank.mint_decimals,
+        borrowed_price.price,
+        collateral_bank.mint_decimals,
+        collateral_price.price,
+        collateral_bank.liquidation_bonus,
+    )?;
+
+    // 借款人的抵押品不够支付时，只拿走全部抵押品，并按比例减少偿还数量（向上取整，对协议有利）
+    if seized > deposited {
+        repay = mul_div(repay, deposited, seized, true)?;
+        seized = deposited;
     }
+    require!(repay > 0 && seized > 0, ErrorCode::InvalidLiquidationAmount);
 
-    // 计算清算金额 = 总借贷 × 清算关闭因子
-    let liquidation_amount = total_borrowed * collateral_bank.liquidation_close_factor;
+    // 需要销毁的份额：还清全部债务时销毁所有借款份额；拿走全部抵押品时销毁所有存款份额
+    let borrowed_shares_to_burn = if repay == debt {
+        borrowed_shares
+    } else {
+        amount_to_shares_down(repay, borrowed_bank.total_borrowed_shares, borrowed_bank.total_borrowed)?
+    };
+    let deposited_shares_to_burn = if seized == deposited {
+        deposited_shares
+    } else {
+        amount_to_shares_up(seized, collateral_bank.total_deposit_shares, collateral_bank.total_deposits)?
+            .min(deposited_shares)
+    };
 
     // liquidator pays back the borrowed amount back to the bank
     // 清算人偿还借贷金额给银行
@@ -133,12 +223,10 @@ pub fn process_liquidate(ctx: Context<Liquidate>) -> Result<()> {
     let decimals = ctx.accounts.borrowed_mint.decimals;
 
     // 执行代币转账，清算人向银行偿还借贷
-    token_interface::transfer_checked(cpi_ctx_to_bank, liquidation_amount, decimals)?;
+    token_interface::transfer_checked(cpi_ctx_to_bank, repay, decimals)?;
 
-    // Transfer liquidation value and bonus to liquidator
-    // 将清算价值和奖励转给清算人
-    // 计算清算奖励 = (清算金额 × 清算奖励比例) + 清算金额
-    let liquidation_bonus = (liquidation_amount * collateral_bank.liquidation_bonus) + liquidation_amount;
+    // Transfer seized collateral (value repaid plus bonus) to liquidator
+    // 将抵押品（偿还价值 + 清算奖励）转给清算人
 
     // 设置从银行向清算人转账抵押品的 CPI 账户结构
     let transfer_to_liquidator = TransferChecked {
@@ -161,7 +249,43 @@ pub fn process_liquidate(ctx: Context<Liquidate>) -> Result<()> {
     let cpi_ctx_to_liquidator = CpiContext::new(cpi_program.clone(), transfer_to_liquidator).with_signer(signer_seeds);
     let collateral_decimals = ctx.accounts.collateral_mint.decimals;
     // 执行代币转账，将抵押品和奖励转给清算人
-    token_interface::transfer_checked(cpi_ctx_to_liquidator, liquidation_bonus, collateral_decimals)?;
+    token_interface::transfer_checked(cpi_ctx_to_liquidator, seized, collateral_decimals)?;
+
+    // 销毁被拿走的抵押品份额对应的存款凭证
+    burn_receipts(
+        &ctx.accounts.token_program,
+        &ctx.accounts.collateral_receipt_mint,
+        &ctx.accounts.collateral_receipt_vault,
+        &ctx.accounts.collateral_bank,
+        ctx.bumps.collateral_bank,
+        deposited_shares_to_burn,
+    )?;
+
+    // 隔离模式下，偿还的隔离模式可借资产同时从隔离资产的累计债务中扣除
+    let isolated_repay = ctx.accounts.user_account.isolated_bank == collateral_bank_key
+        && ctx.accounts.borrowed_bank.borrowable_in_isolation;
+    if isolated_repay {
+        let units = isolation_debt_units(repay, ctx.accounts.borrowed_bank.mint_decimals, false)?;
+        let collateral_bank = &mut ctx.accounts.collateral_bank;
+        collateral_bank.isolation_debt = collateral_bank.isolation_debt.saturating_sub(units);
+    }
+
+    // 更新借款人的头寸
+    let user = &mut ctx.accounts.user_account;
+    user.position_mut(&borrowed_bank_key)?.borrowed_shares -= borrowed_shares_to_burn;
+    user.position_mut(&collateral_bank_key)?.deposited_shares -= deposited_shares_to_burn;
+    user.remove_empty_positions();
+    user.last_updated = clock.unix_timestamp;
+
+    // 更新借款银行的总借贷（债务向上取整，可能比总额多出几个最小单位）
+    let borrowed_bank = &mut ctx.accounts.borrowed_bank;
+    borrowed_bank.total_borrowed = borrowed_bank.total_borrowed.saturating_sub(repay);
+    borrowed_bank.total_borrowed_shares -= borrowed_shares_to_burn;
+
+    // 更新抵押品银行的总存款
+    let collateral_bank = &mut ctx.accounts.collateral_bank;
+    collateral_bank.total_deposits -= seized;
+    collateral_bank.total_deposit_shares -= deposited_shares_to_burn;
 
     Ok(())
-}
\ No newline at end of file
+}
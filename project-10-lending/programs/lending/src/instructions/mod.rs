@@ -9,4 +9,14 @@ pub mod withdraw;
 pub use repay::*;
 pub mod repay;
 pub use liquidate::*;
-pub mod liquidate;
\ No newline at end of file
+pub mod liquidate;
+pub use flash_loan::*;
+pub mod flash_loan;
+pub use emode::*;
+pub mod emode;
+pub use insurance::*;
+pub mod insurance;
+pub use receipt::*;
+pub mod receipt;
+pub use get_health::*;
+pub mod get_health;
\ No newline at end of file
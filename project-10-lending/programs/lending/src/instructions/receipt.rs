@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::state::*;
+use crate::error::ErrorCode;
+use crate::health::value_positions;
+use crate::constants::MIN_HEALTH_FACTOR;
+use crate::math::health_factor;
+
+// 存款凭证（cToken）：每个银行有一个凭证铸币 [b"receipt", mint]，铸币权限是银行 PDA，
+// 1 个凭证最小单位 = 1 份存款份额，可兑换 total_deposits / total_deposit_shares 个代币。
+// 凭证总供应量始终等于 total_deposit_shares：
+//     deposit 按新增份额铸造凭证，withdraw 和清算按销毁的份额销毁凭证。
+// 作为抵押品的份额对应的凭证保管在银行的凭证金库 [b"receipt_vault", mint] 中，
+// 金库余额始终等于所有用户 deposited_shares 之和，因此同一份额不会既在钱包里又被算作抵押品。
+//     unlock_receipts：把头寸中的份额取出为钱包里的凭证，可以自由转账或在其他协议中使用（不再算作抵押品）
+//     lock_receipts：把钱包里的凭证存回金库，计入持有人的头寸作为抵押品（任何持有人都可以）
+
+#[derive(Accounts)]
+pub struct MoveReceipts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump,
+    )]
+    pub bank: Account<'info, Bank>,
+    #[account(
+        seeds = [b"receipt", mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"receipt_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref()],
+        bump,
+    )]
+    pub user_account: Account<'info, User>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program,
+    )]
+    pub user_receipt_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// 把头寸中的 shares 份额取出为钱包里的凭证
+// 与 withdraw 一样，有借款时通过 remaining_accounts 重新计算健康因子
+pub fn process_unlock_receipts(ctx: Context<MoveReceipts>, shares: u64) -> Result<()> {
+    ctx.accounts.bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+    ctx.accounts.bank.require_active()?;
+
+    let bank_key = ctx.accounts.bank.key();
+    let user = &mut ctx.accounts.user_account;
+    let position = user.position_mut(&bank_key)?;
+    require!(shares > 0 && shares <= position.deposited_shares, ErrorCode::InsufficientFunds);
+    position.deposited_shares -= shares;
+
+    if user.positions.iter().any(|p| p.borrowed_shares > 0) {
+        let values = value_positions(user, ctx.remaining_accounts, &[(bank_key, &*ctx.accounts.bank)])?;
+        user.health_factor = health_factor(&values);
+        require!(user.health_factor >= MIN_HEALTH_FACTOR, ErrorCode::UnderCollateralized);
+    }
+    user.remove_empty_positions();
+
+    // 金库 -> 用户钱包，由银行 PDA 签名
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[mint_key.as_ref(), &[ctx.bumps.bank]]];
+    let transfer_cpi_accounts = TransferChecked {
+        from: ctx.accounts.receipt_vault.to_account_info(),
+        mint: ctx.accounts.receipt_mint.to_account_info(),
+        to: ctx.accounts.user_receipt_account.to_account_info(),
+        authority: ctx.accounts.bank.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts)
+        .with_signer(signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, shares, ctx.accounts.receipt_mint.decimals)?;
+
+    Ok(())
+}
+
+// 把钱包里的 shares 个凭证存回金库，计入头寸作为抵押品
+pub fn process_lock_receipts(ctx: Context<MoveReceipts>, shares: u64) -> Result<()> {
+    ctx.accounts.bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+    ctx.accounts.bank.require_active()?;
+    require!(shares > 0, ErrorCode::ZeroShares);
+
+    // 用户钱包 -> 金库
+    let transfer_cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_receipt_account.to_account_info(),
+        mint: ctx.accounts.receipt_mint.to_account_info(),
+        to: ctx.accounts.receipt_vault.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, shares, ctx.accounts.receipt_mint.decimals)?;
+
+    let bank = &ctx.accounts.bank;
+    let user = &mut ctx.accounts.user_account;
+    user.add_deposit(&bank.key(), bank.isolated, shares)?;
+    user.last_updated = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+// 向凭证金库铸造 shares 个凭证，由银行 PDA（铸币权限）签名
+pub fn mint_receipts<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    receipt_mint: &InterfaceAccount<'info, Mint>,
+    receipt_vault: &InterfaceAccount<'info, TokenAccount>,
+    bank: &Account<'info, Bank>,
+    bank_bump: u8,
+    shares: u64,
+) -> Result<()> {
+    let mint_key = bank.mint_address;
+    let signer_seeds: &[&[&[u8]]] = &[&[mint_key.as_ref(), &[bank_bump]]];
+    let cpi_accounts = MintTo {
+        mint: receipt_mint.to_account_info(),
+        to: receipt_vault.to_account_info(),
+        authority: bank.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts).with_signer(signer_seeds);
+    token_interface::mint_to(cpi_ctx, shares)
+}
+
+// 从凭证金库销毁 shares 个凭证，由银行 PDA（金库所有者）签名
+pub fn burn_receipts<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    receipt_mint: &InterfaceAccount<'info, Mint>,
+    receipt_vault: &InterfaceAccount<'info, TokenAccount>,
+    bank: &Account<'info, Bank>,
+    bank_bump: u8,
+    shares: u64,
+) -> Result<()> {
+    let mint_key = bank.mint_address;
+    let signer_seeds: &[&[&[u8]]] = &[&[mint_key.as_ref(), &[bank_bump]]];
+    let cpi_accounts = Burn {
+        mint: receipt_mint.to_account_info(),
+        from: receipt_vault.to_account_info(),
+        authority: bank.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts).with_signer(signer_seeds);
+    token_interface::burn(cpi_ctx, shares)
+}
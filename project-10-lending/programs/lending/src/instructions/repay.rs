@@ -3,6 +3,7 @@ use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{ self, Mint, TokenAccount, TokenInterface, TransferChecked };
 use crate::state::*;
 use crate::error::ErrorCode;
+use crate::math::{amount_to_shares_down, isolation_debt_units};
 
 #[derive(Accounts)]
 pub struct Repay<'info> {
@@ -35,39 +36,54 @@ pub struct Repay<'info> {
         associated_token::token_program = token_program,
     )]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>, 
+    /// 用户处于隔离模式且偿还的是隔离模式可借资产时传入其隔离资产银行；否则传 None
+    #[account(mut)]
+    pub isolated_bank: Option<Account<'info, Bank>>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-// Repay function just needs to make a CPI transfer from the user's token account into the bank's token account
-// // 还款功能只需要从用户的代币账户向银行的代币账户进行 CPI 转账
+// Repay transfers tokens from the user's token account into the bank's token account and burns borrow shares
+// 还款：从用户的代币账户向银行的代币账户进行 CPI 转账，并按当前兑换比例销毁借款份额
 pub fn process_repay(ctx: Context<Repay>, amount: u64) -> Result<()> {
-    // 获取用户账户的可变引用
-    let user = &mut ctx.accounts.user_account;
-
-    let borrowed_asset; 
-
-    // Note: For simplicity, interest fees are not included in this calculation
-    // 注意：为了简化，此计算中不包括利息费用
-
-    // 根据代币类型确定用户的借贷余额
-    match ctx.accounts.mint.to_account_info().key() {
-        key if key == user.usdc_address => {
-            // 如果是 USDC，获取用户的 USDC 借贷余额
-            borrowed_asset = user.borrowed_usdc;
-        },
-        _ => {
-            // 否则是 SOL，获取用户的 SOL 借贷余额
-            borrowed_asset = user.borrowed_sol;
-        }
-    }
+    // 先计息，保证后续份额换算使用最新的兑换比例
+    ctx.accounts.bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+
+    // 用户当前的债务 = 借款份额 × 总借款 / 总借款份额（向上取整，已包含利息）
+    let bank_key = ctx.accounts.bank.key();
+    let bank = &ctx.accounts.bank;
+    let borrowed_shares = ctx.accounts.user_account
+        .position(&bank_key)
+        .ok_or(ErrorCode::PositionNotFound)?
+        .borrowed_shares;
+    let borrowed_asset = bank.debt_amount(borrowed_shares)?;
 
     // 检查还款金额是否超过借贷余额
     if amount > borrowed_asset {
         return Err(ErrorCode::OverRepay.into());
     }
 
+    // 需要销毁的份额 = 还款金额 × 总借款份额 / 总借款（向下取整，对池子有利）
+    // 还清全部债务时直接销毁所有份额，避免留下无法偿还的零头
+    let users_shares = if amount == borrowed_asset {
+        borrowed_shares
+    } else {
+        amount_to_shares_down(amount, bank.total_borrowed_shares, bank.total_borrowed)?
+    };
+
+    // 隔离模式：还款同时减少隔离资产的累计债务
+    let user = &ctx.accounts.user_account;
+    if user.is_isolated() && bank.borrowable_in_isolation {
+        let units = isolation_debt_units(amount, bank.mint_decimals, false)?;
+        let isolated_bank_key = user.isolated_bank;
+        let isolated_bank = ctx.accounts.isolated_bank
+            .as_mut()
+            .filter(|b| b.key() == isolated_bank_key)
+            .ok_or(ErrorCode::IsolatedBankMismatch)?;
+        isolated_bank.isolation_debt = isolated_bank.isolation_debt.saturating_sub(units);
+    }
+
     // 设置代币转账的 CPI 账户结构
     let transfer_cpi_accounts = TransferChecked {
         from: ctx.accounts.user_token_account.to_account_info(),
@@ -90,36 +106,18 @@ pub fn process_repay(ctx: Context<Repay>, amount: u64) -> Result<()> {
     // 注意：checked_ 前缀在 Rust 中用于安全地执行操作，通过检查计算过程中可能发生的
     // 算术溢出或其他错误。如果发生此类错误，这些方法返回 None 而不是引起 panic。
 
-    // 获取银行账户的可变引用
-    let bank = &mut ctx.accounts.bank;
-
-    // 计算还款比例和用户份额
-    let borrowed_ratio = amount.checked_div(bank.total_borrowed).unwrap();
-    let users_shares = bank.total_borrowed_shares.checked_mul(borrowed_ratio).unwrap();
-
-    // 重新获取用户账户的可变引用
+    // 更新用户在该银行的借款份额
     let user = &mut ctx.accounts.user_account;
-
-    // 根据代币类型更新用户的借贷余额
-    match ctx.accounts.mint.to_account_info().key() {
-        key if key == user.usdc_address => {
-            // 如果是 USDC，减少用户的 USDC 借贷数据
-            user.borrowed_usdc -= amount;
-            user.borrowed_usdc_shares -= users_shares;
-        },
-        _ => {
-            // 如果是 SOL，减少用户的 SOL 借贷数据
-            user.borrowed_sol -= amount;
-            user.borrowed_sol_shares -= users_shares; 
-        }
-    }
-
-    // 在这里添加"更新健康因子"功能
-    // Add in "update health factor" function here
+    let position = user.position_mut(&bank_key)?;
+    position.borrowed_shares -= users_shares;
 
     // 更新银行的总借贷和总借贷份额
-    bank.total_borrowed -= amount; // 减少银行总借贷
+    let bank = &mut ctx.accounts.bank;
+    // 各借款人的债务都向上取整，最后一个还款人可能比总额多出几个最小单位
+    bank.total_borrowed = bank.total_borrowed.saturating_sub(amount); // 减少银行总借贷
     bank.total_borrowed_shares -= users_shares; // 减少银行总借贷份额
 
+    user.remove_empty_positions();
+
     Ok(())
 }
\ No newline at end of file
@@ -3,6 +3,11 @@ use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{ self, Mint, TokenAccount, TokenInterface, TransferChecked };
 use crate::state::*;
 use crate::error::ErrorCode;
+use crate::health::value_positions;
+use crate::constants::MIN_HEALTH_FACTOR;
+use crate::math::health_factor;
+use crate::math::amount_to_shares_up;
+use super::receipt::burn_receipts;
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
@@ -21,6 +26,18 @@ pub struct Withdraw<'info> {
         bump, 
     )]  
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"receipt", mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"receipt_vault", mint.key().as_ref()],
+        bump,
+    )]
+    pub receipt_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut, 
         seeds = [signer.key().as_ref()],
@@ -42,32 +59,29 @@ pub struct Withdraw<'info> {
 
 // 1. CPI transfer from bank's token account to user's token account
 // 2. Calculate new shares to be removed from the bank
-// 3. Update user's deposited amount and total collateral value
+// 3. Burn the user's deposit shares (rounded up)
 // 4. Update bank's total deposits and total deposit shares
-// 5. Update users health factor ??
+// 5. Check the user's health factor across all positions (remaining_accounts)
 // 这个 process_withdraw 方法实现了用户从借贷协议中提取存款的核心逻辑。
 // 方法首先验证用户有足够的存款余额，然后通过 CPI 调用将代币从银行的 PDA 账户转移到用户账户，
 // 最后更新相关的状态数据。该方法使用份额系统来跟踪用户的存款比例，确保利息分配的准确性。
 
 pub fn process_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-    // 获取用户账户的可变引用
-    let user = &mut ctx.accounts.user_account;
+    // 先计息，保证后续份额换算使用最新的兑换比例
+    ctx.accounts.bank.accrue_interest(Clock::get()?.unix_timestamp)?;
+    ctx.accounts.bank.require_active()?;
 
-    let deposited_value;
+    // 需要销毁的份额 = 提取金额 × 总份额 / 总存款（向上取整，对池子有利）
+    let bank_key = ctx.accounts.bank.key();
+    let bank = &ctx.accounts.bank;
+    let shares_to_remove = amount_to_shares_up(amount, bank.total_deposit_shares, bank.total_deposits)?;
+    let deposited_shares = ctx.accounts.user_account
+        .position(&bank_key)
+        .ok_or(ErrorCode::PositionNotFound)?
+        .deposited_shares;
 
-    // 检查要提取的代币类型，确定用户的存款余额
-    // FIXME: Change from if statement to match statement?? Use PDA deserialization to get the mint address??
-    // FIXME: 将 if 语句改为 match 语句？使用 PDA 反序列化获取铸币地址？
-    if ctx.accounts.mint.to_account_info().key() == user.usdc_address {
-        // 如果是 USDC，获取用户的 USDC 存款余额
-        deposited_value = user.deposited_usdc;
-    } else {
-        // 否则是 SOL，获取用户的 SOL 存款余额
-        deposited_value = user.deposited_sol;
-    }
-
-    // 检查用户是否有足够的存款余额进行提取
-    if amount > deposited_value {
+    // 检查用户是否有足够的存款份额进行提取
+    if shares_to_remove > deposited_shares {
         return Err(ErrorCode::InsufficientFunds.into());
     }
 
@@ -99,26 +113,35 @@ pub fn process_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     // 执行代币转账，从银行转给用户
     token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
 
-    // 获取银行账户的可变引用
-    let bank = &mut ctx.accounts.bank;
-    // 计算需要移除的份额数量（基于提取金额占总存款的比例）
-    let shares_to_remove = (amount as f64 / bank.total_deposits as f64) * bank.total_deposit_shares as f64;
+    // 销毁与份额等量的存款凭证
+    burn_receipts(
+        &ctx.accounts.token_program,
+        &ctx.accounts.receipt_mint,
+        &ctx.accounts.receipt_vault,
+        &ctx.accounts.bank,
+        ctx.bumps.bank,
+        shares_to_remove,
+    )?;
 
-    // 重新获取用户账户的可变引用
+    // 更新用户在该银行的存款份额
     let user = &mut ctx.accounts.user_account;
+    let position = user.position_mut(&bank_key)?;
+    position.deposited_shares -= shares_to_remove;
+
+    // 更新银行的总存款和总份额
+    let bank = &mut ctx.accounts.bank;
+    bank.total_deposits -= amount; // 减少银行总存款
+    bank.total_deposit_shares -= shares_to_remove; // 减少银行总份额
 
-    // 根据代币类型更新用户的存款余额
-    if ctx.accounts.mint.to_account_info().key() == user.usdc_address {
-        // 如果是 USDC，减少用户的 USDC 存款
-        user.deposited_usdc -= shares_to_remove as u64;
+    // 有借款时，提取后在所有头寸上重新计算健康因子，抵押品不足时拒绝提取
+    if user.positions.iter().any(|p| p.borrowed_shares > 0) {
+        let values = value_positions(user, ctx.remaining_accounts, &[(bank_key, &**bank)])?;
+        user.health_factor = health_factor(&values);
+        require!(user.health_factor >= MIN_HEALTH_FACTOR, ErrorCode::UnderCollateralized);
     } else {
-        // 如果是 SOL，减少用户的 SOL 存款
-        user.deposited_sol -= shares_to_remove as u64;
+        user.health_factor = u64::MAX;
     }
+    user.remove_empty_positions();
 
-    // 更新银行的总存款和总份额
-    bank.total_deposits -= amount; // 减少银行总存款
-    bank.total_deposit_shares -= shares_to_remove as u64; // 减少银行总份额
-    
-    Ok(())    
+    Ok(())
 }
\ No newline at end of file
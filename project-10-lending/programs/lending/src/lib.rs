@@ -2,11 +2,15 @@
 #![allow(unexpected_cfgs)]
 use anchor_lang::prelude::*;
 use instructions::*;
+use state::{InterestRateConfig, UpdateBankParams};
 
-mod state;
+pub mod state;
 mod instructions;
 mod error;
 mod constants;
+pub mod math;
+pub mod health;
+pub mod oracle;
 
 declare_id!("CdZeD33fXsAHfZYS8jdxg4qHgXYJwBQ1Bv6GJyETtLST");
 
@@ -15,6 +19,9 @@ declare_id!("CdZeD33fXsAHfZYS8jdxg4qHgXYJwBQ1Bv6GJyETtLST");
     简化的 DeFi 借贷协议，实现了核心的借贷功能。
     1. 管理员功能
         初始化银行 (init_bank)：为特定代币创建银行账户，设置清算阈值和最大贷款价值比率。 admin.rs:46-53
+        更新银行 (update_bank)：仅银行管理员可调用，调整清算阈值、最大 LTV、清算奖励、清算关闭因子、
+            存款/借款上限、暂停标志和利率模型，写入前校验参数范围。 admin.rs
+        管理员转移 (transfer_bank_authority / accept_bank_authority)：两步转移，新管理员签名确认后生效。 admin.rs
         初始化用户 (init_user)：为用户创建账户来跟踪其存款、借款和份额。 admin.rs:55-64
     2. 用户操作功能
         存款 (deposit)：用户将资产作为抵押品存入银行，使用份额系统跟踪存款。 deposit.rs:47-97
@@ -22,18 +29,52 @@ declare_id!("CdZeD33fXsAHfZYS8jdxg4qHgXYJwBQ1Bv6GJyETtLST");
         还款 (repay)：用户偿还借入的资产。 repay.rs:44-105
         提取 (withdraw)：用户提取存入的抵押品。 withdraw.rs:49-102
     3. 清算功能
-        清算 (liquidate)：当用户的健康因子低于 1 时，清算人可以清算抵押不足的头寸，获得清算奖励。 liquidate.rs:73-136
+        清算 (liquidate)：当借款人的健康因子低于 1 时，清算人代为偿还 min(债务 × 清算关闭因子, 请求数量)，
+            按两个资产的预言机价格和小数位数换算为抵押品并获得清算奖励。 liquidate.rs
+    4. 储备金与坏账
+        储备金 (collect_reserves)：利息按 reserve_factor 计提储备金，从金库转入每个银行的保险基金账户
+            [b"insurance", mint]。 insurance.rs
+        坏账核销 (settle_bad_debt)：借款人已没有任何抵押品但仍有借款时，先用保险基金偿还，
+            不足部分从 total_deposits 中扣除，由存款人按份额分摊。 insurance.rs
+    5. 闪电贷
+        闪电贷 (flash_borrow / flash_repay)：在同一笔交易内从银行金库借出并归还代币，通过指令 sysvar
+            自省确认后面有配对的 flash_repay，手续费计入 total_deposits 归存款人。 flash_loan.rs
+    6. 查询
+        健康查询 (get_health)：只读指令，通过 return data 返回每个头寸的估值、加权抵押品、总借款、
+            健康因子和每个资产还能借出的最大数量，客户端用 .view() 调用。 get_health.rs
+        链下模拟：math.rs 是不依赖运行时的纯 Rust 模块（lending::math），链下可以用与链上相同的代码
+            模拟计息和健康因子，例如 simulate_borrow（与链上 borrow 相同的借款上限、隔离模式、e-mode 和 max_ltv 检查）。 math.rs
+            health.rs / oracle.rs / state.rs 同样是公开模块，链下可以直接用账户数据估值（position_value、load_price）。
     核心概念
         健康因子：计算公式为 (总抵押品 * 清算阈值) / 总借款，当健康因子 < 1 时可被清算。 liquidate.rs:90-91
+        借款时使用更严格的最大贷款价值比率：借款后 Σ(抵押品 * max_ltv) 必须不小于总借款。 borrow.rs
     份额系统
         使用份额机制跟踪用户存款和借款，实现利息累积而无需更新每个用户余额。 deposit.rs:68-74
+    存款凭证
+        每个银行有一个凭证铸币 [b"receipt", mint]（铸币权限为银行 PDA），1 个凭证 = 1 份存款份额。
+        作为抵押品的份额对应的凭证保管在凭证金库中；unlock_receipts / lock_receipts 在头寸和钱包之间移动凭证，
+        钱包里的凭证可以自由转账。 receipt.rs
+    利率模型
+        每个银行有独立的拐点利率模型（base_rate / slope1 / slope2 / optimal_utilization），
+        每条指令开始时先调用 Bank::accrue_interest，把利息计入 total_borrowed 与 total_deposits。 state.rs
     价格预言机集成
-        使用 Pyth 预言机获取 SOL 和 USDC 的实时价格数据。 borrow.rs:65-67
+        每个银行保存自己的 Pyth 价格源 ID（oracle_feed_id），可以上架任意资产。
+        oracle.rs 按 exponent 把价格换算为 WAD 美元价格，按银行配置检查时效（max_price_age）和置信区间（max_confidence）；
+        主价格源过期或无效时才使用备用价格源，备用价格源有自己的时效（fallback_max_price_age）。
+        抵押品按 price − conf 估值，债务按 price + conf 估值。
+    多资产头寸
+        User 保存一个按银行区分的头寸列表（positions），借款、提取和清算时通过 remaining_accounts
+        按头寸顺序传入 [bank, price_update] 账户对（配置了备用价格源的银行再加 fallback_price_update），在所有头寸上计算健康因子。 health.rs
+    隔离模式与 e-mode
+        隔离资产 (isolated) 只能作为用户唯一的抵押品，只能借出 borrowable_in_isolation 的银行（稳定币），
+            所有用户以该资产为抵押的借款合计不能超过 isolation_debt_ceiling。
+        e-mode (set_emode)：用户选择一个分组后，同组资产（如 SOL/mSOL）使用更高的 emode_max_ltv 和
+            emode_liquidation_threshold，并且只能借出同组资产。 emode.rs
     协议定义了两个主要账户结构：
         Bank：存储银行状态，包括总存款、总借款、清算参数等。 state.rs:3-29
-        User：跟踪用户的存款、借款、份额和健康因子。 state.rs:32-59
+        User：跟踪用户在各个银行中的头寸（存款、借款、份额）和健康因子。
 
-    这是一个教育性质的简化借贷协议，仅支持 SOL 和 USDC 两种资产，包含基本的借贷、清算机制
+    这是一个教育性质的简化借贷协议，包含基本的借贷、清算机制
     但缺少生产环境所需的完整安全措施和复杂的利率模型。协议使用 Anchor 框架开发，集成了 Pyth 预言机进行价格获取。
     Setup accounts
 */
@@ -43,12 +84,34 @@ pub mod lending_protocol {
 
     use super::*;
 
-    pub fn init_bank(ctx: Context<InitBank>, liquidation_threshold: u64, max_ltv: u64) -> Result<()> {
-        process_init_bank(ctx, liquidation_threshold, max_ltv)
+    pub fn init_bank(
+        ctx: Context<InitBank>,
+        oracle_feed_id: String,
+        liquidation_threshold: u64,
+        max_ltv: u64,
+        interest_rate_config: InterestRateConfig,
+    ) -> Result<()> {
+        process_init_bank(ctx, oracle_feed_id, liquidation_threshold, max_ltv, interest_rate_config)
     }
 
-    pub fn init_user(ctx: Context<InitUser>, usdc_address: Pubkey) -> Result<()> {
-        process_init_user(ctx, usdc_address)
+    pub fn update_bank(ctx: Context<UpdateBank>, params: UpdateBankParams) -> Result<()> {
+        process_update_bank(ctx, params)
+    }
+
+    pub fn transfer_bank_authority(ctx: Context<UpdateBank>, new_authority: Pubkey) -> Result<()> {
+        process_transfer_bank_authority(ctx, new_authority)
+    }
+
+    pub fn accept_bank_authority(ctx: Context<AcceptBankAuthority>) -> Result<()> {
+        process_accept_bank_authority(ctx)
+    }
+
+    pub fn init_user(ctx: Context<InitUser>) -> Result<()> {
+        process_init_user(ctx)
+    }
+
+    pub fn set_emode(ctx: Context<SetEMode>, emode_group: u8) -> Result<()> {
+        process_set_emode(ctx, emode_group)
     }
 
     pub fn deposit (ctx: Context<Deposit>, amount: u64) -> Result<()> {
@@ -67,8 +130,36 @@ pub mod lending_protocol {
         process_repay(ctx, amount)
     }
 
-    pub fn liquidate(ctx: Context<Liquidate>) -> Result<()> {
-        process_liquidate(ctx)
+    pub fn liquidate(ctx: Context<Liquidate>, amount: u64) -> Result<()> {
+        process_liquidate(ctx, amount)
+    }
+
+    pub fn unlock_receipts(ctx: Context<MoveReceipts>, shares: u64) -> Result<()> {
+        process_unlock_receipts(ctx, shares)
+    }
+
+    pub fn lock_receipts(ctx: Context<MoveReceipts>, shares: u64) -> Result<()> {
+        process_lock_receipts(ctx, shares)
+    }
+
+    pub fn collect_reserves(ctx: Context<CollectReserves>) -> Result<()> {
+        process_collect_reserves(ctx)
+    }
+
+    pub fn settle_bad_debt(ctx: Context<SettleBadDebt>) -> Result<()> {
+        process_settle_bad_debt(ctx)
+    }
+
+    pub fn flash_borrow(ctx: Context<FlashLoan>, amount: u64) -> Result<()> {
+        process_flash_borrow(ctx, amount)
+    }
+
+    pub fn flash_repay(ctx: Context<FlashLoan>, amount: u64, borrow_instruction_index: u16) -> Result<()> {
+        process_flash_repay(ctx, amount, borrow_instruction_index)
+    }
+
+    pub fn get_health(ctx: Context<GetHealth>) -> Result<HealthReport> {
+        process_get_health(ctx)
     }
 }
 
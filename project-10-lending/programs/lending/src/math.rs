@@ -0,0 +1,604 @@
+use crate::constants::{BPS_DENOMINATOR, HEALTH_FACTOR_PRECISION, ISOLATION_DEBT_DECIMALS, SECONDS_PER_YEAR, WAD};
+
+// 借贷协议使用的定点数计算。所有中间结果使用 u128，比例和利率以 WAD (1e18) 表示，
+// 配置参数以基点 (bps) 表示。
+//
+// 本模块是纯 Rust 代码，不依赖账户、Clock 等运行时状态，链下可以直接引用 lending::math，
+// 用与链上完全相同的代码模拟计息、份额换算和健康因子（例如“如果我再借 X 会怎样”）。
+// 链上指令中 MathError 通过 From 自动转换为对应的 ErrorCode。
+
+/// 定点数计算错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    /// 溢出或除以零
+    Overflow,
+    /// 价格不为正
+    InvalidPrice,
+    /// 拐点利用率为零
+    InvalidInterestRateConfig,
+    /// 模拟时指定的头寸不存在
+    InvalidPosition,
+    /// 仍有份额但资产已经归零（坏账核销扣光了总存款），无法按比例换算份额
+    DepletedPool,
+}
+
+pub type MathResult<T> = std::result::Result<T, MathError>;
+
+/// 基点转换为 WAD
+pub fn bps_to_wad(bps: u64) -> MathResult<u128> {
+    (bps as u128)
+        .checked_mul(WAD)
+        .map(|v| v / BPS_DENOMINATOR)
+        .ok_or(MathError::Overflow)
+}
+
+/// 资金利用率 = 总借款 / 总存款（WAD，最大为 1）
+pub fn utilization_rate(total_borrowed: u64, total_deposits: u64) -> MathResult<u128> {
+    if total_deposits == 0 {
+        return Ok(0);
+    }
+    let utilization = (total_borrowed as u128)
+        .checked_mul(WAD)
+        .ok_or(MathError::Overflow)?
+        / total_deposits as u128;
+    Ok(utilization.min(WAD))
+}
+
+/// 拐点利率模型，返回年化借款利率（WAD）
+///
+/// - 利用率 <= 拐点：base + slope1 * U / U_optimal
+/// - 利用率 >  拐点：base + slope1 + slope2 * (U - U_optimal) / (1 - U_optimal)
+pub fn borrow_rate(
+    utilization: u128,
+    optimal_utilization: u64,
+    base_rate: u64,
+    slope1: u64,
+    slope2: u64,
+) -> MathResult<u128> {
+    let optimal = bps_to_wad(optimal_utilization)?;
+    let base = bps_to_wad(base_rate)?;
+    let slope1 = bps_to_wad(slope1)?;
+    let slope2 = bps_to_wad(slope2)?;
+    if optimal == 0 {
+        return Err(MathError::InvalidInterestRateConfig);
+    }
+
+    let rate = if utilization <= optimal {
+        slope1
+            .checked_mul(utilization)
+            .ok_or(MathError::Overflow)?
+            / optimal
+    } else {
+        let excess = utilization - optimal;
+        let excess_rate = if optimal >= WAD {
+            0
+        } else {
+            slope2
+                .checked_mul(excess)
+                .ok_or(MathError::Overflow)?
+                / (WAD - optimal)
+        };
+        slope1 + excess_rate
+    };
+    base.checked_add(rate).ok_or(MathError::Overflow)
+}
+
+/// 在 elapsed 秒内按年化利率 annual_rate（WAD）产生的利息（单利，按每次指令复利）
+pub fn accrued_interest(principal: u64, annual_rate: u128, elapsed: i64) -> MathResult<u64> {
+    if principal == 0 || annual_rate == 0 || elapsed <= 0 {
+        return Ok(0);
+    }
+    let interest = (principal as u128)
+        .checked_mul(annual_rate)
+        .and_then(|v| v.checked_mul(elapsed as u128))
+        .ok_or(MathError::Overflow)?
+        / (SECONDS_PER_YEAR * WAD);
+    u64::try_from(interest).map_err(|_| MathError::Overflow)
+}
+
+// 份额换算：shares = amount × total_shares / total_assets，amount = shares × total_assets / total_shares
+// 舍入方向总是对池子有利：
+//     存款铸造份额、还款销毁份额向下取整；
+//     取款销毁份额、借款铸造份额向上取整；
+//     存款份额估值向下取整，借款份额估值向上取整。
+// 这样任何一次存取或借还的往返都不会凭空创造价值。
+
+/// 代币数量换算为份额（向下取整）。没有份额时按 1:1 发行；
+/// 仍有份额但资产为零时返回 DepletedPool，否则新资金会被旧的、已经不值钱的份额按比例分走
+pub fn amount_to_shares_down(amount: u64, total_shares: u64, total_assets: u64) -> MathResult<u64> {
+    if total_shares == 0 {
+        return Ok(amount);
+    }
+    if total_assets == 0 {
+        return Err(MathError::DepletedPool);
+    }
+    mul_div(amount, total_shares, total_assets, false)
+}
+
+/// 代币数量换算为份额（向上取整）。没有份额时按 1:1 发行，仍有份额但资产为零时返回 DepletedPool
+pub fn amount_to_shares_up(amount: u64, total_shares: u64, total_assets: u64) -> MathResult<u64> {
+    if total_shares == 0 {
+        return Ok(amount);
+    }
+    if total_assets == 0 {
+        return Err(MathError::DepletedPool);
+    }
+    mul_div(amount, total_shares, total_assets, true)
+}
+
+/// 份额换算为代币数量（向下取整）
+pub fn shares_to_amount_down(shares: u64, total_shares: u64, total_assets: u64) -> MathResult<u64> {
+    if total_shares == 0 {
+        return Ok(0);
+    }
+    mul_div(shares, total_assets, total_shares, false)
+}
+
+/// 份额换算为代币数量（向上取整）
+pub fn shares_to_amount_up(shares: u64, total_shares: u64, total_assets: u64) -> MathResult<u64> {
+    if total_shares == 0 {
+        return Ok(0);
+    }
+    mul_div(shares, total_assets, total_shares, true)
+}
+
+/// value × numerator / denominator，在 u128 中计算，按 round_up 决定舍入方向
+pub fn mul_div(value: u64, numerator: u64, denominator: u64, round_up: bool) -> MathResult<u64> {
+    if denominator == 0 {
+        return Err(MathError::Overflow);
+    }
+    let product = (value as u128)
+        .checked_mul(numerator as u128)
+        .ok_or(MathError::Overflow)?;
+    let denominator = denominator as u128;
+    let result = if round_up {
+        product.div_ceil(denominator)
+    } else {
+        product / denominator
+    };
+    u64::try_from(result).map_err(|_| MathError::Overflow)
+}
+
+/// a × b / c，在 u128 中计算（向下取整）。先拆成 (a / c) × b + (a % c) × b / c，
+/// 避免 a × b 在大数量、高价格时溢出
+pub fn mul_div_u128(a: u128, b: u128, c: u128) -> MathResult<u128> {
+    if c == 0 {
+        return Err(MathError::Overflow);
+    }
+    let whole = (a / c).checked_mul(b).ok_or(MathError::Overflow)?;
+    let rest = (a % c).checked_mul(b).ok_or(MathError::Overflow)? / c;
+    whole.checked_add(rest).ok_or(MathError::Overflow)
+}
+
+// 价格均为 WAD 定点数表示的美元价格（每个完整代币），见 oracle.rs
+
+/// amount 个最小单位的美元价值（WAD，向下取整）= amount × price / 10^decimals
+pub fn value_usd(amount: u64, decimals: u8, price: u128) -> MathResult<u128> {
+    let unit = 10u128.checked_pow(decimals as u32).ok_or(MathError::Overflow)?;
+    mul_div_u128(amount as u128, price, unit)
+}
+
+/// 美元价值（WAD）换算为代币最小单位数量（向下取整）= value × 10^decimals / price
+pub fn amount_for_value(value: u128, decimals: u8, price: u128) -> MathResult<u64> {
+    if price == 0 {
+        return Err(MathError::InvalidPrice);
+    }
+    let unit = 10u128.checked_pow(decimals as u32).ok_or(MathError::Overflow)?;
+    let amount = mul_div_u128(value, unit, price)?;
+    u64::try_from(amount).map_err(|_| MathError::Overflow)
+}
+
+/// 清算时，偿还 repay 个借款资产最小单位可以换得的抵押品数量（含清算奖励，向下取整）
+///
+/// seized = repay × P_b / 10^d_b × (1 + bonus) × 10^d_c / P_c
+pub fn collateral_for_repay(
+    repay: u64,
+    borrowed_decimals: u8,
+    borrowed_price: u128,
+    collateral_decimals: u8,
+    collateral_price: u128,
+    liquidation_bonus: u64,
+) -> MathResult<u64> {
+    let repay_value = value_usd(repay, borrowed_decimals, borrowed_price)?;
+    let seized_value = mul_div_u128(repay_value, BPS_DENOMINATOR + liquidation_bonus as u128, BPS_DENOMINATOR)?;
+    amount_for_value(seized_value, collateral_decimals, collateral_price)
+}
+
+/// 稳定币数量换算为隔离模式债务单位（美元，ISOLATION_DEBT_DECIMALS 位小数，按 1 美元计）
+pub fn isolation_debt_units(amount: u64, decimals: u8, round_up: bool) -> MathResult<u64> {
+    let unit = 10u64.checked_pow(decimals as u32).ok_or(MathError::Overflow)?;
+    let debt_unit = 10u64.pow(ISOLATION_DEBT_DECIMALS as u32);
+    mul_div(amount, debt_unit, unit, round_up)
+}
+
+/// Pyth 价格 value × 10^exponent 转换为 WAD
+pub fn normalize_price(value: u128, exponent: i32) -> MathResult<u128> {
+    let factor = 10u128.checked_pow(exponent.unsigned_abs()).ok_or(MathError::Overflow)?;
+    if exponent >= 0 {
+        value
+            .checked_mul(WAD)
+            .and_then(|v| v.checked_mul(factor))
+            .ok_or(MathError::Overflow)
+    } else {
+        mul_div_u128(value, WAD, factor)
+    }
+}
+
+// 健康因子相关计算。链上由 health::value_positions 从账户中构造 PositionValue，
+// 链下可以用同样的字段（银行状态 + 预言机价格）自行构造后调用下面的函数。
+
+/// 单个头寸按预言机价格估值后的结果（美元，WAD）
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PositionValue {
+    /// 存款（抵押品）价值，按 price − conf 计算
+    pub collateral_value: u128,
+    /// 借款价值，按 price + conf 计算
+    pub debt_value: u128,
+    /// 该银行的清算阈值（基点），用户处于该银行的 e-mode 分组时使用 e-mode 参数
+    pub liquidation_threshold: u64,
+    /// 该银行的最大贷款价值比率（基点），用户处于该银行的 e-mode 分组时使用 e-mode 参数
+    pub max_ltv: u64,
+    /// 该银行的 e-mode 分组
+    pub emode_group: u8,
+}
+
+/// 按清算阈值加权后的抵押品总价值
+pub fn weighted_collateral(values: &[PositionValue]) -> u128 {
+    values
+        .iter()
+        .map(|v| v.collateral_value * v.liquidation_threshold as u128 / BPS_DENOMINATOR)
+        .sum()
+}
+
+/// 按最大贷款价值比率加权后的抵押品总价值，即当前最多可以承担的借款价值
+pub fn borrowable_collateral(values: &[PositionValue]) -> u128 {
+    values
+        .iter()
+        .map(|v| v.collateral_value * v.max_ltv as u128 / BPS_DENOMINATOR)
+        .sum()
+}
+
+/// 借款总价值
+pub fn total_debt(values: &[PositionValue]) -> u128 {
+    values.iter().map(|v| v.debt_value).sum()
+}
+
+/// 健康因子 = (Σ 抵押品价值 × 清算阈值 / 10000) / Σ 借款价值，以基点定点数表示（HEALTH_FACTOR_PRECISION = 1.0），
+/// 向下取整，没有借款时为 u64::MAX，超出 u64 时取 u64::MAX
+pub fn health_factor(values: &[PositionValue]) -> u64 {
+    let debt = total_debt(values);
+    if debt == 0 {
+        return u64::MAX;
+    }
+    mul_div_u128(weighted_collateral(values), HEALTH_FACTOR_PRECISION as u128, debt)
+        .map_or(u64::MAX, |health| health.min(u64::MAX as u128) as u64)
+}
+
+/// 借款检查：Σ(抵押品价值 × max_ltv) 必须覆盖全部借款
+pub fn within_max_ltv(values: &[PositionValue]) -> bool {
+    borrowable_collateral(values) >= total_debt(values)
+}
+
+/// 在不超过 max_ltv 的前提下还能新增的借款价值（WAD 美元）
+pub fn max_additional_borrow_value(values: &[PositionValue]) -> u128 {
+    borrowable_collateral(values).saturating_sub(total_debt(values))
+}
+
+/// 链上 borrow 在 max_ltv 之前检查的借出银行和用户状态，可以由 Bank::borrow_limits 构造
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BorrowLimits {
+    /// 借出银行是否暂停
+    pub paused: bool,
+    /// 借出银行当前的总借款（已计息）
+    pub total_borrowed: u64,
+    /// 借出银行的借款上限，0 表示不限制
+    pub borrow_cap: u64,
+    /// 借出银行的 e-mode 分组
+    pub emode_group: u8,
+    /// 用户选择的 e-mode 分组，0 表示不使用
+    pub user_emode_group: u8,
+    /// 借出银行是否允许隔离模式的用户借款
+    pub borrowable_in_isolation: bool,
+    /// 用户处于隔离模式时为隔离资产银行的 (isolation_debt, isolation_debt_ceiling)
+    pub isolation: Option<(u64, u64)>,
+}
+
+/// 模拟借款的结果，除 Allowed 外与链上 borrow 返回的错误一一对应
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorrowCheck {
+    Allowed,
+    BankPaused,
+    BorrowCapExceeded,
+    EModeMismatch,
+    NotBorrowableInIsolation,
+    IsolationDebtCeilingExceeded,
+    OverLTV,
+}
+
+/// 按链上 borrow 的顺序检查暂停、借款上限、e-mode 分组和隔离模式，不包括 max_ltv
+pub fn check_borrow_limits(limits: &BorrowLimits, amount: u64, decimals: u8) -> MathResult<BorrowCheck> {
+    if limits.paused {
+        return Ok(BorrowCheck::BankPaused);
+    }
+    if limits.borrow_cap > 0 {
+        let total_borrowed = limits.total_borrowed.checked_add(amount).ok_or(MathError::Overflow)?;
+        if total_borrowed > limits.borrow_cap {
+            return Ok(BorrowCheck::BorrowCapExceeded);
+        }
+    }
+    if limits.user_emode_group != 0 && limits.emode_group != limits.user_emode_group {
+        return Ok(BorrowCheck::EModeMismatch);
+    }
+    if let Some((isolation_debt, isolation_debt_ceiling)) = limits.isolation {
+        if !limits.borrowable_in_isolation {
+            return Ok(BorrowCheck::NotBorrowableInIsolation);
+        }
+        let units = isolation_debt_units(amount, decimals, true)?;
+        let isolation_debt = isolation_debt.checked_add(units).ok_or(MathError::Overflow)?;
+        if isolation_debt > isolation_debt_ceiling {
+            return Ok(BorrowCheck::IsolationDebtCeilingExceeded);
+        }
+    }
+    Ok(BorrowCheck::Allowed)
+}
+
+/// check_borrow_limits 允许的最大借款数量（不包括 max_ltv 和银行流动性），u64::MAX 表示不受限制。
+/// 借款数量不超过该值时 check_borrow_limits 返回 Allowed，再多 1 个最小单位就不再允许
+pub fn max_borrow_within_limits(limits: &BorrowLimits, decimals: u8) -> MathResult<u64> {
+    if limits.paused || (limits.user_emode_group != 0 && limits.emode_group != limits.user_emode_group) {
+        return Ok(0);
+    }
+    let mut amount = u64::MAX;
+    if limits.borrow_cap > 0 {
+        amount = limits.borrow_cap.saturating_sub(limits.total_borrowed);
+    }
+    if let Some((isolation_debt, isolation_debt_ceiling)) = limits.isolation {
+        if !limits.borrowable_in_isolation {
+            return Ok(0);
+        }
+        // 剩余的隔离债务额度按 1 美元换算为代币数量（向下取整），超出 u64 时不受该限制
+        let unit = 10u64.checked_pow(decimals as u32).ok_or(MathError::Overflow)?;
+        let debt_unit = 10u64.pow(ISOLATION_DEBT_DECIMALS as u32);
+        let remaining = isolation_debt_ceiling.saturating_sub(isolation_debt);
+        amount = amount.min(mul_div(remaining, unit, debt_unit, false).unwrap_or(u64::MAX));
+    }
+    Ok(amount)
+}
+
+/// 模拟在 values[index] 对应的头寸再借 amount 个最小单位（新开头寸时先追加一个 debt_value 为 0 的 PositionValue）：
+/// 返回借款后的估值，以及链上 borrow 是否允许这笔借款（第一个不通过的检查）
+pub fn simulate_borrow(
+    values: &[PositionValue],
+    index: usize,
+    amount: u64,
+    decimals: u8,
+    debt_price: u128,
+    limits: &BorrowLimits,
+) -> MathResult<(Vec<PositionValue>, BorrowCheck)> {
+    let mut after = values.to_vec();
+    let position = after.get_mut(index).ok_or(MathError::InvalidPosition)?;
+    position.debt_value = position
+        .debt_value
+        .checked_add(value_usd(amount, decimals, debt_price)?)
+        .ok_or(MathError::Overflow)?;
+
+    let check = match check_borrow_limits(limits, amount, decimals)? {
+        BorrowCheck::Allowed if !within_max_ltv(&after) => BorrowCheck::OverLTV,
+        check => check,
+    };
+    Ok((after, check))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MIN_HEALTH_FACTOR;
+
+    fn position(collateral_value: u128, debt_value: u128, liquidation_threshold: u64) -> PositionValue {
+        PositionValue {
+            collateral_value,
+            debt_value,
+            liquidation_threshold,
+            max_ltv: liquidation_threshold,
+            emode_group: 0,
+        }
+    }
+
+    #[test]
+    fn borrow_rate_and_interest_at_known_utilization() {
+        // 拐点 80%，base 0，slope1 4%，slope2 75%
+        let rate = |borrowed, deposits| {
+            borrow_rate(utilization_rate(borrowed, deposits).unwrap(), 8_000, 0, 400, 7_500).unwrap()
+        };
+        assert_eq!(rate(400, 1_000), WAD * 2 / 100);
+        assert_eq!(rate(800, 1_000), WAD * 4 / 100);
+        // 拐点之后：4% + 75% × (90% − 80%) / (100% − 80%) = 41.5%
+        assert_eq!(rate(900, 1_000), WAD * 415 / 1_000);
+        // 利用率最大为 100%，没有存款时为 0
+        assert_eq!(rate(2_000, 1_000), WAD * 79 / 100);
+        assert_eq!(rate(1, 0), 0);
+
+        // 400 × 2% 一年 = 8，半年 = 4，不足一个最小单位时向下取整
+        let year = SECONDS_PER_YEAR as i64;
+        assert_eq!(accrued_interest(400_000_000, WAD * 2 / 100, year), Ok(8_000_000));
+        assert_eq!(accrued_interest(400_000_000, WAD * 2 / 100, year / 2), Ok(4_000_000));
+        assert_eq!(accrued_interest(400, WAD * 2 / 100, 1), Ok(0));
+        assert_eq!(accrued_interest(400, WAD * 2 / 100, -1), Ok(0));
+    }
+
+    #[test]
+    fn mul_div_rounding_at_boundaries() {
+        // 整除时两种舍入结果相同
+        assert_eq!(mul_div(6, 10, 3, false), Ok(20));
+        assert_eq!(mul_div(6, 10, 3, true), Ok(20));
+        // 有余数时向上取整多 1
+        assert_eq!(mul_div(7, 1, 3, false), Ok(2));
+        assert_eq!(mul_div(7, 1, 3, true), Ok(3));
+        assert_eq!(mul_div(1, 1, u64::MAX, false), Ok(0));
+        assert_eq!(mul_div(1, 1, u64::MAX, true), Ok(1));
+        // 中间结果超出 u64 但结果在范围内
+        assert_eq!(mul_div(u64::MAX, u64::MAX, u64::MAX, true), Ok(u64::MAX));
+        assert_eq!(mul_div(u64::MAX, 2, 2, false), Ok(u64::MAX));
+        // 结果超出 u64、向上取整越界或除以零
+        assert_eq!(mul_div(u64::MAX, 2, 1, false), Err(MathError::Overflow));
+        assert_eq!(mul_div(u64::MAX, 3, 2, true), Err(MathError::Overflow));
+        assert_eq!(mul_div(1, 1, 0, false), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn share_conversions_round_in_favor_of_the_pool() {
+        // 1000 份额对应 1001 个代币：1 个代币不足 1 份额
+        assert_eq!(amount_to_shares_down(1, 1_000, 1_001), Ok(0));
+        assert_eq!(amount_to_shares_up(1, 1_000, 1_001), Ok(1));
+        assert_eq!(shares_to_amount_down(1, 1_000, 1_001), Ok(1));
+        assert_eq!(shares_to_amount_up(1, 1_000, 1_001), Ok(2));
+        // 恰好整除时没有舍入
+        assert_eq!(amount_to_shares_down(1_001, 1_000, 1_001), Ok(1_000));
+        assert_eq!(amount_to_shares_up(1_001, 1_000, 1_001), Ok(1_000));
+        assert_eq!(shares_to_amount_up(1_000, 1_000, 1_001), Ok(1_001));
+        // 空池子按 1:1 发行，没有份额时份额不值钱
+        assert_eq!(amount_to_shares_down(5, 0, 0), Ok(5));
+        assert_eq!(amount_to_shares_up(5, 0, 7), Ok(5));
+        // 有份额但资产归零时不能再按 1:1 发行，否则旧份额会稀释新存款
+        assert_eq!(amount_to_shares_down(5, 3, 0), Err(MathError::DepletedPool));
+        assert_eq!(amount_to_shares_up(5, 3, 0), Err(MathError::DepletedPool));
+        assert_eq!(shares_to_amount_down(5, 0, 7), Ok(0));
+        assert_eq!(shares_to_amount_up(5, 0, 7), Ok(0));
+    }
+
+    #[test]
+    fn share_round_trips_never_create_value() {
+        let totals = [1, 3, 7, 1_000, 1_001, 999_999_937, 1_000_000_000_000_000_000];
+        let amounts = [1, 2, 3, 999, 1_000_001, 123_456_789_011, 1_000_000_000_000_000_000];
+        for total_shares in totals {
+            for total_assets in totals {
+                for amount in amounts {
+                    // 存款铸造份额向下取整，立即取回时份额估值也向下取整
+                    if let Ok(shares) = amount_to_shares_down(amount, total_shares, total_assets) {
+                        let withdrawn = shares_to_amount_down(shares, total_shares + shares, total_assets + amount).unwrap();
+                        assert!(withdrawn <= amount, "deposit {amount} into {total_shares}/{total_assets} returned {withdrawn}");
+                    }
+                    // 借款铸造份额向上取整，立即还清时债务也向上取整
+                    if let Ok(shares) = amount_to_shares_up(amount, total_shares, total_assets) {
+                        let debt = shares_to_amount_up(shares, total_shares + shares, total_assets + amount).unwrap();
+                        assert!(debt >= amount, "borrow {amount} from {total_shares}/{total_assets} owes {debt}");
+                    }
+                    // 按数量取款销毁的份额向上取整，这些份额的价值不少于取出的代币
+                    if let Ok(burned) = amount_to_shares_up(amount, total_shares, total_assets) {
+                        let value = shares_to_amount_down(burned, total_shares, total_assets).unwrap_or(u64::MAX);
+                        assert!(value >= amount, "withdraw {amount} from {total_shares}/{total_assets} burned {burned}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn health_factor_keeps_decimals() {
+        // 150 美元抵押品、100% 阈值、100 美元借款 → 1.5
+        let values = [position(150 * WAD, 100 * WAD, 10_000)];
+        assert_eq!(health_factor(&values), 15_000);
+        // 150 × 80% / 100 = 1.2
+        let values = [position(150 * WAD, 100 * WAD, 8_000)];
+        assert_eq!(health_factor(&values), 12_000);
+        // 多个头寸合并计算：(100 × 80% + 50 × 50%) / 70 = 1.5
+        let values = [position(100 * WAD, 0, 8_000), position(50 * WAD, 70 * WAD, 5_000)];
+        assert_eq!(health_factor(&values), 15_000);
+    }
+
+    #[test]
+    fn health_factor_boundary() {
+        // 加权抵押品恰好等于借款 → 1.0，不可清算
+        let values = [position(125 * WAD, 100 * WAD, 8_000)];
+        assert_eq!(health_factor(&values), MIN_HEALTH_FACTOR);
+        // 借款多 1 个最小单位 → 向下取整后低于 1.0，可以清算
+        let values = [position(125 * WAD, 100 * WAD + 1, 8_000)];
+        assert_eq!(health_factor(&values), MIN_HEALTH_FACTOR - 1);
+        // 旧的整数表示会把 1.99 截断为 1
+        let values = [position(199 * WAD, 100 * WAD, 10_000)];
+        assert_eq!(health_factor(&values), 19_900);
+    }
+
+    #[test]
+    fn simulate_borrow_applies_the_borrow_checks() {
+        // 100 美元抵押品、max_ltv 80%，借 1 美元 = 1 个 6 位小数的代币
+        let values = [position(100 * WAD, 0, 8_000), PositionValue::default()];
+        let limits = BorrowLimits::default();
+        let simulate = |amount: u64, limits: &BorrowLimits| simulate_borrow(&values, 1, amount, 6, WAD, limits).unwrap().1;
+
+        assert_eq!(simulate(80_000_000, &limits), BorrowCheck::Allowed);
+        assert_eq!(simulate(80_000_001, &limits), BorrowCheck::OverLTV);
+        let (after, _) = simulate_borrow(&values, 1, 80_000_000, 6, WAD, &limits).unwrap();
+        assert_eq!(after[1].debt_value, 80 * WAD);
+        assert_eq!(simulate_borrow(&values, 2, 1, 6, WAD, &limits).unwrap_err(), MathError::InvalidPosition);
+
+        assert_eq!(simulate(1, &BorrowLimits { paused: true, ..limits }), BorrowCheck::BankPaused);
+
+        // 借款上限包含已有借款，恰好达到上限时允许
+        let capped = BorrowLimits { total_borrowed: 900, borrow_cap: 1_000, ..limits };
+        assert_eq!(simulate(100, &capped), BorrowCheck::Allowed);
+        assert_eq!(simulate(101, &capped), BorrowCheck::BorrowCapExceeded);
+
+        // 用户选择了 e-mode 分组时只能借出同组资产
+        let emode = BorrowLimits { emode_group: 1, user_emode_group: 2, ..limits };
+        assert_eq!(simulate(1, &emode), BorrowCheck::EModeMismatch);
+        assert_eq!(simulate(1, &BorrowLimits { user_emode_group: 1, ..emode }), BorrowCheck::Allowed);
+        assert_eq!(simulate(1, &BorrowLimits { emode_group: 1, ..limits }), BorrowCheck::Allowed);
+
+        // 隔离模式：只能借出稳定币银行，隔离债务（6 位小数美元，向上取整）不能超过上限
+        let isolated = BorrowLimits { isolation: Some((9_000_000, 10_000_000)), ..limits };
+        assert_eq!(simulate(1, &isolated), BorrowCheck::NotBorrowableInIsolation);
+        let isolated = BorrowLimits { borrowable_in_isolation: true, ..isolated };
+        assert_eq!(simulate(1_000_000, &isolated), BorrowCheck::Allowed);
+        assert_eq!(simulate(1_000_001, &isolated), BorrowCheck::IsolationDebtCeilingExceeded);
+        assert_eq!(
+            simulate_borrow(&values, 1, 10, 9, WAD, &isolated).unwrap().1,
+            BorrowCheck::Allowed
+        );
+
+        // 检查顺序与链上 borrow 相同：上限在 max_ltv 之前
+        assert_eq!(simulate(80_000_001, &capped), BorrowCheck::BorrowCapExceeded);
+    }
+
+    #[test]
+    fn max_borrow_within_limits_matches_check_borrow_limits() {
+        let limits = BorrowLimits::default();
+        let cases = [
+            (BorrowLimits { total_borrowed: 900, borrow_cap: 1_000, ..limits }, 6),
+            (BorrowLimits { isolation: Some((9_000_000, 10_000_000)), borrowable_in_isolation: true, ..limits }, 6),
+            (BorrowLimits { isolation: Some((9_000_001, 10_000_000)), borrowable_in_isolation: true, ..limits }, 9),
+            (BorrowLimits { isolation: Some((0, 10_000_000)), borrowable_in_isolation: true, ..limits }, 2),
+            (
+                BorrowLimits {
+                    total_borrowed: 500,
+                    borrow_cap: 2_000_000,
+                    isolation: Some((0, 1_000_000)),
+                    borrowable_in_isolation: true,
+                    ..limits
+                },
+                6,
+            ),
+        ];
+        for (limits, decimals) in cases {
+            let max = max_borrow_within_limits(&limits, decimals).unwrap();
+            assert_eq!(check_borrow_limits(&limits, max, decimals), Ok(BorrowCheck::Allowed), "{limits:?}");
+            assert_ne!(check_borrow_limits(&limits, max + 1, decimals), Ok(BorrowCheck::Allowed), "{limits:?}");
+        }
+
+        assert_eq!(max_borrow_within_limits(&limits, 6), Ok(u64::MAX));
+        assert_eq!(max_borrow_within_limits(&BorrowLimits { paused: true, ..limits }, 6), Ok(0));
+        let emode = BorrowLimits { emode_group: 1, user_emode_group: 2, ..limits };
+        assert_eq!(max_borrow_within_limits(&emode, 6), Ok(0));
+        let isolated = BorrowLimits { isolation: Some((0, 10_000_000)), ..limits };
+        assert_eq!(max_borrow_within_limits(&isolated, 6), Ok(0));
+    }
+
+    #[test]
+    fn health_factor_without_debt_or_collateral() {
+        assert_eq!(health_factor(&[position(100 * WAD, 0, 8_000)]), u64::MAX);
+        assert_eq!(health_factor(&[]), u64::MAX);
+        assert_eq!(health_factor(&[position(0, WAD, 8_000)]), 0);
+        // 结果超出 u64 时取 u64::MAX
+        assert_eq!(health_factor(&[position(1_000_000_000_000 * WAD, 1, 10_000)]), u64::MAX);
+    }
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::math::normalize_price;
+use crate::state::Bank;
+
+// 预言机安全层：把 Pyth 价格换算为 WAD (1e18) 定点数表示的美元价格（每个完整代币），
+// 并按银行配置检查价格源、时效和置信区间。
+//
+// Pyth 价格 = price × 10^exponent，置信区间 conf 使用同一个指数。
+// 估值时使用保守价格：抵押品按 price − conf，债务按 price + conf。
+
+/// 标准化后的预言机价格（WAD 美元 / 每个完整代币）
+#[derive(Clone, Copy)]
+pub struct OraclePrice {
+    /// 中间价
+    pub price: u128,
+    /// 置信区间
+    pub confidence: u128,
+}
+
+impl OraclePrice {
+    /// 抵押品估值使用的价格：price − conf
+    pub fn collateral_price(&self) -> u128 {
+        self.price.saturating_sub(self.confidence)
+    }
+
+    /// 债务估值使用的价格：price + conf
+    pub fn debt_price(&self) -> u128 {
+        self.price.saturating_add(self.confidence)
+    }
+}
+
+/// 读取并校验银行的价格
+///
+/// - 优先使用主价格源：价格不能早于 bank.max_price_age 秒，必须为正，且 conf / price 不能超过 bank.max_confidence（基点）
+/// - 主价格源过期或无效时，如果银行配置了备用价格源并传入了它的价格更新，才改用备用价格源，
+///   时效按备用价格源自己的 bank.fallback_max_price_age 检查
+/// - price_update 必须是主价格源的价格更新，不能用其他账户跳过主价格源
+pub fn load_price(
+    bank: &Bank,
+    price_update: &PriceUpdateV2,
+    fallback_price_update: Option<&PriceUpdateV2>,
+    clock: &Clock,
+) -> Result<OraclePrice> {
+    require!(price_update.price_message.feed_id == bank.oracle_feed_id, ErrorCode::InvalidOracle);
+    let primary = checked_price(price_update, bank.max_price_age, bank.max_confidence, clock);
+    match fallback_price_update {
+        Some(fallback) if primary.is_err() && bank.has_fallback_oracle() => {
+            require!(fallback.price_message.feed_id == bank.fallback_oracle_feed_id, ErrorCode::InvalidOracle);
+            checked_price(fallback, bank.fallback_max_price_age, bank.max_confidence, clock)
+        }
+        _ => primary,
+    }
+}
+
+/// 检查单个价格更新的时效、符号和置信区间，并换算为 WAD
+fn checked_price(price_update: &PriceUpdateV2, max_age: u64, max_confidence: u64, clock: &Clock) -> Result<OraclePrice> {
+    let feed_id = price_update.price_message.feed_id;
+    let price = price_update.get_price_no_older_than(clock, max_age, &feed_id)?;
+    require!(price.price > 0, ErrorCode::InvalidOracle);
+
+    let raw_price = price.price as u128;
+    let raw_confidence = price.conf as u128;
+    require!(
+        raw_confidence * BPS_DENOMINATOR <= raw_price * max_confidence as u128,
+        ErrorCode::OracleConfidenceTooWide
+    );
+
+    Ok(OraclePrice {
+        price: normalize_price(raw_price, price.exponent)?,
+        confidence: normalize_price(raw_confidence, price.exponent)?,
+    })
+}
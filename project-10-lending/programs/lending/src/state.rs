@@ -1,4 +1,9 @@
 use anchor_lang::prelude::*;
+use crate::constants::{BPS_DENOMINATOR, MAX_BORROW_RATE, MAX_POSITIONS, WAD};
+use crate::error::ErrorCode;
+use crate::math::{
+    accrued_interest, borrow_rate, mul_div, shares_to_amount_down, shares_to_amount_up, utilization_rate, BorrowLimits,
+};
 
 /// 借贷协议中的银行账户，用于管理特定代币的资金池。
 #[account]
@@ -22,26 +27,304 @@ pub struct Bank {
     /// Current number of borrowed shares in the bank
     /// 银行中借款份额的总数量
     pub total_borrowed_shares: u64,
-    /// LTV at which the loan is defined as under collateralized and can be liquidated
-    /// 清算阈值，决定何时可以清算抵押不足的头寸
+    /// LTV (bps) at which the loan is defined as under collateralized and can be liquidated
+    /// 清算阈值（基点），决定何时可以清算抵押不足的头寸
     pub liquidation_threshold: u64,
-    /// Bonus percentage of collateral that can be liquidated
-    /// 清算奖励百分比
+    /// Bonus (bps) of seized collateral paid to the liquidator
+    /// 清算奖励（基点），清算人按偿还价值额外获得的抵押品比例
     pub liquidation_bonus: u64,
-    /// Percentage of collateral that can be liquidated
-    /// 可清算抵押品的百分比
+    /// Max share (bps) of a borrower's debt that can be repaid in one liquidation
+    /// 清算关闭因子（基点），单次清算最多可偿还的债务比例
     pub liquidation_close_factor: u64,
-    /// Max percentage of collateral that can be borrowed
-    /// 最大贷款价值比率
+    /// Max LTV (bps) a new borrow may reach
+    /// 最大贷款价值比率（基点），借款后不能超过
     pub max_ltv: u64,
+    /// Max total deposits in tokens, 0 means no cap
+    /// 存款上限（代币数量），0 表示不限制
+    pub deposit_cap: u64,
+    /// Max total borrows in tokens, 0 means no cap
+    /// 借款上限（代币数量），0 表示不限制
+    pub borrow_cap: u64,
+    /// Share (bps) of accrued interest set aside for the insurance fund
+    /// 储备金率（基点），利息中划入保险基金的比例，其余归存款人
+    pub reserve_factor: u64,
+    /// Reserves accrued but not yet moved from the treasury into the insurance fund
+    /// 已计提但尚未从金库转入保险基金的储备金
+    pub pending_reserves: u64,
+    /// Fee (bps) charged on flash loans, paid to depositors
+    /// 闪电贷手续费（基点），计入总存款归存款人所有
+    pub flash_loan_fee: u64,
+    /// Deposits, borrows and withdrawals are rejected while paused
+    /// 暂停标志，暂停期间拒绝存款、借款和提取
+    pub paused: bool,
+    /// Authority proposed by `transfer_bank_authority`, waiting for `accept_bank_authority`
+    /// 待接收的新管理员，由新管理员签名调用 accept_bank_authority 后生效
+    pub pending_authority: Pubkey,
+    /// Decimals of the asset mint
+    /// 代币铸币的小数位数，用于把数量换算为美元价值
+    pub mint_decimals: u8,
+    /// Pyth price feed id of the asset (USD quote)
+    /// 该资产的 Pyth 价格源 ID（以 USD 计价）
+    pub oracle_feed_id: [u8; 32],
+    /// Optional fallback Pyth feed id, all zeros when unset
+    /// 备用价格源 ID，全零表示未设置；只在主价格源过期或无效时使用
+    pub fallback_oracle_feed_id: [u8; 32],
+    /// Max age in seconds of an accepted primary price
+    /// 主价格源的最大时效（秒）
+    pub max_price_age: u64,
+    /// Max age in seconds of an accepted fallback price
+    /// 备用价格源的最大时效（秒），不同价格源的更新频率不同，单独配置
+    pub fallback_max_price_age: u64,
+    /// Max confidence interval (bps of price) of an accepted price
+    /// 允许的最大置信区间（基点，conf / price）
+    pub max_confidence: u64,
+    /// Deposits in an isolated bank can only back borrows of `borrowable_in_isolation` banks
+    /// 隔离资产：只能单独作为抵押品，且只能借出 borrowable_in_isolation 的银行
+    pub isolated: bool,
+    /// Max total debt (USD, `ISOLATION_DEBT_DECIMALS` decimals) backed by this isolated asset
+    /// 隔离资产的债务上限（美元，6 位小数），所有用户以该资产为抵押的借款合计不能超过
+    pub isolation_debt_ceiling: u64,
+    /// Current debt (USD, `ISOLATION_DEBT_DECIMALS` decimals) backed by this isolated asset
+    /// 当前以该隔离资产为抵押的借款合计（美元，6 位小数）
+    pub isolation_debt: u64,
+    /// Whether users in isolation mode may borrow from this bank (stable assets)
+    /// 是否允许隔离模式的用户从本银行借款（通常是稳定币）
+    pub borrowable_in_isolation: bool,
+    /// E-mode group of this asset, 0 means none
+    /// 高效模式（e-mode）分组，0 表示不属于任何分组；同组资产价格高度相关（如 SOL/mSOL）
+    pub emode_group: u8,
+    /// Max LTV (bps) for users in this bank's e-mode group
+    /// 用户处于本银行的 e-mode 分组时使用的最大贷款价值比率（基点）
+    pub emode_max_ltv: u64,
+    /// Liquidation threshold (bps) for users in this bank's e-mode group
+    /// 用户处于本银行的 e-mode 分组时使用的清算阈值（基点）
+    pub emode_liquidation_threshold: u64,
     /// Last updated timestamp
-    /// 最后更新时间戳
+    /// 最后一次计息的时间戳
     pub last_updated: i64,
-    /// 利率
+    /// Current annual borrow rate in basis points
+    /// 当前年化借款利率（基点），每次计息时更新
     pub interest_rate: u64,
+    /// Utilization (bps) at which the rate curve kinks
+    /// 拐点利用率（基点）
+    pub optimal_utilization: u64,
+    /// Annual borrow rate at zero utilization (bps)
+    /// 利用率为 0 时的基础年化利率（基点）
+    pub base_rate: u64,
+    /// Rate added between zero and optimal utilization (bps)
+    /// 拐点之前的利率斜率（基点）
+    pub slope1: u64,
+    /// Rate added between optimal and full utilization (bps)
+    /// 拐点之后的利率斜率（基点）
+    pub slope2: u64,
+}
+
+/// 拐点利率模型参数（均为基点）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct InterestRateConfig {
+    /// 拐点利用率
+    pub optimal_utilization: u64,
+    /// 利用率为 0 时的基础年化利率
+    pub base_rate: u64,
+    /// 拐点之前的利率斜率
+    pub slope1: u64,
+    /// 拐点之后的利率斜率
+    pub slope2: u64,
+}
+
+/// update_bank 的参数，为 None 的字段保持不变
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateBankParams {
+    pub liquidation_threshold: Option<u64>,
+    pub max_ltv: Option<u64>,
+    pub liquidation_bonus: Option<u64>,
+    pub liquidation_close_factor: Option<u64>,
+    pub deposit_cap: Option<u64>,
+    pub borrow_cap: Option<u64>,
+    pub paused: Option<bool>,
+    pub flash_loan_fee: Option<u64>,
+    pub reserve_factor: Option<u64>,
+    pub max_price_age: Option<u64>,
+    pub fallback_max_price_age: Option<u64>,
+    pub max_confidence: Option<u64>,
+    /// 备用价格源 ID（十六进制），空字符串表示清除
+    pub fallback_oracle_feed_id: Option<String>,
+    pub isolated: Option<bool>,
+    pub isolation_debt_ceiling: Option<u64>,
+    pub borrowable_in_isolation: Option<bool>,
+    pub emode_group: Option<u8>,
+    pub emode_max_ltv: Option<u64>,
+    pub emode_liquidation_threshold: Option<u64>,
+    pub interest_rate_config: Option<InterestRateConfig>,
+}
+
+impl Bank {
+    /// 校验风险参数（均为基点）
+    /// - 0 < max_ltv <= liquidation_threshold < 100%
+    /// - liquidation_threshold × (1 + liquidation_bonus) <= 100%，
+    ///   否则刚到清算线的头寸抵押品不足以支付清算奖励
+    /// - 0 < liquidation_close_factor <= 100%
+    /// - flash_loan_fee <= 100%，reserve_factor <= 100%
+    /// - max_price_age > 0，fallback_max_price_age > 0，0 < max_confidence <= 100%
+    /// - 隔离资产本身不能在隔离模式下被借出
+    /// - base_rate + slope1 + slope2 <= MAX_BORROW_RATE（利用率 100% 时的年化利率）
+    /// - 属于 e-mode 分组时：max_ltv <= emode_max_ltv <= emode_liquidation_threshold < 100%，
+    ///   liquidation_threshold <= emode_liquidation_threshold，同样要能支付清算奖励
+    pub fn validate_risk_params(&self) -> Result<()> {
+        self.validate_ltv_and_threshold(self.max_ltv, self.liquidation_threshold)?;
+        if self.emode_group != 0 {
+            require!(
+                self.max_ltv <= self.emode_max_ltv
+                    && self.liquidation_threshold <= self.emode_liquidation_threshold,
+                ErrorCode::InvalidRiskParams
+            );
+            self.validate_ltv_and_threshold(self.emode_max_ltv, self.emode_liquidation_threshold)?;
+        }
+        require!(!(self.isolated && self.borrowable_in_isolation), ErrorCode::InvalidRiskParams);
+        let max_rate = (self.base_rate as u128) + (self.slope1 as u128) + (self.slope2 as u128);
+        require!(max_rate <= MAX_BORROW_RATE as u128, ErrorCode::InvalidInterestRateConfig);
+        require!(
+            self.liquidation_close_factor > 0 && self.liquidation_close_factor as u128 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidRiskParams
+        );
+        require!(self.flash_loan_fee as u128 <= BPS_DENOMINATOR, ErrorCode::InvalidRiskParams);
+        require!(self.reserve_factor as u128 <= BPS_DENOMINATOR, ErrorCode::InvalidRiskParams);
+        require!(
+            self.max_price_age > 0
+                && self.fallback_max_price_age > 0
+                && self.max_confidence > 0
+                && self.max_confidence as u128 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidRiskParams
+        );
+        Ok(())
+    }
+
+    fn validate_ltv_and_threshold(&self, max_ltv: u64, liquidation_threshold: u64) -> Result<()> {
+        let threshold = liquidation_threshold as u128;
+        require!(
+            max_ltv > 0 && max_ltv <= liquidation_threshold && threshold < BPS_DENOMINATOR,
+            ErrorCode::InvalidRiskParams
+        );
+        let seized = threshold
+            .checked_mul(BPS_DENOMINATOR + self.liquidation_bonus as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(seized <= BPS_DENOMINATOR * BPS_DENOMINATOR, ErrorCode::InvalidRiskParams);
+        Ok(())
+    }
+
+    /// 是否配置了备用价格源
+    pub fn has_fallback_oracle(&self) -> bool {
+        self.fallback_oracle_feed_id != [0u8; 32]
+    }
+
+    /// 用户处于 emode_group 时本银行适用的 (max_ltv, liquidation_threshold)
+    pub fn ltv_and_threshold(&self, emode_group: u8) -> (u64, u64) {
+        if emode_group != 0 && self.emode_group == emode_group {
+            (self.emode_max_ltv, self.emode_liquidation_threshold)
+        } else {
+            (self.max_ltv, self.liquidation_threshold)
+        }
+    }
+
+    /// 从本银行借款时 math::simulate_borrow 使用的限制；isolated_bank 在用户处于隔离模式时传入其隔离资产银行
+    pub fn borrow_limits(&self, user_emode_group: u8, isolated_bank: Option<&Bank>) -> BorrowLimits {
+        BorrowLimits {
+            paused: self.paused,
+            total_borrowed: self.total_borrowed,
+            borrow_cap: self.borrow_cap,
+            emode_group: self.emode_group,
+            user_emode_group,
+            borrowable_in_isolation: self.borrowable_in_isolation,
+            isolation: isolated_bank.map(|bank| (bank.isolation_debt, bank.isolation_debt_ceiling)),
+        }
+    }
+
+    /// 暂停期间拒绝存款、借款和提取
+    pub fn require_active(&self) -> Result<()> {
+        require!(!self.paused, ErrorCode::BankPaused);
+        Ok(())
+    }
+
+    /// 写入利率模型参数
+    pub fn set_interest_rate_config(&mut self, config: InterestRateConfig) -> Result<()> {
+        // 拐点利用率必须在 (0, 100%] 之间，否则利率曲线无法计算
+        require!(
+            config.optimal_utilization > 0 && config.optimal_utilization as u128 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidInterestRateConfig
+        );
+        self.optimal_utilization = config.optimal_utilization;
+        self.base_rate = config.base_rate;
+        self.slope1 = config.slope1;
+        self.slope2 = config.slope2;
+        Ok(())
+    }
+
+    /// Accrue interest since `last_updated` into `total_borrowed` and `total_deposits`
+    /// 计息：把自上次更新以来产生的利息计入总借款与总存款，
+    /// 份额数量不变，因此存款份额和借款份额的兑换比例随之上涨。
+    /// 利息中按 reserve_factor 计提的储备金不计入总存款，而是记在 pending_reserves，
+    /// 由 collect_reserves 转入保险基金
+    pub fn accrue_interest(&mut self, now: i64) -> Result<()> {
+        let elapsed = now - self.last_updated;
+        if elapsed <= 0 {
+            return Ok(());
+        }
+
+        let utilization = utilization_rate(self.total_borrowed, self.total_deposits)?;
+        let rate = borrow_rate(
+            utilization,
+            self.optimal_utilization,
+            self.base_rate,
+            self.slope1,
+            self.slope2,
+        )?;
+        let interest = accrued_interest(self.total_borrowed, rate, elapsed)?;
+
+        let reserve = mul_div(interest, self.reserve_factor, BPS_DENOMINATOR as u64, false)?;
+
+        self.total_borrowed = self.total_borrowed.checked_add(interest).ok_or(ErrorCode::MathOverflow)?;
+        self.total_deposits = self.total_deposits.checked_add(interest - reserve).ok_or(ErrorCode::MathOverflow)?;
+        self.pending_reserves = self.pending_reserves.checked_add(reserve).ok_or(ErrorCode::MathOverflow)?;
+        self.interest_rate = rate
+            .checked_mul(BPS_DENOMINATOR)
+            .and_then(|rate| u64::try_from(rate / WAD).ok())
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.last_updated = now;
+        Ok(())
+    }
+
+    /// 存款份额当前可兑换的代币数量（向下取整）
+    pub fn deposit_amount(&self, shares: u64) -> Result<u64> {
+        Ok(shares_to_amount_down(shares, self.total_deposit_shares, self.total_deposits)?)
+    }
+
+    /// 借款份额当前对应的债务数量（向上取整）
+    pub fn debt_amount(&self, shares: u64) -> Result<u64> {
+        Ok(shares_to_amount_up(shares, self.total_borrowed_shares, self.total_borrowed)?)
+    }
+}
+
+/// 用户在某一个银行中的头寸（存款与借款）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct Position {
+    /// Bank PDA this position belongs to
+    /// 头寸所属的银行账户（PDA）
+    pub bank: Pubkey,
+    /// User's deposited shares in the bank, worth `Bank::deposit_amount(shares)` tokens
+    /// 用户在该银行中的存款份额，对应的代币数量随利息增长
+    pub deposited_shares: u64,
+    /// User's borrowed shares in the bank, worth `Bank::debt_amount(shares)` tokens
+    /// 用户在该银行中的借款份额
+    pub borrowed_shares: u64,
+}
+
+impl Position {
+    /// 存款和借款都已清零的头寸可以被移除，腾出位置
+    pub fn is_empty(&self) -> bool {
+        self.deposited_shares == 0 && self.borrowed_shares == 0
+    }
 }
 
-// Challenge: How would you update the user state to save "all_deposited_assets" and "all_borrowed_assets" to accommodate for several asset listings?  
 /// 跟踪单个用户在借贷协议中的所有活动和余额
 #[account]
 #[derive(InitSpace)]
@@ -49,38 +332,118 @@ pub struct User {
     /// Pubkey of the user's wallet
     /// 用户钱包的公钥
     pub owner: Pubkey,
-    /// User's deposited tokens in the SOL bank
-    /// 用户在 SOL 银行中存入的代币数量
-    pub deposited_sol: u64,
-    /// User's deposited shares in the SOL bank
-    /// 用户在 SOL 银行中的存款份额
-    pub deposited_sol_shares: u64,
-    /// User's borrowed tokens in the SOL bank
-    /// 用户从 SOL 银行借入的代币数量
-    pub borrowed_sol: u64,
-    /// User's borrowed shares in the SOL bank
-    /// 用户在 SOL 银行中的借款份额
-    pub borrowed_sol_shares: u64, 
-    /// User's deposited tokens in the USDC bank
-    /// 用户在 USDC 银行中存入的代币数量
-    pub deposited_usdc: u64,
-    /// User's deposited shares in the USDC bank
-    /// 用户在 USDC 银行中的存款份额
-    pub deposited_usdc_shares: u64, 
-    /// User's borrowed tokens in the USDC bank
-    /// 用户从 USDC 银行借入的代币数量
-    pub borrowed_usdc: u64,
-    /// User's borrowed shares in the USDC bank
-    /// 用户在 USDC 银行中的借款份额
-    pub borrowed_usdc_shares: u64, 
-    /// USDC mint address
-    /// USDC 代币的铸币地址
-    pub usdc_address: Pubkey,
-    /// Current health factor of the user
-    /// 用户当前的健康因子
+    /// One entry per bank the user has deposited into or borrowed from
+    /// 用户的所有头寸，每个银行最多一个，按开仓顺序排列
+    #[max_len(MAX_POSITIONS)]
+    pub positions: Vec<Position>,
+    /// Isolated bank the user deposited into, default pubkey when not in isolation mode
+    /// 隔离模式：用户存入的隔离资产银行，默认公钥表示不在隔离模式
+    pub isolated_bank: Pubkey,
+    /// E-mode group chosen by the user, 0 means none
+    /// 用户选择的 e-mode 分组，0 表示不使用
+    pub emode_group: u8,
+    /// Health factor snapshot written by the last borrow / withdraw / unlock_receipts / set_emode
+    /// 最近一次 borrow / withdraw / unlock_receipts / set_emode 计算的健康因子快照（基点定点数，10000 = 1.0，没有借款时为 u64::MAX），
+    /// 价格变化后会过时，实时数值使用 get_health 查询
     pub health_factor: u64,
     /// Last updated timestamp
     /// 最后更新时间戳
     pub last_updated: i64,
 }
 
+impl User {
+    /// 查找用户在指定银行的头寸
+    pub fn position(&self, bank: &Pubkey) -> Option<&Position> {
+        self.positions.iter().find(|p| p.bank == *bank)
+    }
+
+    /// 查找用户在指定银行的头寸，不存在则新建一个
+    pub fn position_mut(&mut self, bank: &Pubkey) -> Result<&mut Position> {
+        if let Some(index) = self.positions.iter().position(|p| p.bank == *bank) {
+            return Ok(&mut self.positions[index]);
+        }
+        require!(self.positions.len() < MAX_POSITIONS, ErrorCode::TooManyPositions);
+        self.positions.push(Position { bank: *bank, ..Default::default() });
+        Ok(self.positions.last_mut().unwrap())
+    }
+
+    /// 增加用户在某个银行的存款份额（抵押品）
+    /// 隔离资产只能作为用户唯一的抵押品：存入隔离资产前不能有其他存款，也不能有借款；
+    /// 处于隔离模式的用户不能再存入其他资产
+    pub fn add_deposit(&mut self, bank: &Pubkey, isolated: bool, shares: u64) -> Result<()> {
+        if isolated {
+            if self.isolated_bank != *bank {
+                require!(
+                    self.positions.iter().all(|p| p.deposited_shares == 0 && p.borrowed_shares == 0),
+                    ErrorCode::IsolatedCollateralMix
+                );
+            }
+            self.isolated_bank = *bank;
+        } else {
+            require!(!self.is_isolated(), ErrorCode::IsolatedCollateralMix);
+        }
+
+        let position = self.position_mut(bank)?;
+        position.deposited_shares = position.deposited_shares.checked_add(shares).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 用户是否处于隔离模式
+    pub fn is_isolated(&self) -> bool {
+        self.isolated_bank != Pubkey::default()
+    }
+
+    /// 移除已经清零的头寸；隔离资产的存款和所有借款都清零后才退出隔离模式，
+    /// 否则剩余借款在还款、清算和坏账核销时无法减少隔离资产的累计债务
+    pub fn remove_empty_positions(&mut self) {
+        self.positions.retain(|p| !p.is_empty());
+        let isolated_bank = self.isolated_bank;
+        let isolated_deposit = self.positions.iter().any(|p| p.bank == isolated_bank && p.deposited_shares > 0);
+        let borrows = self.positions.iter().any(|p| p.borrowed_shares > 0);
+        if !isolated_deposit && !borrows {
+            self.isolated_bank = Pubkey::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(isolated_bank: Pubkey, positions: Vec<Position>) -> User {
+        User {
+            owner: Pubkey::default(),
+            positions,
+            isolated_bank,
+            emode_group: 0,
+            health_factor: u64::MAX,
+            last_updated: 0,
+        }
+    }
+
+    #[test]
+    fn isolation_mode_ends_after_deposit_and_borrows_are_cleared() {
+        let isolated = Pubkey::new_unique();
+        let stable = Pubkey::new_unique();
+
+        // 隔离资产被清算完，但稳定币借款还在：仍处于隔离模式
+        let mut borrower = user(isolated, vec![
+            Position { bank: isolated, deposited_shares: 0, borrowed_shares: 0 },
+            Position { bank: stable, deposited_shares: 0, borrowed_shares: 100 },
+        ]);
+        borrower.remove_empty_positions();
+        assert_eq!(borrower.positions.len(), 1);
+        assert!(borrower.is_isolated());
+
+        // 借款还清后退出隔离模式
+        borrower.positions[0].borrowed_shares = 0;
+        borrower.remove_empty_positions();
+        assert!(borrower.positions.is_empty());
+        assert!(!borrower.is_isolated());
+
+        // 还有隔离资产存款时保持隔离模式
+        let mut depositor = user(isolated, vec![Position { bank: isolated, deposited_shares: 1, borrowed_shares: 0 }]);
+        depositor.remove_empty_positions();
+        assert!(depositor.is_isolated());
+    }
+}
@@ -1,13 +1,26 @@
 import { describe, it } from "node:test";
+import assert from "node:assert";
 import { BN, Program } from "@coral-xyz/anchor";
 import { BankrunProvider } from "anchor-bankrun";
-import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  AccountLayout,
+  MintLayout,
+  TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
 import { createAccount, createMint, mintTo } from "spl-token-bankrun";
 import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
 
-import { startAnchor, BanksClient, ProgramTestContext } from "solana-bankrun";
+import { startAnchor, BanksClient, Clock, ProgramTestContext } from "solana-bankrun";
 
-import { PublicKey, Keypair, Connection } from "@solana/web3.js";
+import {
+  PublicKey,
+  Keypair,
+  Connection,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
 
 // @ts-ignore
 import IDL from "../target/idl/lending_protocol.json";
@@ -109,12 +122,178 @@ describe("Lending Smart Contract Tests", async () => {
     program.programId
   );
 
+  const [usdcBank] = PublicKey.findProgramAddressSync(
+    [mintUSDC.toBuffer()],
+    program.programId
+  );
+
+  const [solBank] = PublicKey.findProgramAddressSync(
+    [mintSOL.toBuffer()],
+    program.programId
+  );
+
+  const [userAccount] = PublicKey.findProgramAddressSync(
+    [signer.publicKey.toBuffer()],
+    program.programId
+  );
+
+  const userUsdcTokenAccount = getAssociatedTokenAddressSync(
+    mintUSDC,
+    signer.publicKey
+  );
+
+  const userSolTokenAccount = getAssociatedTokenAddressSync(
+    mintSOL,
+    signer.publicKey
+  );
+
+  async function tokenBalance(address: PublicKey): Promise<bigint> {
+    const account = await banksClient.getAccount(address);
+    return AccountLayout.decode(Buffer.from(account!.data)).amount;
+  }
+
+  // 按用户头寸顺序生成 [bank, price_update] 剩余账户，配置了备用价格源的银行再追加备用价格更新
+  // （测试中所有价格源都是同一个已加载的账户）
+  async function positionAccounts(extraBanks: PublicKey[] = [], owner: PublicKey = signer.publicKey) {
+    const [account] = PublicKey.findProgramAddressSync([owner.toBuffer()], program.programId);
+    const user = await program.account.user.fetch(account);
+    const banks = user.positions.map((position) => position.bank);
+    for (const bank of extraBanks) {
+      if (!banks.some((b) => b.equals(bank))) banks.push(bank);
+    }
+    const accounts = [];
+    for (const bank of banks) {
+      const { fallbackOracleFeedId } = await program.account.bank.fetch(bank);
+      const priceUpdates = fallbackOracleFeedId.some((b: number) => b !== 0) ? 2 : 1;
+      accounts.push({ pubkey: bank, isSigner: false, isWritable: false });
+      for (let i = 0; i < priceUpdates; i++) {
+        accounts.push({ pubkey: solUsdPriceFeedAccountPubkey, isSigner: false, isWritable: false });
+      }
+    }
+    return accounts;
+  }
+
+  // 新建一个有 SOL 余额、已初始化用户账户的钱包
+  async function newUser(): Promise<Keypair> {
+    const user = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({ fromPubkey: signer.publicKey, toPubkey: user.publicKey, lamports: 1_000_000_000 })
+      )
+    );
+    await program.methods
+      .initUser()
+      .accounts({ signer: user.publicKey })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    return user;
+  }
+
+  // 新建一个 6 位小数的代币和对应的银行，interestRateConfig 默认与其他银行相同
+  async function newBank(interestRateConfig: Record<string, BN> = {}) {
+    const mint = await createMint(
+      // @ts-ignore
+      banksClient,
+      signer,
+      signer.publicKey,
+      null,
+      6
+    );
+    await program.methods
+      .initBank(SOL_PRICE_FEED_ID, new BN(8000), new BN(7500), {
+        optimalUtilization: new BN(8000),
+        baseRate: new BN(0),
+        slope1: new BN(400),
+        slope2: new BN(7500),
+        ...interestRateConfig,
+      })
+      .accounts({ signer: signer.publicKey, mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    const [bank] = PublicKey.findProgramAddressSync([mint.toBuffer()], program.programId);
+    return { mint, bank };
+  }
+
+  // 为 owner 创建关联代币账户并铸造 amount 个最小单位
+  async function fund(mint: PublicKey, owner: PublicKey, amount: number) {
+    const tokenAccount = await createAccount(
+      // @ts-ignores
+      banksClient,
+      signer,
+      mint,
+      owner
+    );
+    await mintTo(
+      // @ts-ignores
+      banksClient,
+      signer,
+      mint,
+      tokenAccount,
+      signer,
+      amount
+    );
+    return tokenAccount;
+  }
+
+  async function positionOf(bank: PublicKey) {
+    const user = await program.account.user.fetch(userAccount);
+    return user.positions.find((position) => position.bank.equals(bank));
+  }
+
+  // update_bank 参数，未指定的字段传 null 保持不变
+  function bankParams(overrides: Record<string, unknown> = {}) {
+    return {
+      liquidationThreshold: null,
+      maxLtv: null,
+      liquidationBonus: null,
+      liquidationCloseFactor: null,
+      depositCap: null,
+      borrowCap: null,
+      paused: null,
+      flashLoanFee: null,
+      reserveFactor: null,
+      maxPriceAge: null,
+      fallbackMaxPriceAge: null,
+      maxConfidence: null,
+      fallbackOracleFeedId: null,
+      interestRateConfig: null,
+      ...overrides,
+    };
+  }
+
+  // 与 math.rs 相同的拐点利率模型和单利计息，返回年化利率（WAD）、elapsed 秒内的利息和其中计提的储备金
+  function expectedAccrual(bank: Awaited<ReturnType<typeof program.account.bank.fetch>>, elapsed: BN) {
+    const WAD = new BN(10).pow(new BN(18));
+    const wad = (bps: BN) => bps.mul(WAD).divn(10_000);
+    const utilization = bank.totalDeposits.isZero()
+      ? new BN(0)
+      : BN.min(bank.totalBorrowed.mul(WAD).div(bank.totalDeposits), WAD);
+    const optimal = wad(bank.optimalUtilization);
+    const rate = wad(bank.baseRate).add(
+      utilization.lte(optimal)
+        ? wad(bank.slope1).mul(utilization).div(optimal)
+        : wad(bank.slope1).add(wad(bank.slope2).mul(utilization.sub(optimal)).div(WAD.sub(optimal)))
+    );
+    const interest = bank.totalBorrowed.mul(rate).mul(elapsed).div(new BN(365 * 24 * 60 * 60).mul(WAD));
+    const reserve = interest.mul(bank.reserveFactor).divn(10_000);
+    return { utilization, rate, interest, reserve };
+  }
+
+  // 固定种子的伪随机数（mulberry32），每次运行使用同一组数量，失败可以复现
+  let seed = 0x2f6b1a3d;
+  function randomAmount(max: number): BN {
+    seed = (seed + 0x6d2b79f5) | 0;
+    let t = Math.imul(seed ^ (seed >>> 15), 1 | seed);
+    t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+    const random = ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+    return new BN(1 + Math.floor(random * max));
+  }
+
   console.log("USDC Bank Account", usdcBankAccount.toBase58());
 
   console.log("SOL Bank Account", solBankAccount.toBase58());
   it("Test Init User", async () => {
     const initUserTx = await program.methods
-      .initUser(mintUSDC)
+      .initUser()
       .accounts({
         signer: signer.publicKey,
       })
@@ -125,7 +304,17 @@ describe("Lending Smart Contract Tests", async () => {
 
   it("Test Init and Fund USDC Bank", async () => {
     const initUSDCBankTx = await program.methods
-      .initBank(new BN(1), new BN(1))
+      .initBank(
+        SOL_PRICE_FEED_ID, // 测试中两个银行共用同一个已加载的价格源
+        new BN(8000), // liquidation threshold, bps
+        new BN(7500), // max LTV, bps
+        {
+          optimalUtilization: new BN(8000), // bps
+          baseRate: new BN(0),
+          slope1: new BN(400),
+          slope2: new BN(7500),
+        }
+      )
       .accounts({
         signer: signer.publicKey,
         mint: mintUSDC,
@@ -151,7 +340,17 @@ describe("Lending Smart Contract Tests", async () => {
 
   it("Test Init amd Fund SOL Bank", async () => {
     const initSOLBankTx = await program.methods
-      .initBank(new BN(1), new BN(1))
+      .initBank(
+        SOL_PRICE_FEED_ID, // 测试中两个银行共用同一个已加载的价格源
+        new BN(8000), // liquidation threshold, bps
+        new BN(7500), // max LTV, bps
+        {
+          optimalUtilization: new BN(8000), // bps
+          baseRate: new BN(0),
+          slope1: new BN(400),
+          slope2: new BN(7500),
+        }
+      )
       .accounts({
         signer: signer.publicKey,
         mint: mintSOL,
@@ -220,8 +419,15 @@ describe("Lending Smart Contract Tests", async () => {
         signer: signer.publicKey,
         mint: mintSOL,
         tokenProgram: TOKEN_PROGRAM_ID,
-        priceUpdate: solUsdPriceFeedAccount,
+        isolatedBank: null,
       })
+      // 按用户头寸顺序传入 [bank, price_update]：USDC 存款头寸，然后是新开的 SOL 借款头寸
+      .remainingAccounts([
+        { pubkey: usdcBank, isSigner: false, isWritable: false },
+        { pubkey: solUsdPriceFeedAccountPubkey, isSigner: false, isWritable: false },
+        { pubkey: solBank, isSigner: false, isWritable: false },
+        { pubkey: solUsdPriceFeedAccountPubkey, isSigner: false, isWritable: false },
+      ])
       .rpc({ commitment: "confirmed" });
 
     console.log("Borrow SOL", borrowSOL);
@@ -234,6 +440,7 @@ describe("Lending Smart Contract Tests", async () => {
         signer: signer.publicKey,
         mint: mintSOL,
         tokenProgram: TOKEN_PROGRAM_ID,
+        isolatedBank: null,
       })
       .rpc({ commitment: "confirmed" });
 
@@ -252,4 +459,847 @@ describe("Lending Smart Contract Tests", async () => {
 
     console.log("Withdraw USDC", withdrawUSDC);
   });
+
+  it("Property: deposit/withdraw round trips never create value", async () => {
+    for (let i = 0; i < 25; i++) {
+      const before = await tokenBalance(userUsdcTokenAccount);
+      const sharesBefore = (await positionOf(usdcBank))?.depositedShares ?? new BN(0);
+
+      await program.methods
+        .deposit(randomAmount(1_000_000_000))
+        .accounts({ signer: signer.publicKey, mint: mintUSDC, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc({ commitment: "confirmed" });
+
+      // 只取回这次存款新增份额按当前兑换比例（向下取整）值多少
+      const bank = await program.account.bank.fetch(usdcBank);
+      const newShares = (await positionOf(usdcBank))!.depositedShares.sub(sharesBefore);
+      const withdrawable = newShares.mul(bank.totalDeposits).div(bank.totalDepositShares);
+
+      await program.methods
+        .withdraw(withdrawable)
+        .accounts({ signer: signer.publicKey, mint: mintUSDC, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(await positionAccounts())
+        .rpc({ commitment: "confirmed" });
+
+      const after = await tokenBalance(userUsdcTokenAccount);
+      assert.ok(after <= before, `round trip ${i} created value: ${before} -> ${after}`);
+    }
+  });
+
+  it("Property: borrow/repay round trips never create value", async () => {
+    for (let i = 0; i < 25; i++) {
+      const before = await tokenBalance(userSolTokenAccount);
+
+      await program.methods
+        .borrow(randomAmount(1_000_000))
+        .accounts({ signer: signer.publicKey, mint: mintSOL, tokenProgram: TOKEN_PROGRAM_ID, isolatedBank: null })
+        .remainingAccounts(await positionAccounts([solBank]))
+        .rpc({ commitment: "confirmed" });
+
+      // 债务按借款份额向上取整，全部还清
+      const bank = await program.account.bank.fetch(solBank);
+      const shares = (await positionOf(solBank))!.borrowedShares;
+      const debt = shares
+        .mul(bank.totalBorrowed)
+        .add(bank.totalBorrowedShares.subn(1))
+        .div(bank.totalBorrowedShares);
+
+      await program.methods
+        .repay(debt)
+        .accounts({ signer: signer.publicKey, mint: mintSOL, tokenProgram: TOKEN_PROGRAM_ID, isolatedBank: null })
+        .rpc({ commitment: "confirmed" });
+
+      const after = await tokenBalance(userSolTokenAccount);
+      assert.ok(after <= before, `round trip ${i} created value: ${before} -> ${after}`);
+      assert.equal(await positionOf(solBank), undefined);
+    }
+  });
+
+  it("Interest accrues at the kinked rate for the bank's utilization", async () => {
+    // 新建一个银行，使利用率完全由本测试决定
+    const mint = await createMint(
+      // @ts-ignore
+      banksClient,
+      signer,
+      signer.publicKey,
+      null,
+      6
+    );
+    const [bank] = PublicKey.findProgramAddressSync([mint.toBuffer()], program.programId);
+    await program.methods
+      .initBank(SOL_PRICE_FEED_ID, new BN(8000), new BN(7500), {
+        optimalUtilization: new BN(8000),
+        baseRate: new BN(0),
+        slope1: new BN(400),
+        slope2: new BN(7500),
+      })
+      .accounts({ signer: signer.publicKey, mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    const userTokenAccount = await createAccount(
+      // @ts-ignores
+      banksClient,
+      signer,
+      mint,
+      signer.publicKey
+    );
+    await mintTo(
+      // @ts-ignores
+      banksClient,
+      signer,
+      mint,
+      userTokenAccount,
+      signer,
+      2_000_000_000
+    );
+
+    // 存入 1000、借出 400：利用率 40%
+    await program.methods
+      .deposit(new BN(1_000_000_000))
+      .accounts({ signer: signer.publicKey, mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .borrow(new BN(400_000_000))
+      .accounts({ signer: signer.publicKey, mint, tokenProgram: TOKEN_PROGRAM_ID, isolatedBank: null })
+      .remainingAccounts(await positionAccounts([bank]))
+      .rpc({ commitment: "confirmed" });
+    const before = await program.account.bank.fetch(bank);
+    assert.equal(before.totalBorrowedShares.toString(), "400000000");
+
+    // 时间前进一年后计息（collect_reserves 不需要价格），结束后恢复时钟
+    const clock = await banksClient.getClock();
+    const at = (unixTimestamp: bigint) =>
+      new Clock(clock.slot, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, unixTimestamp);
+    context.setClock(at(BigInt(before.lastUpdated.toString()) + BigInt(365 * 24 * 60 * 60)));
+    await program.methods
+      .collectReserves()
+      .accounts({ signer: signer.publicKey, mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    context.setClock(at(clock.unixTimestamp));
+
+    // 年化利率 = 4% × 40% / 80% = 2%，一年利息 = 8，其中 10% 是储备金
+    const after = await program.account.bank.fetch(bank);
+    assert.equal(after.interestRate.toNumber(), 200);
+    assert.equal(after.totalBorrowed.toString(), "408000000");
+    assert.equal(after.totalDeposits.toString(), "1007200000");
+    // 份额不变，借款指数（每份额债务）从 1 变为 1.02，存款指数从 1 变为 1.0072
+    assert.ok(after.totalBorrowedShares.eq(before.totalBorrowedShares));
+    assert.ok(after.totalDepositShares.eq(before.totalDepositShares));
+    assert.equal(after.totalBorrowed.muln(10_000).div(after.totalBorrowedShares).toNumber(), 10_200);
+    assert.equal(after.totalDeposits.muln(10_000).div(after.totalDepositShares).toNumber(), 10_072);
+
+    // 还清债务并取回存款，移除这个头寸，不影响后面的测试
+    await program.methods
+      .repay(new BN(408_000_000))
+      .accounts({ signer: signer.publicKey, mint, tokenProgram: TOKEN_PROGRAM_ID, isolatedBank: null })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .withdraw(new BN(1_007_200_000))
+      .accounts({ signer: signer.publicKey, mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .remainingAccounts(await positionAccounts())
+      .rpc({ commitment: "confirmed" });
+    assert.equal(await positionOf(bank), undefined);
+  });
+
+  it("Update Bank rejects non-authority", async () => {
+    const attacker = Keypair.generate();
+    await assert.rejects(
+      program.methods
+        .updateBank(bankParams({ paused: true }))
+        .accounts({ authority: attacker.publicKey, mint: mintUSDC })
+        .signers([attacker])
+        .rpc({ commitment: "confirmed" }),
+      /Unauthorized/
+    );
+  });
+
+  it("Update Bank rejects invalid risk params", async () => {
+    // max LTV 不能高于清算阈值
+    await assert.rejects(
+      program.methods
+        .updateBank(bankParams({ maxLtv: new BN(9000) }))
+        .accounts({ authority: signer.publicKey, mint: mintUSDC })
+        .rpc({ commitment: "confirmed" }),
+      /InvalidRiskParams/
+    );
+    // 清算阈值 × (1 + 奖励) 超过 100%
+    await assert.rejects(
+      program.methods
+        .updateBank(bankParams({ liquidationBonus: new BN(3000) }))
+        .accounts({ authority: signer.publicKey, mint: mintUSDC })
+        .rpc({ commitment: "confirmed" }),
+      /InvalidRiskParams/
+    );
+    // 利用率 100% 时的年化利率超过 1000%
+    await assert.rejects(
+      program.methods
+        .updateBank(
+          bankParams({
+            interestRateConfig: {
+              optimalUtilization: new BN(8000),
+              baseRate: new BN(0),
+              slope1: new BN(400),
+              slope2: new BN(99_601),
+            },
+          })
+        )
+        .accounts({ authority: signer.publicKey, mint: mintUSDC })
+        .rpc({ commitment: "confirmed" }),
+      /InvalidInterestRateConfig/
+    );
+
+    await program.methods
+      .updateBank(bankParams({ liquidationBonus: new BN(1000), liquidationCloseFactor: new BN(4000) }))
+      .accounts({ authority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+    const bank = await program.account.bank.fetch(usdcBank);
+    assert.equal(bank.liquidationBonus.toNumber(), 1000);
+    assert.equal(bank.liquidationCloseFactor.toNumber(), 4000);
+  });
+
+  it("Update Bank validates oracle params", async () => {
+    for (const params of [
+      { maxPriceAge: new BN(0) },
+      { fallbackMaxPriceAge: new BN(0) },
+      { maxConfidence: new BN(0) },
+      { maxConfidence: new BN(10_001) },
+    ]) {
+      await assert.rejects(
+        program.methods
+          .updateBank(bankParams(params))
+          .accounts({ authority: signer.publicKey, mint: mintUSDC })
+          .rpc({ commitment: "confirmed" }),
+        /InvalidRiskParams/
+      );
+    }
+
+    await program.methods
+      .updateBank(bankParams({ maxPriceAge: new BN(60), fallbackOracleFeedId: SOL_PRICE_FEED_ID }))
+      .accounts({ authority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+    let bank = await program.account.bank.fetch(usdcBank);
+    assert.equal(bank.maxPriceAge.toNumber(), 60);
+    assert.equal(Buffer.from(bank.fallbackOracleFeedId).toString("hex"), SOL_PRICE_FEED_ID.slice(2));
+
+    // 空字符串清除备用价格源，并恢复默认时效
+    await program.methods
+      .updateBank(bankParams({ maxPriceAge: new BN(100), fallbackOracleFeedId: "" }))
+      .accounts({ authority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+    bank = await program.account.bank.fetch(usdcBank);
+    assert.ok(bank.fallbackOracleFeedId.every((b: number) => b === 0));
+  });
+
+  it("Fallback price is used only when the primary is stale, with its own max age", async () => {
+    const { priceMessage } = (await pythSolanaReceiver.fetchPriceUpdateAccount(solUsdPriceFeedAccountPubkey))!;
+    const clock = await banksClient.getClock();
+    const at = (unixTimestamp: bigint) =>
+      new Clock(clock.slot, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, unixTimestamp);
+    const getHealth = async () =>
+      program.methods.getHealth().accounts({ userAccount }).remainingAccounts(await positionAccounts()).view();
+    const updateUsdcBank = (params: Record<string, unknown>) =>
+      program.methods
+        .updateBank(bankParams(params))
+        .accounts({ authority: signer.publicKey, mint: mintUSDC })
+        .rpc({ commitment: "confirmed" });
+
+    // 用户只有 USDC 头寸；价格发布 200 秒后主价格源超过 max_price_age（100 秒）
+    assert.deepEqual((await program.account.user.fetch(userAccount)).positions.map((p) => p.bank), [usdcBank]);
+    context.setClock(at(BigInt(priceMessage.publishTime.toString()) + BigInt(200)));
+    await assert.rejects(getHealth());
+
+    // 备用价格源有自己的时效：1 小时内可以替代过期的主价格源，60 秒则同样过期
+    await updateUsdcBank({ fallbackOracleFeedId: SOL_PRICE_FEED_ID, fallbackMaxPriceAge: new BN(3600) });
+    const report = await getHealth();
+    assert.ok(!report.positions[0].collateralValue.isZero());
+
+    await updateUsdcBank({ fallbackMaxPriceAge: new BN(60) });
+    await assert.rejects(getHealth());
+
+    context.setClock(at(clock.unixTimestamp));
+    await updateUsdcBank({ fallbackOracleFeedId: "", fallbackMaxPriceAge: new BN(100) });
+  });
+
+  it("Paused bank rejects deposits", async () => {
+    await program.methods
+      .updateBank(bankParams({ paused: true }))
+      .accounts({ authority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+
+    await assert.rejects(
+      program.methods
+        .deposit(new BN(1_000))
+        .accounts({ signer: signer.publicKey, mint: mintUSDC, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc({ commitment: "confirmed" }),
+      /BankPaused/
+    );
+
+    await program.methods
+      .updateBank(bankParams({ paused: false }))
+      .accounts({ authority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Deposit and borrow caps are enforced", async () => {
+    const usdc = await program.account.bank.fetch(usdcBank);
+    await program.methods
+      .updateBank(bankParams({ depositCap: usdc.totalDeposits.addn(1_000) }))
+      .accounts({ authority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+    await assert.rejects(
+      program.methods
+        .deposit(new BN(1_001))
+        .accounts({ signer: signer.publicKey, mint: mintUSDC, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc({ commitment: "confirmed" }),
+      /DepositCapExceeded/
+    );
+
+    const sol = await program.account.bank.fetch(solBank);
+    await program.methods
+      .updateBank(bankParams({ borrowCap: sol.totalBorrowed.addn(10) }))
+      .accounts({ authority: signer.publicKey, mint: mintSOL })
+      .rpc({ commitment: "confirmed" });
+    await assert.rejects(
+      program.methods
+        .borrow(new BN(11))
+        .accounts({ signer: signer.publicKey, mint: mintSOL, tokenProgram: TOKEN_PROGRAM_ID, isolatedBank: null })
+        .remainingAccounts(await positionAccounts([solBank]))
+        .rpc({ commitment: "confirmed" }),
+      /BorrowCapExceeded/
+    );
+
+    // 0 表示不限制
+    for (const mint of [mintUSDC, mintSOL]) {
+      await program.methods
+        .updateBank(bankParams({ depositCap: new BN(0), borrowCap: new BN(0) }))
+        .accounts({ authority: signer.publicKey, mint })
+        .rpc({ commitment: "confirmed" });
+    }
+  });
+
+  it("Bank authority transfer takes two steps", async () => {
+    const newAuthority = Keypair.generate();
+    await program.methods
+      .transferBankAuthority(newAuthority.publicKey)
+      .accounts({ authority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+
+    // 提名之后、确认之前，管理员仍然是原来的地址
+    let bank = await program.account.bank.fetch(usdcBank);
+    assert.ok(bank.authority.equals(signer.publicKey));
+    assert.ok(bank.pendingAuthority.equals(newAuthority.publicKey));
+
+    await assert.rejects(
+      program.methods
+        .acceptBankAuthority()
+        .accounts({ pendingAuthority: Keypair.generate().publicKey, mint: mintUSDC })
+        .rpc({ commitment: "confirmed" })
+    );
+
+    await program.methods
+      .acceptBankAuthority()
+      .accounts({ pendingAuthority: newAuthority.publicKey, mint: mintUSDC })
+      .signers([newAuthority])
+      .rpc({ commitment: "confirmed" });
+    bank = await program.account.bank.fetch(usdcBank);
+    assert.ok(bank.authority.equals(newAuthority.publicKey));
+    assert.ok(bank.pendingAuthority.equals(PublicKey.default));
+
+    // 转回原管理员，后续测试继续使用 signer
+    await program.methods
+      .transferBankAuthority(signer.publicKey)
+      .accounts({ authority: newAuthority.publicKey, mint: mintUSDC })
+      .signers([newAuthority])
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .acceptBankAuthority()
+      .accounts({ pendingAuthority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Liquidate targets the borrower and updates both banks", async () => {
+    await program.methods
+      .borrow(new BN(50_000_000_000))
+      .accounts({ signer: signer.publicKey, mint: mintSOL, tokenProgram: TOKEN_PROGRAM_ID, isolatedBank: null })
+      .remainingAccounts(await positionAccounts([solBank]))
+      .rpc({ commitment: "confirmed" });
+
+    // 下调 USDC 的清算阈值，使借款人的健康因子低于 1
+    await program.methods
+      .updateBank(bankParams({ liquidationThreshold: new BN(100), maxLtv: new BN(100) }))
+      .accounts({ authority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+
+    const usdcBefore = await program.account.bank.fetch(usdcBank);
+    const solBefore = await program.account.bank.fetch(solBank);
+    const repay = new BN(1_000_000_000);
+
+    // 测试中清算人和借款人使用同一个钱包，借款人通过 borrower 账户显式指定
+    await program.methods
+      .liquidate(repay)
+      .accounts({
+        liquidator: signer.publicKey,
+        collateralMint: mintUSDC,
+        borrowedMint: mintSOL,
+        borrower: signer.publicKey,
+        collateralPriceUpdate: solUsdPriceFeedAccountPubkey,
+        borrowedPriceUpdate: solUsdPriceFeedAccountPubkey,
+        collateralFallbackPriceUpdate: null,
+        borrowedFallbackPriceUpdate: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(await positionAccounts())
+      .rpc({ commitment: "confirmed" });
+
+    // 两个资产价格和小数位数相同，抵押品 = 偿还数量 × (1 + 10% 清算奖励)
+    const usdcAfter = await program.account.bank.fetch(usdcBank);
+    const solAfter = await program.account.bank.fetch(solBank);
+    const seized = usdcBefore.totalDeposits.sub(usdcAfter.totalDeposits);
+    assert.equal(seized.toString(), repay.muln(11).divn(10).toString());
+    assert.ok(solBefore.totalBorrowed.sub(solAfter.totalBorrowed).gte(repay.subn(1)));
+
+    await program.methods
+      .updateBank(bankParams({ liquidationThreshold: new BN(8000), maxLtv: new BN(7500) }))
+      .accounts({ authority: signer.publicKey, mint: mintUSDC })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Flash loan repaid in the same transaction pays depositors", async () => {
+    const amount = new BN(1_000_000);
+    const accounts = {
+      signer: signer.publicKey,
+      mint: mintUSDC,
+      instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const before = await program.account.bank.fetch(usdcBank);
+
+    // 第 0 条指令借出，第 1 条指令引用第 0 条并还款
+    const tx = new Transaction().add(
+      await program.methods.flashBorrow(amount).accountsPartial(accounts).instruction(),
+      await program.methods.flashRepay(amount, 0).accountsPartial(accounts).instruction()
+    );
+    await provider.sendAndConfirm(tx);
+
+    // 手续费 = ceil(金额 × 9 bps)，计入总存款
+    const after = await program.account.bank.fetch(usdcBank);
+    const fee = amount.muln(9).addn(9_999).divn(10_000);
+    assert.equal(after.totalDeposits.sub(before.totalDeposits).toString(), fee.toString());
+    assert.ok(after.totalDepositShares.eq(before.totalDepositShares));
+  });
+
+  it("Flash borrow without a matching repay fails", async () => {
+    const accounts = {
+      signer: signer.publicKey,
+      mint: mintUSDC,
+      instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    await assert.rejects(
+      program.methods
+        .flashBorrow(new BN(1_000_000))
+        .accountsPartial(accounts)
+        .rpc({ commitment: "confirmed" }),
+      /FlashLoanNotRepaid/
+    );
+
+    // 还款金额与借款不一致
+    const tx = new Transaction().add(
+      await program.methods.flashBorrow(new BN(1_000_000)).accountsPartial(accounts).instruction(),
+      await program.methods.flashRepay(new BN(1), 0).accountsPartial(accounts).instruction()
+    );
+    await assert.rejects(provider.sendAndConfirm(tx));
+  });
+
+  it("Isolated collateral cannot be mixed with other collateral", async () => {
+    // 已有存款的银行不能切换隔离模式
+    await assert.rejects(
+      program.methods
+        .updateBank(bankParams({ isolated: true }))
+        .accounts({ authority: signer.publicKey, mint: mintUSDC })
+        .rpc({ commitment: "confirmed" }),
+      /BankHasDeposits/
+    );
+
+    await program.methods
+      .updateBank(bankParams({ isolated: true, isolationDebtCeiling: new BN(1_000_000_000) }))
+      .accounts({ authority: signer.publicKey, mint: mintSOL })
+      .rpc({ commitment: "confirmed" });
+
+    // 用户已经有 USDC 存款，不能再存入隔离资产
+    await assert.rejects(
+      program.methods
+        .deposit(new BN(1_000))
+        .accounts({ signer: signer.publicKey, mint: mintSOL, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc({ commitment: "confirmed" }),
+      /IsolatedCollateralMix/
+    );
+
+    // 隔离资产本身不能在隔离模式下被借出
+    await assert.rejects(
+      program.methods
+        .updateBank(bankParams({ borrowableInIsolation: true }))
+        .accounts({ authority: signer.publicKey, mint: mintSOL })
+        .rpc({ commitment: "confirmed" }),
+      /InvalidRiskParams/
+    );
+
+    await program.methods
+      .updateBank(bankParams({ isolated: false }))
+      .accounts({ authority: signer.publicKey, mint: mintSOL })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Isolation mode borrows are capped by the isolated asset's debt ceiling", async () => {
+    // 隔离资产债务上限 10 美元（6 位小数），稳定币银行也是 6 位小数，按 1 美元计 1 个代币 = 1_000_000 单位
+    const isolated = await newBank();
+    const stable = await newBank();
+    await program.methods
+      .updateBank(bankParams({ isolated: true, isolationDebtCeiling: new BN(10_000_000) }))
+      .accounts({ authority: signer.publicKey, mint: isolated.mint })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .updateBank(bankParams({ borrowableInIsolation: true }))
+      .accounts({ authority: signer.publicKey, mint: stable.mint })
+      .rpc({ commitment: "confirmed" });
+
+    const lender = await newUser();
+    const borrower = await newUser();
+    await fund(stable.mint, lender.publicKey, 100_000_000);
+    await fund(isolated.mint, borrower.publicKey, 100_000_000);
+    await program.methods
+      .deposit(new BN(100_000_000))
+      .accounts({ signer: lender.publicKey, mint: stable.mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .signers([lender])
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .deposit(new BN(100_000_000))
+      .accounts({ signer: borrower.publicKey, mint: isolated.mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .signers([borrower])
+      .rpc({ commitment: "confirmed" });
+
+    const borrow = async (amount: number) =>
+      program.methods
+        .borrow(new BN(amount))
+        .accounts({
+          signer: borrower.publicKey,
+          mint: stable.mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          isolatedBank: isolated.bank,
+        })
+        .remainingAccounts(await positionAccounts([stable.bank], borrower.publicKey))
+        .signers([borrower])
+        .rpc({ commitment: "confirmed" });
+    const isolationDebt = async () => (await program.account.bank.fetch(isolated.bank)).isolationDebt.toNumber();
+
+    // 借到恰好等于上限，再多 1 个单位就失败
+    await borrow(9_999_999);
+    await assert.rejects(borrow(2), /IsolationDebtCeilingExceeded/);
+    await borrow(1);
+    assert.equal(await isolationDebt(), 10_000_000);
+    await assert.rejects(borrow(1), /IsolationDebtCeilingExceeded/);
+
+    // 还款释放额度，之后可以重新借到上限
+    await program.methods
+      .repay(new BN(4_000_000))
+      .accounts({
+        signer: borrower.publicKey,
+        mint: stable.mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        isolatedBank: isolated.bank,
+      })
+      .signers([borrower])
+      .rpc({ commitment: "confirmed" });
+    assert.equal(await isolationDebt(), 6_000_000);
+    await borrow(4_000_000);
+    assert.equal(await isolationDebt(), 10_000_000);
+    await assert.rejects(borrow(1), /IsolationDebtCeilingExceeded/);
+  });
+
+  it("E-mode applies group LTVs and only to assets in the group", async () => {
+    for (const mint of [mintUSDC, mintSOL]) {
+      await program.methods
+        .updateBank(bankParams({ emodeGroup: 1, emodeMaxLtv: new BN(8500), emodeLiquidationThreshold: new BN(9000) }))
+        .accounts({ authority: signer.publicKey, mint })
+        .rpc({ commitment: "confirmed" });
+    }
+
+    await program.methods
+      .setEmode(1)
+      .accounts({ signer: signer.publicKey })
+      .remainingAccounts(await positionAccounts())
+      .rpc({ commitment: "confirmed" });
+    assert.equal((await program.account.user.fetch(userAccount)).emodeGroup, 1);
+
+    // 已有的 SOL 借款不属于分组 2
+    await assert.rejects(
+      program.methods
+        .setEmode(2)
+        .accounts({ signer: signer.publicKey })
+        .remainingAccounts(await positionAccounts())
+        .rpc({ commitment: "confirmed" }),
+      /EModeMismatch/
+    );
+
+    await program.methods
+      .setEmode(0)
+      .accounts({ signer: signer.publicKey })
+      .remainingAccounts(await positionAccounts())
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Reserves skim interest into the insurance fund", async () => {
+    const [insuranceFund] = PublicKey.findProgramAddressSync(
+      [Buffer.from("insurance"), mintSOL.toBuffer()],
+      program.programId
+    );
+
+    // SOL 银行的流动性是直接铸造到金库的，total_deposits 为 0、利率为 0；先存入真实存款，
+    // 使已有的 SOL 借款产生利用率
+    await mintTo(
+      // @ts-ignores
+      banksClient,
+      signer,
+      mintSOL,
+      userSolTokenAccount,
+      signer,
+      100_000_000_000
+    );
+    await program.methods
+      .deposit(new BN(100_000_000_000))
+      .accounts({ signer: signer.publicKey, mint: mintSOL, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+
+    const before = await program.account.bank.fetch(solBank);
+    const insuranceBefore = await tokenBalance(insuranceFund);
+    assert.ok(!before.totalBorrowed.isZero() && !before.totalDeposits.isZero());
+
+    // 时间前进一天产生利息，collect_reserves 不需要价格，结束后恢复时钟
+    const clock = await banksClient.getClock();
+    const day = BigInt(24 * 60 * 60);
+    const at = (unixTimestamp: bigint) =>
+      new Clock(clock.slot, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, unixTimestamp);
+    context.setClock(at(clock.unixTimestamp + day));
+
+    await program.methods
+      .collectReserves()
+      .accounts({ signer: signer.publicKey, mint: mintSOL, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    context.setClock(at(clock.unixTimestamp));
+
+    // 储备金 = 利息 × reserve_factor（向下取整），利息 = 总借款 × 年化利率 × elapsed / 一年
+    const elapsed = new BN((clock.unixTimestamp + day).toString()).sub(before.lastUpdated);
+    const { interest, reserve } = expectedAccrual(before, elapsed);
+    assert.ok(!reserve.isZero());
+
+    const after = await program.account.bank.fetch(solBank);
+    assert.equal(after.totalBorrowed.sub(before.totalBorrowed).toString(), interest.toString());
+    assert.equal(after.totalDeposits.sub(before.totalDeposits).toString(), interest.sub(reserve).toString());
+    assert.equal(
+      (await tokenBalance(insuranceFund)) - insuranceBefore,
+      BigInt(before.pendingReserves.add(reserve).toString())
+    );
+    assert.equal(after.pendingReserves.toNumber(), 0);
+  });
+
+  it("Bad debt cannot be settled while the borrower has collateral", async () => {
+    await assert.rejects(
+      program.methods
+        .settleBadDebt()
+        .accounts({
+          signer: signer.publicKey,
+          mint: mintSOL,
+          borrower: signer.publicKey,
+          isolatedBank: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: "confirmed" }),
+      /BorrowerHasCollateral/
+    );
+  });
+
+  it("Bad debt is covered by insurance first and the rest lowers the share price", async () => {
+    // 借款银行固定 500% 年化利率，一年后借款人的债务远超抵押品
+    const collateral = await newBank();
+    const debt = await newBank({ baseRate: new BN(50_000), slope1: new BN(0), slope2: new BN(0) });
+    const [insuranceFund] = PublicKey.findProgramAddressSync(
+      [Buffer.from("insurance"), debt.mint.toBuffer()],
+      program.programId
+    );
+    const lender = await newUser();
+    const borrower = await newUser();
+    const [borrowerAccount] = PublicKey.findProgramAddressSync([borrower.publicKey.toBuffer()], program.programId);
+    await fund(debt.mint, lender.publicKey, 2_000_000_000);
+    await fund(collateral.mint, borrower.publicKey, 100_000_000);
+    await fund(debt.mint, signer.publicKey, 1_000_000_000);
+
+    await program.methods
+      .deposit(new BN(1_000_000_000))
+      .accounts({ signer: lender.publicKey, mint: debt.mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .signers([lender])
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .deposit(new BN(100_000_000))
+      .accounts({ signer: borrower.publicKey, mint: collateral.mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .signers([borrower])
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .borrow(new BN(50_000_000))
+      .accounts({ signer: borrower.publicKey, mint: debt.mint, tokenProgram: TOKEN_PROGRAM_ID, isolatedBank: null })
+      .remainingAccounts(await positionAccounts([debt.bank], borrower.publicKey))
+      .signers([borrower])
+      .rpc({ commitment: "confirmed" });
+
+    // 时间前进一年计息，储备金（利息的 10%）转入保险基金，结束后恢复时钟
+    const clock = await banksClient.getClock();
+    const at = (unixTimestamp: bigint) =>
+      new Clock(clock.slot, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, unixTimestamp);
+    context.setClock(at(clock.unixTimestamp + BigInt(365 * 24 * 60 * 60)));
+    await program.methods
+      .collectReserves()
+      .accounts({ signer: signer.publicKey, mint: debt.mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    context.setClock(at(clock.unixTimestamp));
+    const insurance = await tokenBalance(insuranceFund);
+    assert.ok(insurance > BigInt(0));
+
+    // 一次清算拿走借款人的全部抵押品，剩余债务成为坏账
+    await program.methods
+      .updateBank(bankParams({ liquidationCloseFactor: new BN(10_000) }))
+      .accounts({ authority: signer.publicKey, mint: debt.mint })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .liquidate(new BN(1_000_000_000))
+      .accounts({
+        liquidator: signer.publicKey,
+        collateralMint: collateral.mint,
+        borrowedMint: debt.mint,
+        borrower: borrower.publicKey,
+        collateralPriceUpdate: solUsdPriceFeedAccountPubkey,
+        borrowedPriceUpdate: solUsdPriceFeedAccountPubkey,
+        collateralFallbackPriceUpdate: null,
+        borrowedFallbackPriceUpdate: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(await positionAccounts([], borrower.publicKey))
+      .rpc({ commitment: "confirmed" });
+    const positions = (await program.account.user.fetch(borrowerAccount)).positions;
+    assert.equal(positions.length, 1);
+    assert.ok(positions[0].bank.equals(debt.bank) && positions[0].depositedShares.isZero());
+
+    const before = await program.account.bank.fetch(debt.bank);
+    const badDebt = positions[0].borrowedShares
+      .mul(before.totalBorrowed)
+      .add(before.totalBorrowedShares.subn(1))
+      .div(before.totalBorrowedShares);
+    const covered = BN.min(badDebt, new BN(insurance.toString()));
+    assert.ok(covered.lt(badDebt));
+
+    await program.methods
+      .settleBadDebt()
+      .accounts({
+        signer: signer.publicKey,
+        mint: debt.mint,
+        borrower: borrower.publicKey,
+        isolatedBank: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    // 保险基金先覆盖，剩余部分从总存款中扣除，份额不变，兑换比例下降
+    const after = await program.account.bank.fetch(debt.bank);
+    assert.equal(await tokenBalance(insuranceFund), insurance - BigInt(covered.toString()));
+    assert.equal(before.totalDeposits.sub(after.totalDeposits).toString(), badDebt.sub(covered).toString());
+    assert.ok(after.totalDepositShares.eq(before.totalDepositShares));
+    assert.ok(after.totalBorrowedShares.isZero());
+    assert.equal((await program.account.user.fetch(borrowerAccount)).positions.length, 0);
+    assert.ok(
+      after.totalDeposits.mul(before.totalDepositShares).lt(before.totalDeposits.mul(after.totalDepositShares))
+    );
+
+    // 之后的存款按下降后的兑换比例发行份额，新存款人的份额价值不被旧份额分走（两次向下取整最多损失 2 个最小单位）
+    const amount = new BN(500_000_000);
+    await program.methods
+      .deposit(amount)
+      .accounts({ signer: lender.publicKey, mint: debt.mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .signers([lender])
+      .rpc({ commitment: "confirmed" });
+    const latest = await program.account.bank.fetch(debt.bank);
+    const newShares = latest.totalDepositShares.sub(after.totalDepositShares);
+    const value = newShares.mul(latest.totalDeposits).div(latest.totalDepositShares);
+    assert.ok(value.lte(amount) && value.gte(amount.subn(2)), `deposit of ${amount} is worth ${value}`);
+  });
+
+  it("Deposit receipts track shares and move between position and wallet", async () => {
+    const [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), mintUSDC.toBuffer()],
+      program.programId
+    );
+    const [receiptVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_vault"), mintUSDC.toBuffer()],
+      program.programId
+    );
+    const userReceiptAccount = getAssociatedTokenAddressSync(receiptMint, signer.publicKey);
+    const receiptSupply = async () =>
+      MintLayout.decode(Buffer.from((await banksClient.getAccount(receiptMint))!.data)).supply;
+
+    // 凭证总供应量 = 总存款份额，金库余额 = 用户作为抵押品的份额
+    let bank = await program.account.bank.fetch(usdcBank);
+    let shares = (await positionOf(usdcBank))!.depositedShares;
+    assert.equal((await receiptSupply()).toString(), bank.totalDepositShares.toString());
+    assert.equal((await tokenBalance(receiptVault)).toString(), shares.toString());
+
+    const moved = new BN(1_000_000);
+    await program.methods
+      .unlockReceipts(moved)
+      .accounts({ signer: signer.publicKey, mint: mintUSDC, tokenProgram: TOKEN_PROGRAM_ID })
+      .remainingAccounts(await positionAccounts())
+      .rpc({ commitment: "confirmed" });
+    assert.equal((await tokenBalance(userReceiptAccount)).toString(), moved.toString());
+    assert.equal((await positionOf(usdcBank))!.depositedShares.toString(), shares.sub(moved).toString());
+
+    await program.methods
+      .lockReceipts(moved)
+      .accounts({ signer: signer.publicKey, mint: mintUSDC, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    assert.equal((await tokenBalance(userReceiptAccount)).toString(), "0");
+    assert.equal((await positionOf(usdcBank))!.depositedShares.toString(), shares.toString());
+
+    bank = await program.account.bank.fetch(usdcBank);
+    assert.equal((await receiptSupply()).toString(), bank.totalDepositShares.toString());
+  });
+
+  it("Get health reports positions, health factor and borrow limits", async () => {
+    const user = await program.account.user.fetch(userAccount);
+    // 头寸账户对之后追加 SOL 银行，查询它的可借额度
+    const report = await program.methods
+      .getHealth()
+      .accounts({ userAccount })
+      .remainingAccounts([
+        ...(await positionAccounts()),
+        { pubkey: solBank, isSigner: false, isWritable: false },
+        { pubkey: solUsdPriceFeedAccountPubkey, isSigner: false, isWritable: false },
+      ])
+      .view();
+
+    assert.equal(report.positions.length, user.positions.length);
+    report.positions.forEach((position, i) =>
+      assert.ok(position.bank.equals(user.positions[i].bank))
+    );
+    const totalDebt = report.positions.reduce((sum, p) => sum.add(p.debtValue), new BN(0));
+    assert.equal(report.totalDebt.toString(), totalDebt.toString());
+    if (!totalDebt.isZero()) {
+      assert.equal(
+        report.healthFactor.toString(),
+        report.weightedCollateral.muln(10_000).div(totalDebt).toString()
+      );
+    }
+
+    // 可借额度不超过银行金库中扣除待转储备金后真实可借出的流动性
+    const bank = await program.account.bank.fetch(solBank);
+    const liquidity = new BN((await tokenBalance(solTokenAccount)).toString()).sub(bank.pendingReserves);
+    assert.equal(report.otherAssets.length, 1);
+    assert.ok(report.otherAssets[0].bank.equals(solBank));
+    assert.ok(!report.otherAssets[0].maxAdditionalBorrow.isZero());
+    assert.ok(report.otherAssets[0].maxAdditionalBorrow.lte(liquidity));
+  });
 });
@@ -11,6 +11,20 @@ pub const SEED_CONFIG_ACCOUNT: &[u8] = b"config"; // 配置账户PDA种子
 pub const SEED_COLLATERAL_ACCOUNT: &[u8] = b"collateral"; // 抵押品账户PDA种子
 pub const SEED_SOL_ACCOUNT: &[u8] = b"sol"; // SOL存储账户PDA种子
 pub const SEED_MINT_ACCOUNT: &[u8] = b"mint"; // 稳定币铸造账户PDA种子
+pub const SEED_COLLATERAL_TYPE_ACCOUNT: &[u8] = b"collateral_type"; // 抵押品类型注册账户PDA种子
+pub const SEED_VAULT_ACCOUNT: &[u8] = b"vault"; // 代币抵押品存储账户PDA种子
+pub const SEED_SURPLUS_ACCOUNT: &[u8] = b"surplus"; // 协议盈余稳定币账户PDA种子
+pub const SEED_PSM_ACCOUNT: &[u8] = b"psm"; // 锚定稳定模块（PSM）账户PDA种子
+pub const SEED_PSM_RESERVE_ACCOUNT: &[u8] = b"psm_reserve"; // PSM外部稳定币储备账户PDA种子
+pub const SEED_AUCTION_ACCOUNT: &[u8] = b"auction"; // 清算拍卖账户PDA种子
+pub const SEED_AUCTION_VAULT_ACCOUNT: &[u8] = b"auction_vault"; // 代币抵押品拍卖的保管代币账户PDA种子
+pub const SEED_SAVINGS_ACCOUNT: &[u8] = b"savings"; // 储蓄模块账户PDA种子
+pub const SEED_SAVINGS_VAULT_ACCOUNT: &[u8] = b"savings_vault"; // 储蓄金库稳定币账户PDA种子
+pub const SEED_SAVINGS_POSITION_ACCOUNT: &[u8] = b"savings_position"; // 用户储蓄份额账户PDA种子
+pub const SEED_SHUTDOWN_ACCOUNT: &[u8] = b"shutdown"; // 紧急关停账户PDA种子，也保管结算得到的SOL
+pub const SEED_SHUTDOWN_VAULT_ACCOUNT: &[u8] = b"shutdown_vault"; // 紧急关停后代币抵押品结算池PDA种子
+pub const SEED_SHUTDOWN_CLAIM_ACCOUNT: &[u8] = b"shutdown_claim"; // 用户锁定稳定币记录PDA种子
+pub const SEED_SHUTDOWN_CASH_ACCOUNT: &[u8] = b"shutdown_cash"; // 用户在每个结算池已兑付数量PDA种子
 
 // Pyth价格预言机相关常量
 #[constant]
@@ -18,9 +32,6 @@ pub const FEED_ID: &str = "0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4
 // SOL/USD价格预言机的Feed ID，用于获取SOL的实时价格数据
 pub const MAXIMUM_AGE: u64 = 100; // allow pricefeed 100 sec old, to avoid stale price feed errors
                                   // 允许价格数据的最大过期时间（秒），防止使用过时的价格数据
-pub const PRICE_FEED_DECIMAL_ADJUSTMENT: u128 = 10; // price feed returns 1e8, multiple by 10 to match lamports 10e9
-                                                    // 价格数据小数位调整常量
-                                                    // Pyth返回的价格精度是1e8，乘以10调整为lamports的1e9精度
 
 // 协议配置默认值常量
 // Constants for configuration values
@@ -31,8 +42,34 @@ pub const LIQUIDATION_THRESHOLD: u64 = 50; // 200% over-collateralized
 pub const LIQUIDATION_BONUS: u64 = 10; // 10% bonus lamports when liquidating
                                        // 清算奖励：10%，清算者执行清算时获得的额外lamport奖励
 
-pub const MIN_HEALTH_FACTOR: u64 = 1;
-// 最小健康因子：1，低于此值的仓位可被清算
+pub const HEALTH_FACTOR_PRECISION: u64 = 10_000;
+// 健康因子使用基点定点数表示：10_000 = 1.0，12_000 = 1.2
+
+pub const MIN_HEALTH_FACTOR: u64 = HEALTH_FACTOR_PRECISION;
+// 最小健康因子：1.0，低于此值的仓位可被清算
 
 pub const MINT_DECIMALS: u8 = 9;
 // 稳定币的小数位数：9位，与SOL的lamports精度保持一致
+
+pub const DEFAULT_STABILITY_FEE: u64 = 200;
+// 默认年化稳定费：200 基点（2%），原生SOL仓位使用
+
+// 参数上限，update_config 和 register_collateral_type 使用
+pub const MAX_LIQUIDATION_BONUS: u64 = 50; // 清算奖励最多 50%
+pub const MAX_STABILITY_FEE: u64 = 10_000; // 年化稳定费最多 10000 基点（100%）
+pub const MAX_PSM_FEE: u64 = 1_000; // PSM兑换手续费最多 1000 基点（10%）
+pub const MAX_SAVINGS_RATE: u64 = 10_000; // 年化储蓄利率最多 10000 基点（100%）
+
+// 荷兰式拍卖清算参数
+pub const AUCTION_PRICE_PREMIUM: u64 = 12_000; // 起拍价 = 预言机价格 × 120%（基点）
+pub const AUCTION_DURATION: i64 = 60 * 60; // 价格在 1 小时内线性降到 0，之后需要 restart_auction 重新起拍
+pub const AUCTION_KEEPER_TIP: u64 = 100; // 发起拍卖的 keeper 获得债务的 1%（基点），计入拍卖需要筹集的债务
+
+// 紧急关停参数
+pub const DEFAULT_DEBT_CEILING: u64 = u64::MAX; // 全局债务上限默认不限制，由管理员通过 update_config 设置
+pub const DEFAULT_SHUTDOWN_WAIT: i64 = 3 * 24 * 60 * 60; // 关停后等待 3 天才能 finalize_shutdown，留给 keeper 结算资不抵债的仓位
+
+// 定点数常量
+pub const WAD: u128 = 1_000_000_000_000_000_000; // 1e18，利率指数的精度
+pub const BPS_DENOMINATOR: u128 = 10_000; // 基点分母
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60; // 一年的秒数，用于年化费率换算
@@ -17,4 +17,107 @@ pub enum CustomError {
     // 资产价格应该始终为正数
     #[msg("Price should not be negative")]
     InvalidPrice,
+    // 抵押品数量不足错误
+    // 当赎回或清算的抵押品数量超过仓位中的余额时触发
+    #[msg("Insufficient collateral")]
+    InsufficientCollateral,
+    // 销毁数量超过债务错误
+    // 当清算时要销毁的稳定币数量超过仓位已铸造的数量时触发
+    #[msg("Amount exceeds outstanding debt")]
+    AmountExceedsDebt,
+    // 超过债务上限错误
+    // 当某个抵押品类型的已铸造稳定币总量超过其债务上限时触发
+    #[msg("Debt ceiling exceeded for this collateral type")]
+    DebtCeilingExceeded,
+    // 数学运算溢出错误
+    #[msg("Math overflow")]
+    MathOverflow,
+    // 无权限错误
+    // 当签名者不是配置账户的管理员（或待接受的新管理员）时触发
+    #[msg("Unauthorized")]
+    Unauthorized,
+    // 配置参数无效错误
+    // 当 update_config 或 register_collateral_type 的参数超出允许范围时触发
+    #[msg("Invalid config parameters")]
+    InvalidConfigParams,
+    // PSM储备不足错误
+    // 当换出的外部稳定币数量超过储备余额时触发
+    #[msg("Insufficient PSM reserve")]
+    InsufficientPsmReserve,
+    // 兑换数量为零错误
+    // 当扣除手续费并换算小数位后得到的数量为零时触发
+    #[msg("Swap amount too small")]
+    SwapAmountTooSmall,
+    // 拍卖已过期错误
+    // 当拍卖价格已经降到 0 时触发，需要先调用 restart_auction
+    #[msg("Auction expired, restart it first")]
+    AuctionExpired,
+    // 拍卖未过期错误
+    // 当拍卖价格还没有降到 0 时调用 restart_auction 触发
+    #[msg("Auction has not expired")]
+    AuctionNotExpired,
+    // 拍卖价格过高错误
+    // 当当前拍卖价格高于竞拍者愿意支付的最高价格时触发
+    #[msg("Auction price above the bidder's max price")]
+    AuctionPriceTooHigh,
+    // 竞拍数量过小错误
+    // 当竞拍买入的抵押品或支付的稳定币为 0 时触发
+    #[msg("Bid too small")]
+    BidTooSmall,
+    // 储蓄份额不足错误
+    // 当赎回的份额超过用户持有的份额时触发
+    #[msg("Insufficient savings shares")]
+    InsufficientShares,
+    // 存入数量过小错误
+    // 当存入的稳定币换算后得到 0 份额时触发
+    #[msg("Deposit too small")]
+    DepositTooSmall,
+    // 协议已暂停错误
+    // 暂停期间调用铸造或销毁稳定币的指令时触发
+    #[msg("Stablecoin is paused")]
+    Paused,
+    // 扩展未启用错误
+    // 当mint创建时没有启用对应的 Token-2022 扩展时触发
+    #[msg("Mint extension not enabled")]
+    ExtensionNotEnabled,
+    // 超过全局债务上限错误
+    // 当铸造后的稳定币总供应量超过 Config.debt_ceiling 时触发
+    #[msg("Global debt ceiling exceeded")]
+    GlobalDebtCeilingExceeded,
+    // 协议已关停错误
+    // emergency_shutdown 之后调用铸造、仓位操作、清算或PSM指令时触发
+    #[msg("Protocol has been shut down")]
+    Shutdown,
+    // 协议未关停错误
+    // 没有 emergency_shutdown 就调用结算指令时触发
+    #[msg("Protocol is not shut down")]
+    NotShutdown,
+    // 关停价格未记录错误
+    // 结算代币抵押品前需要先调用 record_shutdown_price
+    #[msg("Shutdown price not recorded for this collateral type")]
+    ShutdownPriceNotRecorded,
+    // 关停等待期未结束错误
+    // 关停后 shutdown_wait 秒内调用 finalize_shutdown 时触发
+    #[msg("Shutdown wait period has not elapsed")]
+    ShutdownWaitNotElapsed,
+    // 关停已完成结算错误
+    // 重复调用 finalize_shutdown 时触发
+    #[msg("Shutdown already finalized")]
+    ShutdownFinalized,
+    // 关停未完成结算错误
+    // finalize_shutdown 之前锁定或兑付稳定币时触发
+    #[msg("Shutdown not finalized")]
+    ShutdownNotFinalized,
+    // 锁定的稳定币不足错误
+    // 在某个结算池兑付的数量超过用户锁定的稳定币时触发
+    #[msg("Redeem amount exceeds locked stablecoins")]
+    InsufficientLockedStablecoin,
+    // 拍卖抵押品不匹配错误
+    // 用原生SOL拍卖的指令处理代币拍卖（或相反）时触发
+    #[msg("Auction collateral does not match the instruction")]
+    AuctionCollateralMismatch,
+    // 协议账户错误
+    // seize_tokens 试图从协议盈余账户或储蓄金库转出稳定币时触发
+    #[msg("Cannot seize from a protocol-owned account")]
+    ProtocolAccount,
 }
@@ -0,0 +1,61 @@
+use crate::ConfigParams;
+use anchor_lang::prelude::*;
+
+// 协议参数被 update_config 修改，记录修改前后的参数
+#[event]
+pub struct ConfigUpdated {
+    pub old: ConfigParams,
+    pub new: ConfigParams,
+}
+
+// 配置账户的管理员完成两步转移
+#[event]
+pub struct ConfigAuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+// 不健康的原生SOL仓位被没收进入荷兰式拍卖
+#[event]
+pub struct AuctionStarted {
+    pub collateral_account: Pubkey,
+    pub keeper: Pubkey,
+    pub lot: u64,
+    pub tab: u64,
+    pub tip: u64,
+    pub start_price: u64,
+}
+
+// 拍卖结束：剩余的SOL退还给仓位所有者，bad_debt 为没有筹集到的稳定币
+#[event]
+pub struct AuctionSettled {
+    pub collateral_account: Pubkey,
+    pub leftover: u64,
+    pub bad_debt: u64,
+}
+
+// 紧急关停：记录关停时的SOL价格和原生SOL仓位的债务总量
+#[event]
+pub struct ShutdownStarted {
+    pub time: i64,
+    pub sol_price: u64,
+    pub sol_debt: u64,
+}
+
+// 关停后结算一个仓位：seized 进入结算池，returned 退还给仓位所有者，
+// shortfall 为抵押品不足以覆盖债务的部分（抵押品数量）
+#[event]
+pub struct VaultSettled {
+    pub collateral_account: Pubkey,
+    pub seized: u64,
+    pub returned: u64,
+    pub shortfall: u64,
+}
+
+// 关停结算完成：supply 为可以兑付抵押品的稳定币总量
+#[event]
+pub struct ShutdownFinalized {
+    pub supply: u64,
+    pub burned_surplus: u64,
+    pub sol_fix: u128,
+}
@@ -1,8 +1,17 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
 use anchor_spl::token_interface::{
-   Mint, Token2022,
+    default_account_state_initialize, initialize_mint2, metadata_pointer_initialize,
+    permanent_delegate_initialize,
+    spl_token_2022::{
+        extension::ExtensionType,
+        state::{AccountState, Mint},
+    },
+    spl_token_metadata_interface::state::TokenMetadata,
+    token_metadata_initialize, DefaultAccountStateInitialize, InitializeMint2, MetadataPointerInitialize,
+    PermanentDelegateInitialize, Token2022, TokenMetadataInitialize,
 };
-use crate::{Config, LIQUIDATION_BONUS, LIQUIDATION_THRESHOLD, MINT_DECIMALS, MIN_HEALTH_FACTOR, SEED_CONFIG_ACCOUNT, SEED_MINT_ACCOUNT};
+use crate::{Config, DebtState, InitializeConfigParams, DEFAULT_DEBT_CEILING, DEFAULT_SHUTDOWN_WAIT, DEFAULT_STABILITY_FEE, LIQUIDATION_BONUS, LIQUIDATION_THRESHOLD, MINT_DECIMALS, MIN_HEALTH_FACTOR, SEED_CONFIG_ACCOUNT, SEED_MINT_ACCOUNT};
 
 #[derive(Accounts)]
 pub struct InitializeConfig<'info> {
@@ -22,17 +31,14 @@ pub struct InitializeConfig<'info> {
     pub config_account: Account<'info, Config>,
     // 稳定币铸造账户 - 控制稳定币的发行和销毁
     // 使用 "mint" 作为种子生成确定性地址
+    // 账户大小取决于启用的扩展，所以在 create_mint 中手动创建和初始化
+    /// CHECK: created and initialized as a Token-2022 mint in this instruction
     #[account(
-        init,
-        payer = authority,
+        mut,
         seeds = [SEED_MINT_ACCOUNT],
         bump,
-        mint::decimals = MINT_DECIMALS,
-        mint::authority = mint_account,
-        mint::freeze_authority = mint_account,
-        mint::token_program = token_program
     )]
-    pub mint_account: InterfaceAccount<'info, Mint>,
+    pub mint_account: UncheckedAccount<'info>,
     // Token2022 程序 - 处理代币相关操作
     pub token_program: Program<'info, Token2022>,
     // 系统程序 - 处理账户创建和租金支付
@@ -49,7 +55,9 @@ pub struct InitializeConfig<'info> {
 
 **/
 
-pub fn process_initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+pub fn process_initialize_config(ctx: Context<InitializeConfig>, params: InitializeConfigParams) -> Result<()> {
+    create_mint(ctx.accounts, ctx.bumps.mint_account, &params)?;
+
     *ctx.accounts.config_account = Config {
         // 设置协议管理员为当前调用者
         authority: ctx.accounts.authority.key(),
@@ -61,13 +69,147 @@ pub fn process_initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
         liquidation_bonus: LIQUIDATION_BONUS,
         // 最小健康因子 - 低于此值的仓位可被清算（例如：1.0）
         min_health_factor: MIN_HEALTH_FACTOR,
+        // 原生SOL仓位的稳定费，利率指数从 1.0 开始
+        debt: DebtState::new(DEFAULT_STABILITY_FEE, Clock::get()?.unix_timestamp),
         // 保存配置账户的bump值，用于后续PDA验证
         bump: ctx.bumps.config_account,
         // 保存mint账户的bump值，用于后续PDA验证
         bump_mint_account:  ctx.bumps.mint_account,
+        // 没有待处理的管理员转移
+        pending_authority: Pubkey::default(),
+        // 暂停权限默认为管理员
+        pause_authority: params.pause_authority.unwrap_or(ctx.accounts.authority.key()),
+        paused: false,
+        // 记录mint启用的扩展
+        permanent_delegate: params.permanent_delegate,
+        default_frozen: params.default_frozen,
+        // 全局债务上限默认不限制，关停参数使用默认值
+        debt_ceiling: DEFAULT_DEBT_CEILING,
+        shutdown_wait: DEFAULT_SHUTDOWN_WAIT,
+        shutdown: false,
     };
     // 打印配置信息到程序日志，便于调试
     msg!("Initialized Config Acccount:{:#?}", ctx.accounts.config_account);
     Ok(())
 }
 
+/**
+创建稳定币mint并按参数启用 Token-2022 扩展：
+    元数据指针 + 元数据：名称、符号和URI保存在mint账户本身，更新权限为配置账户PDA
+    永久代理：代理为配置账户PDA，只有管理员可以通过 seize_tokens 使用（例如执行法院命令）
+    默认冻结：新代币账户创建后处于冻结状态，管理员通过 set_account_allowed 解冻（允许名单）
+mint 权限和冻结权限都是mint账户PDA本身。扩展必须在 initialize_mint2 之前初始化，元数据在之后。
+**/
+fn create_mint<'info>(
+    accounts: &InitializeConfig<'info>,
+    bump_mint: u8,
+    params: &InitializeConfigParams,
+) -> Result<()> {
+    let mint = accounts.mint_account.to_account_info();
+    let token_program = accounts.token_program.to_account_info();
+    let config_key = accounts.config_account.key();
+
+    let mut extensions = Vec::new();
+    if params.metadata.is_some() {
+        extensions.push(ExtensionType::MetadataPointer);
+    }
+    if params.permanent_delegate {
+        extensions.push(ExtensionType::PermanentDelegate);
+    }
+    if params.default_frozen {
+        extensions.push(ExtensionType::DefaultAccountState);
+    }
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&extensions)?;
+    // 元数据由 token_metadata_initialize 追加到账户末尾，租金按最终大小预先支付
+    let metadata_space = match &params.metadata {
+        Some(metadata) => TokenMetadata {
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri: metadata.uri.clone(),
+            ..Default::default()
+        }
+        .tlv_size_of()?,
+        None => 0,
+    };
+    let lamports = Rent::get()?.minimum_balance(space + metadata_space);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[SEED_MINT_ACCOUNT, &[bump_mint]]];
+    create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: accounts.authority.to_account_info(),
+                to: mint.clone(),
+            },
+            signer_seeds,
+        ),
+        lamports,
+        space as u64,
+        &token_program.key(),
+    )?;
+
+    if params.metadata.is_some() {
+        metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            Some(config_key),
+            Some(mint.key()),
+        )?;
+    }
+    if params.permanent_delegate {
+        permanent_delegate_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                PermanentDelegateInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            &config_key,
+        )?;
+    }
+    if params.default_frozen {
+        default_account_state_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                DefaultAccountStateInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            &AccountState::Frozen,
+        )?;
+    }
+
+    initialize_mint2(
+        CpiContext::new(token_program.clone(), InitializeMint2 { mint: mint.clone() }),
+        MINT_DECIMALS,
+        &mint.key(),
+        Some(&mint.key()),
+    )?;
+
+    if let Some(metadata) = &params.metadata {
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataInitialize {
+                    token_program_id: token_program,
+                    metadata: mint.clone(),
+                    update_authority: accounts.config_account.to_account_info(),
+                    mint_authority: mint.clone(),
+                    mint,
+                },
+                signer_seeds,
+            ),
+            metadata.name.clone(),
+            metadata.symbol.clone(),
+            metadata.uri.clone(),
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::{
+    error::CustomError, set_frozen_internal, Config, Savings, MAX_SAVINGS_RATE, SEED_CONFIG_ACCOUNT, SEED_SAVINGS_ACCOUNT,
+    SEED_SAVINGS_VAULT_ACCOUNT, SEED_SURPLUS_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct InitializeSavings<'info> {
+    // 协议管理员 - 支付储蓄账户和金库的租金
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // 配置账户 - 验证调用者是协议管理员
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority @ CustomError::Unauthorized,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 储蓄模块账户 - 全局唯一
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Savings::INIT_SPACE,
+        seeds = [SEED_SAVINGS_ACCOUNT],
+        bump,
+    )]
+    pub savings_account: Box<Account<'info, Savings>>,
+    // 储蓄金库 - 由储蓄账户PDA控制的稳定币账户
+    #[account(
+        init,
+        payer = authority,
+        seeds = [SEED_SAVINGS_VAULT_ACCOUNT],
+        bump,
+        token::mint = mint_account,
+        token::authority = savings_account,
+        token::token_program = token_program,
+    )]
+    pub savings_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 协议盈余账户 - 储蓄利息的来源，还没有调用过 mint_surplus 时在这里创建
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [SEED_SURPLUS_ACCOUNT],
+        bump,
+        token::mint = mint_account,
+        token::authority = config_account,
+        token::token_program = token_program,
+    )]
+    pub surplus_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 稳定币铸造账户
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // Token2022 程序 - 创建稳定币账户
+    pub token_program: Program<'info, Token2022>,
+    // 系统程序 - 处理账户创建和租金支付
+    pub system_program: Program<'info, System>,
+}
+
+/**
+开通储蓄模块（参考 MakerDAO 的 DSR），只有协议管理员可以调用。
+储户存入稳定币获得份额，利息按储蓄利率从协议盈余账户发放到金库，份额的兑换率随之增长。
+**/
+pub fn process_initialize_savings(ctx: Context<InitializeSavings>, savings_rate: u64) -> Result<()> {
+    require!(savings_rate <= MAX_SAVINGS_RATE, CustomError::InvalidConfigParams);
+
+    // 默认冻结模式下新建的金库和盈余账户是冻结的，由协议自己解冻
+    for token_account in [&ctx.accounts.savings_vault, &ctx.accounts.surplus_account] {
+        if token_account.is_frozen() {
+            set_frozen_internal(
+                &ctx.accounts.mint_account,
+                token_account,
+                &ctx.accounts.token_program,
+                ctx.accounts.config_account.bump_mint_account,
+                false,
+            )?;
+        }
+    }
+
+    let savings_account = &mut ctx.accounts.savings_account;
+    savings_account.savings_rate = savings_rate;
+    savings_account.total_shares = 0;
+    savings_account.total_assets = 0;
+    savings_account.last_accrued = Clock::get()?.unix_timestamp;
+    savings_account.bump = ctx.bumps.savings_account;
+    savings_account.bump_vault = ctx.bumps.savings_vault;
+    msg!("Initialized Savings:{:#?}", savings_account);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::{error::CustomError, mint_tokens_internal, set_frozen_internal, CollateralType, Config, SEED_CONFIG_ACCOUNT, SEED_SURPLUS_ACCOUNT};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct MintSurplus<'info> {
+    // 调用者 - 首次调用时支付盈余账户的租金，任何人都可以调用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // 配置账户 - 原生SOL仓位的稳定费记录在这里
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 抵押品类型注册账户 - 传入时铸造该类型的稳定费，不传时铸造原生SOL仓位的稳定费
+    #[account(mut)]
+    pub collateral_type: Option<Box<Account<'info, CollateralType>>>,
+    // 稳定币铸造账户
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 协议盈余账户 - 由配置账户PDA控制的稳定币账户，接收铸造的稳定费
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [SEED_SURPLUS_ACCOUNT],
+        bump,
+        token::mint = mint_account,
+        token::authority = config_account,
+        token::token_program = token_program,
+    )]
+    pub surplus_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // Token2022 程序 - 处理稳定币铸造
+    pub token_program: Program<'info, Token2022>,
+    // 系统程序 - 处理账户创建
+    pub system_program: Program<'info, System>,
+}
+
+/**
+把已计提的稳定费铸造到协议盈余账户。
+稳定费计提时只增加了利率指数（仓位的实际债务随之增长），对应的稳定币在这里才真正铸造出来，
+铸造后 pending_fees 清零。
+**/
+pub fn process_mint_surplus(ctx: Context<MintSurplus>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let debt = match ctx.accounts.collateral_type.as_mut() {
+        Some(collateral_type) => &mut collateral_type.debt,
+        None => &mut ctx.accounts.config_account.debt,
+    };
+    debt.accrue(now)?;
+    let fees = std::mem::take(&mut debt.pending_fees);
+    if fees == 0 {
+        return Ok(());
+    }
+
+    // 默认冻结模式下盈余账户创建后是冻结的，由协议自己解冻
+    if ctx.accounts.surplus_account.is_frozen() {
+        set_frozen_internal(
+            &ctx.accounts.mint_account,
+            &ctx.accounts.surplus_account,
+            &ctx.accounts.token_program,
+            ctx.accounts.config_account.bump_mint_account,
+            false,
+        )?;
+    }
+    mint_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.surplus_account,
+        &ctx.accounts.token_program,
+        ctx.accounts.config_account.bump_mint_account,
+        fees,
+    )?;
+    msg!("Minted {:.9} stability fees to surplus", fees as f64 / 1e9);
+    Ok(())
+}
@@ -2,3 +2,21 @@ pub use initialize_config::*;
 pub mod update_config;
 pub use update_config::*;
 pub mod initialize_config;
+pub use register_collateral_type::*;
+pub mod register_collateral_type;
+pub use mint_surplus::*;
+pub mod mint_surplus;
+pub use register_psm::*;
+pub mod register_psm;
+pub use update_psm::*;
+pub mod update_psm;
+pub use initialize_savings::*;
+pub mod initialize_savings;
+pub use update_savings_rate::*;
+pub mod update_savings_rate;
+pub use set_paused::*;
+pub mod set_paused;
+pub use set_account_allowed::*;
+pub mod set_account_allowed;
+pub use seize_tokens::*;
+pub mod seize_tokens;
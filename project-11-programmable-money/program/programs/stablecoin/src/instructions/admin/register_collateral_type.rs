@@ -0,0 +1,66 @@
+use crate::{
+    error::CustomError, validate_liquidation_params, CollateralType, Config, DebtState, MAX_STABILITY_FEE, SEED_COLLATERAL_TYPE_ACCOUNT,
+    SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
+
+#[derive(Accounts)]
+pub struct RegisterCollateralType<'info> {
+    // 协议管理员 - 支付注册账户的租金
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // 配置账户 - 验证调用者是协议管理员
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority @ CustomError::Unauthorized,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Account<'info, Config>,
+    // 抵押品代币的mint，可以是SPL Token或Token2022代币
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    // 抵押品类型注册账户 - 每个mint一个
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CollateralType::INIT_SPACE,
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump,
+    )]
+    pub collateral_type: Account<'info, CollateralType>,
+    // 系统程序 - 处理账户创建和租金支付
+    pub system_program: Program<'info, System>,
+}
+
+/**
+注册一种新的抵押品类型（除原生SOL之外的SPL代币），只有协议管理员可以调用。
+每种类型有自己的Pyth价格源、清算阈值、债务上限和稳定费，用户可以为每种类型各开一个抵押仓位。
+原生SOL仓位仍然使用 Config 中的参数和 FEED_ID。
+**/
+pub fn process_register_collateral_type(
+    ctx: Context<RegisterCollateralType>,
+    price_feed_id: String,      // Pyth价格源ID（十六进制字符串）
+    liquidation_threshold: u64, // 清算阈值（百分比）
+    debt_ceiling: u64,          // 债务上限（稳定币基础单位）
+    stability_fee: u64,         // 年化稳定费（基点）
+) -> Result<()> {
+    validate_liquidation_params(liquidation_threshold, ctx.accounts.config_account.liquidation_bonus)?;
+    require!(stability_fee <= MAX_STABILITY_FEE, CustomError::InvalidConfigParams);
+
+    *ctx.accounts.collateral_type = CollateralType {
+        mint: ctx.accounts.collateral_mint.key(),
+        mint_decimals: ctx.accounts.collateral_mint.decimals,
+        price_feed_id: get_feed_id_from_hex(&price_feed_id)?,
+        liquidation_threshold,
+        debt_ceiling,
+        debt: DebtState::new(stability_fee, Clock::get()?.unix_timestamp),
+        bump: ctx.bumps.collateral_type,
+        shutdown_price: 0,
+        shutdown_gap: 0,
+        shutdown_fix: 0,
+    };
+    msg!("Registered Collateral Type:{:#?}", ctx.accounts.collateral_type);
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::{
+    error::CustomError, Config, Psm, MAX_PSM_FEE, SEED_CONFIG_ACCOUNT, SEED_PSM_ACCOUNT,
+    SEED_PSM_RESERVE_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct RegisterPsm<'info> {
+    // 协议管理员 - 支付PSM账户和储备账户的租金
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // 配置账户 - 验证调用者是协议管理员
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority @ CustomError::Unauthorized,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Account<'info, Config>,
+    // 外部稳定币的mint，例如USDC
+    pub stable_mint: InterfaceAccount<'info, Mint>,
+    // PSM账户 - 每种外部稳定币一个
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Psm::INIT_SPACE,
+        seeds = [SEED_PSM_ACCOUNT, stable_mint.key().as_ref()],
+        bump,
+    )]
+    pub psm: Account<'info, Psm>,
+    // 储备账户 - 由自身PDA控制，保管换入的外部稳定币
+    #[account(
+        init,
+        payer = authority,
+        seeds = [SEED_PSM_RESERVE_ACCOUNT, stable_mint.key().as_ref()],
+        bump,
+        token::mint = stable_mint,
+        token::authority = reserve,
+        token::token_program = stable_token_program,
+    )]
+    pub reserve: InterfaceAccount<'info, TokenAccount>,
+    // 外部稳定币所属的代币程序（SPL Token 或 Token2022）
+    pub stable_token_program: Interface<'info, TokenInterface>,
+    // 系统程序 - 处理账户创建和租金支付
+    pub system_program: Program<'info, System>,
+}
+
+/**
+为一种外部稳定币开通锚定稳定模块（PSM），只有协议管理员可以调用。
+开通后任何人都可以按 1:1（扣除手续费）在外部稳定币和本稳定币之间兑换，
+通过PSM铸造的稳定币总量不能超过债务上限。
+**/
+pub fn process_register_psm(ctx: Context<RegisterPsm>, fee: u64, debt_ceiling: u64) -> Result<()> {
+    require!(fee <= MAX_PSM_FEE, CustomError::InvalidConfigParams);
+
+    *ctx.accounts.psm = Psm {
+        stable_mint: ctx.accounts.stable_mint.key(),
+        stable_decimals: ctx.accounts.stable_mint.decimals,
+        reserve: ctx.accounts.reserve.key(),
+        fee,
+        debt_ceiling,
+        total_minted: 0,
+        bump: ctx.bumps.psm,
+        bump_reserve: ctx.bumps.reserve,
+        shutdown_fix: 0,
+    };
+    msg!("Registered PSM:{:#?}", ctx.accounts.psm);
+    Ok(())
+}
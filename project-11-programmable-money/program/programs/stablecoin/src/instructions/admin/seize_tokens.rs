@@ -0,0 +1,80 @@
+use crate::{
+    error::CustomError, set_frozen_internal, transfer_stablecoin_internal, Config, SEED_CONFIG_ACCOUNT,
+    SEED_SAVINGS_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SeizeTokens<'info> {
+    // 协议管理员
+    pub authority: Signer<'info>,
+    // 配置账户 - 永久代理，验证调用者是协议管理员
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority @ CustomError::Unauthorized,
+        has_one = mint_account,
+        constraint = config_account.permanent_delegate @ CustomError::ExtensionNotEnabled
+    )]
+    pub config_account: Account<'info, Config>,
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    // 被没收稳定币的代币账户，可以属于任何人，但不能是协议自己的盈余账户（配置账户所有）和储蓄金库（储蓄账户所有）
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program,
+        constraint = from.owner != config_account.key() @ CustomError::ProtocolAccount,
+        constraint = from.owner != Pubkey::find_program_address(&[SEED_SAVINGS_ACCOUNT], &crate::ID).0
+            @ CustomError::ProtocolAccount,
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    // 接收没收稳定币的代币账户
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program,
+    )]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+/**
+管理员通过永久代理（配置账户PDA）从任意代币账户转出稳定币，例如执行法院命令。
+协议自己的盈余和储蓄金库不能没收，否则管理员可以绕过 mint_surplus 和储蓄份额直接取走这些稳定币。
+被冻结的账户先临时解冻，转账后重新冻结。
+**/
+pub fn process_seize_tokens(ctx: Context<SeizeTokens>, amount: u64) -> Result<()> {
+    let frozen = ctx.accounts.from.is_frozen();
+    if frozen {
+        set_frozen_internal(
+            &ctx.accounts.mint_account,
+            &ctx.accounts.from,
+            &ctx.accounts.token_program,
+            ctx.accounts.config_account.bump_mint_account,
+            false,
+        )?;
+    }
+
+    transfer_stablecoin_internal(
+        &ctx.accounts.from,
+        &ctx.accounts.to,
+        &ctx.accounts.mint_account,
+        ctx.accounts.config_account.to_account_info(),
+        &ctx.accounts.token_program,
+        &[&[SEED_CONFIG_ACCOUNT, &[ctx.accounts.config_account.bump]]],
+        amount,
+    )?;
+
+    if frozen {
+        set_frozen_internal(
+            &ctx.accounts.mint_account,
+            &ctx.accounts.from,
+            &ctx.accounts.token_program,
+            ctx.accounts.config_account.bump_mint_account,
+            true,
+        )?;
+    }
+    msg!("Seized {:.9} from {}", amount as f64 / 1e9, ctx.accounts.from.key());
+    Ok(())
+}
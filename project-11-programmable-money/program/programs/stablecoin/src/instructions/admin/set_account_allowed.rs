@@ -0,0 +1,43 @@
+use crate::{error::CustomError, set_frozen_internal, Config, SEED_CONFIG_ACCOUNT};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SetAccountAllowed<'info> {
+    // 协议管理员
+    pub authority: Signer<'info>,
+    // 配置账户 - 验证调用者是协议管理员，并且mint启用了默认冻结
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority @ CustomError::Unauthorized,
+        has_one = mint_account,
+        constraint = config_account.default_frozen @ CustomError::ExtensionNotEnabled
+    )]
+    pub config_account: Account<'info, Config>,
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    // 要加入或移出允许名单的稳定币代币账户
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// 默认冻结模式下的允许名单：管理员完成KYC后解冻用户的代币账户，移出允许名单时重新冻结。
+// 冻结的账户不能转账，也不能接收铸造的稳定币。
+pub fn process_set_account_allowed(ctx: Context<SetAccountAllowed>, allowed: bool) -> Result<()> {
+    if ctx.accounts.token_account.is_frozen() == allowed {
+        set_frozen_internal(
+            &ctx.accounts.mint_account,
+            &ctx.accounts.token_account,
+            &ctx.accounts.token_program,
+            ctx.accounts.config_account.bump_mint_account,
+            !allowed,
+        )?;
+    }
+    msg!("Token account {} allowed: {}", ctx.accounts.token_account.key(), allowed);
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use crate::{error::CustomError, Config, SEED_CONFIG_ACCOUNT};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    // 暂停权限
+    pub pause_authority: Signer<'info>,
+    // 配置账户 - 验证调用者是暂停权限
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = pause_authority @ CustomError::Unauthorized
+    )]
+    pub config_account: Account<'info, Config>,
+}
+
+// 暂停或恢复所有铸造和销毁稳定币的指令（存入铸造、赎回销毁、清算、拍卖、PSM、mint_surplus）。
+// 稳定币本身的转账和储蓄模块不受影响。
+pub fn process_set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    ctx.accounts.config_account.paused = paused;
+    msg!("Paused: {}", paused);
+    Ok(())
+}
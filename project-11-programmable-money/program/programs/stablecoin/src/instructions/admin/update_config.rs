@@ -1,30 +1,91 @@
-use crate::{Config, SEED_CONFIG_ACCOUNT};
+use crate::{error::CustomError, events::*, Config, UpdateConfigParams, SEED_CONFIG_ACCOUNT};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct UpdateConfig<'info> {
+    // 协议管理员 - 必须是配置账户中记录的 authority
+    pub authority: Signer<'info>,
     // 配置账户 - 存储协议全局参数的PDA账户
     // 必须是可变的，因为我们要修改其中的参数
     #[account(
         mut,
         seeds = [SEED_CONFIG_ACCOUNT],
         bump = config_account.bump,
+        has_one = authority @ CustomError::Unauthorized,
     )]
     pub config_account: Account<'info, Config>,
 }
-// 修改健康因子以测试清算指令，没有权限检查，任何人都可以调用
-// Change health factor to test liquidate instruction, no authority check, anyone can invoke
-pub fn process_update_config(ctx: Context<UpdateConfig>, min_health_factor: u64) -> Result<()> {
 
+#[derive(Accounts)]
+pub struct AcceptConfigAuthority<'info> {
+    // 待接受的新管理员 - 必须是 transfer_config_authority 指定的地址
+    pub pending_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        constraint = config_account.pending_authority == pending_authority.key() @ CustomError::Unauthorized,
+    )]
+    pub config_account: Account<'info, Config>,
+}
+
+// 修改协议参数，只有管理员可以调用
+// 参数为 None 时保持不变，写入前做范围检查，并发出记录新旧参数的 ConfigUpdated 事件
+// 修改稳定费前先按旧费率计提到当前时间
+// 关停后不能再修改：稳定费只计提到关停时间，修改 shutdown_wait 会改变 finalize_shutdown 的时间
+pub fn process_update_config(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+    require!(!ctx.accounts.config_account.shutdown, CustomError::Shutdown);
     // 获取配置账户的可变引用
     let config_account = &mut ctx.accounts.config_account;
+    let old = config_account.params();
 
-    // 更新最小健康因子参数
-    // 这个值决定了何时可以清算用户的抵押品
-    // 值越高，清算条件越严格
-    config_account.min_health_factor = min_health_factor;
+    // 最小健康因子决定了何时可以清算用户的抵押品，值越高，清算条件越严格
+    if let Some(min_health_factor) = params.min_health_factor {
+        config_account.min_health_factor = min_health_factor;
+    }
+    if let Some(liquidation_threshold) = params.liquidation_threshold {
+        config_account.liquidation_threshold = liquidation_threshold;
+    }
+    if let Some(liquidation_bonus) = params.liquidation_bonus {
+        config_account.liquidation_bonus = liquidation_bonus;
+    }
+    if let Some(stability_fee) = params.stability_fee {
+        config_account.debt.accrue(Clock::get()?.unix_timestamp)?;
+        config_account.debt.stability_fee = stability_fee;
+    }
+    if let Some(debt_ceiling) = params.debt_ceiling {
+        config_account.debt_ceiling = debt_ceiling;
+    }
+    if let Some(shutdown_wait) = params.shutdown_wait {
+        config_account.shutdown_wait = shutdown_wait;
+    }
+    config_account.validate()?;
 
+    emit!(ConfigUpdated {
+        old,
+        new: config_account.params(),
+    });
     // 打印更新后的配置信息到程序日志，便于调试
     msg!("Update Config Acccount:{:#?}", ctx.accounts.config_account);
     Ok(())
 }
+
+// 两步转移管理员权限：第一步由当前管理员指定新管理员
+pub fn process_transfer_config_authority(ctx: Context<UpdateConfig>, new_authority: Pubkey) -> Result<()> {
+    ctx.accounts.config_account.pending_authority = new_authority;
+    Ok(())
+}
+
+// 第二步由新管理员签名接受，之后旧管理员失去权限
+pub fn process_accept_config_authority(ctx: Context<AcceptConfigAuthority>) -> Result<()> {
+    let config_account = &mut ctx.accounts.config_account;
+    let old_authority = config_account.authority;
+    config_account.authority = config_account.pending_authority;
+    config_account.pending_authority = Pubkey::default();
+
+    emit!(ConfigAuthorityTransferred {
+        old_authority,
+        new_authority: config_account.authority,
+    });
+    Ok(())
+}
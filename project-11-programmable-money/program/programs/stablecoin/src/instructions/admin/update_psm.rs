@@ -0,0 +1,34 @@
+use crate::{error::CustomError, Config, Psm, MAX_PSM_FEE, SEED_CONFIG_ACCOUNT, SEED_PSM_ACCOUNT};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePsm<'info> {
+    // 协议管理员
+    pub authority: Signer<'info>,
+    // 配置账户 - 验证调用者是协议管理员
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority @ CustomError::Unauthorized
+    )]
+    pub config_account: Account<'info, Config>,
+    // 要修改的PSM账户
+    #[account(
+        mut,
+        seeds = [SEED_PSM_ACCOUNT, psm.stable_mint.as_ref()],
+        bump = psm.bump,
+    )]
+    pub psm: Account<'info, Psm>,
+}
+
+// 修改PSM的手续费和债务上限，只有管理员可以调用
+// 债务上限低于当前已铸造总量时只会阻止新的换入，不影响换出
+pub fn process_update_psm(ctx: Context<UpdatePsm>, fee: u64, debt_ceiling: u64) -> Result<()> {
+    require!(fee <= MAX_PSM_FEE, CustomError::InvalidConfigParams);
+
+    let psm = &mut ctx.accounts.psm;
+    psm.fee = fee;
+    psm.debt_ceiling = debt_ceiling;
+    msg!("Update PSM:{:#?}", psm);
+    Ok(())
+}
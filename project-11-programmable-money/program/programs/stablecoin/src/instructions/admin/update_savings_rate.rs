@@ -0,0 +1,58 @@
+use crate::{
+    drip_savings, error::CustomError, Config, Savings, MAX_SAVINGS_RATE, SEED_CONFIG_ACCOUNT,
+    SEED_SAVINGS_ACCOUNT, SEED_SAVINGS_VAULT_ACCOUNT, SEED_SURPLUS_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct UpdateSavingsRate<'info> {
+    // 协议管理员
+    pub authority: Signer<'info>,
+    // 配置账户 - 验证调用者是协议管理员，并为利息转账签名
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority @ CustomError::Unauthorized,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_ACCOUNT],
+        bump = savings_account.bump,
+    )]
+    pub savings_account: Box<Account<'info, Savings>>,
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_VAULT_ACCOUNT],
+        bump = savings_account.bump_vault,
+    )]
+    pub savings_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [SEED_SURPLUS_ACCOUNT],
+        bump,
+    )]
+    pub surplus_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// 修改储蓄利率。先按旧利率发放截至现在的利息，新利率只影响之后的时间。
+// 提高利率让持有稳定币更有吸引力（需求增加，价格上升），降低利率则相反，是调节锚定的工具之一。
+pub fn process_update_savings_rate(ctx: Context<UpdateSavingsRate>, savings_rate: u64) -> Result<()> {
+    require!(savings_rate <= MAX_SAVINGS_RATE, CustomError::InvalidConfigParams);
+
+    drip_savings(
+        &mut ctx.accounts.savings_account,
+        &ctx.accounts.savings_vault,
+        &ctx.accounts.surplus_account,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.config_account,
+        &ctx.accounts.token_program,
+    )?;
+    ctx.accounts.savings_account.savings_rate = savings_rate;
+    msg!("Savings rate set to {} bps", savings_rate);
+    Ok(())
+}
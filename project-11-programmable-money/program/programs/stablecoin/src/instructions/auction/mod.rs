@@ -0,0 +1,14 @@
+pub use start_auction::*;
+pub mod start_auction;
+pub use take_auction::*;
+pub mod take_auction;
+pub use restart_auction::*;
+pub mod restart_auction;
+pub use start_token_auction::*;
+pub mod start_token_auction;
+pub use take_token_auction::*;
+pub mod take_token_auction;
+pub use restart_token_auction::*;
+pub mod restart_token_auction;
+pub use utils::*;
+pub mod utils;
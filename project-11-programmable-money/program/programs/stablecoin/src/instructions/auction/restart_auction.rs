@@ -0,0 +1,85 @@
+use crate::{
+    auction_keeper_tip, auction_start_price, check_debt_ceiling, error::CustomError, get_usd_value,
+    mint_tokens_internal, Auction, Config, SEED_AUCTION_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct RestartAuction<'info> {
+    // 任何人都可以重新起拍，获得剩余 tab 的小费
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    // 价格更新账户 - 按最新预言机价格重新计算起拍价
+    pub price_update: Account<'info, PriceUpdateV2>,
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [SEED_AUCTION_ACCOUNT, auction_account.collateral_account.as_ref()],
+        bump = auction_account.bump,
+        constraint = auction_account.collateral_type == Pubkey::default() @ CustomError::AuctionCollateralMismatch
+    )]
+    pub auction_account: Box<Account<'info, Auction>>,
+    // 稳定币铸造账户 - 铸造小费
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 调用者的代币账户 - 接收小费，不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint_account,
+        associated_token::authority = caller,
+        associated_token::token_program = token_program
+    )]
+    pub caller_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// 价格降到 0 仍没有卖完的拍卖（参考 MakerDAO 的 clip.redo），从最新的 预言机价格 × 溢价 重新开始下降。
+// 调用者获得剩余 tab 的小费，小费计入 tab，铸造前检查全局债务上限。
+// 关停后仍然可以重新起拍，否则价格降到 0 的拍卖抵押品会永远锁在拍卖账户中；关停后不再铸造小费。
+pub fn process_restart_auction(ctx: Context<RestartAuction>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(ctx.accounts.auction_account.price(now) == 0, CustomError::AuctionNotExpired);
+
+    let price = get_usd_value(&LAMPORTS_PER_SOL, &ctx.accounts.price_update)?;
+    let tip = restart_auction_internal(
+        &mut ctx.accounts.auction_account,
+        price as u128,
+        now,
+        ctx.accounts.config_account.shutdown,
+    )?;
+
+    check_debt_ceiling(&ctx.accounts.config_account, &ctx.accounts.mint_account, tip)?;
+    if tip > 0 {
+        mint_tokens_internal(
+            &ctx.accounts.mint_account,
+            &ctx.accounts.caller_token_account,
+            &ctx.accounts.token_program,
+            ctx.accounts.config_account.bump_mint_account,
+            tip,
+        )?;
+    }
+    Ok(())
+}
+
+// 按新的预言机价格重置起拍价和起拍时间，小费计入 tab，返回小费（关停后为 0）
+pub fn restart_auction_internal(auction_account: &mut Auction, price: u128, now: i64, shutdown: bool) -> Result<u64> {
+    let tip = if shutdown { 0 } else { auction_keeper_tip(auction_account.tab) };
+    auction_account.tab = auction_account.tab.checked_add(tip).ok_or(CustomError::MathOverflow)?;
+    auction_account.start_price = auction_start_price(price)?;
+    auction_account.start_time = now;
+    Ok(tip)
+}
@@ -0,0 +1,72 @@
+use crate::{
+    check_debt_ceiling, error::CustomError, get_price_in_usd, mint_tokens_internal, restart_auction_internal, Auction,
+    CollateralType, Config, SEED_AUCTION_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct RestartTokenAuction<'info> {
+    // 任何人都可以重新起拍，获得剩余 tab 的小费
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    // 价格更新账户 - 按该类型最新的预言机价格重新计算起拍价
+    pub price_update: Account<'info, PriceUpdateV2>,
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 拍卖的抵押品类型 - 提供价格源
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    #[account(
+        mut,
+        seeds = [SEED_AUCTION_ACCOUNT, auction_account.collateral_account.as_ref()],
+        bump = auction_account.bump,
+        has_one = collateral_type @ CustomError::AuctionCollateralMismatch
+    )]
+    pub auction_account: Box<Account<'info, Auction>>,
+    // 稳定币铸造账户 - 铸造小费
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 调用者的代币账户 - 接收小费，不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint_account,
+        associated_token::authority = caller,
+        associated_token::token_program = token_program
+    )]
+    pub caller_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// restart_auction 的代币抵押品版本，起拍价按该类型的价格源计算
+pub fn process_restart_token_auction(ctx: Context<RestartTokenAuction>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(ctx.accounts.auction_account.price(now) == 0, CustomError::AuctionNotExpired);
+
+    let price = get_price_in_usd(&ctx.accounts.collateral_type.price_feed_id, &ctx.accounts.price_update)?;
+    let tip =
+        restart_auction_internal(&mut ctx.accounts.auction_account, price, now, ctx.accounts.config_account.shutdown)?;
+
+    check_debt_ceiling(&ctx.accounts.config_account, &ctx.accounts.mint_account, tip)?;
+    if tip > 0 {
+        mint_tokens_internal(
+            &ctx.accounts.mint_account,
+            &ctx.accounts.caller_token_account,
+            &ctx.accounts.token_program,
+            ctx.accounts.config_account.bump_mint_account,
+            tip,
+        )?;
+    }
+    Ok(())
+}
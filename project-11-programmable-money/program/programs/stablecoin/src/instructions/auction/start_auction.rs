@@ -0,0 +1,145 @@
+use crate::{
+    auction_keeper_tip, auction_start_price, calculate_health_factor, check_debt_ceiling, error::CustomError,
+    events::AuctionStarted, get_usd_value, mint_tokens_internal, withdraw_sol_internal, Auction, Collateral, Config,
+    SEED_AUCTION_ACCOUNT, SEED_COLLATERAL_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct StartAuction<'info> {
+    // keeper 账户 - 发现不健康仓位并发起拍卖的用户，支付拍卖账户租金并获得小费
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    // 价格更新账户 - 包含Pyth价格预言机数据，用于检查健康因子和计算起拍价
+    pub price_update: Account<'info, PriceUpdateV2>,
+    // 配置账户 - 存储协议全局参数的PDA账户
+    #[account(
+        mut, // 可变，因为要计提稳定费并减少债务总量
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 被清算的原生SOL仓位 - 种子验证确保不是代币抵押品仓位
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_account.depositor.as_ref()],
+        bump = collateral_account.bump,
+        has_one = sol_account
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    // 被清算用户的SOL存储账户 - 全部抵押品将转入拍卖账户
+    #[account(mut)]
+    pub sol_account: SystemAccount<'info>,
+    // 拍卖账户 - 每个仓位同时只能有一个拍卖，保管被没收的SOL
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + Auction::INIT_SPACE,
+        seeds = [SEED_AUCTION_ACCOUNT, collateral_account.key().as_ref()],
+        bump,
+    )]
+    pub auction_account: Box<Account<'info, Auction>>,
+    // 稳定币铸造账户 - 铸造 keeper 小费
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // keeper 的代币账户 - 接收小费，不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        associated_token::mint = mint_account,
+        associated_token::authority = keeper,
+        associated_token::token_program = token_program
+    )]
+    pub keeper_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+荷兰式拍卖清算的第一步（参考 MakerDAO Liquidations 2.0 的 dog.bark）。
+不健康仓位的全部SOL和全部债务转入拍卖账户，仓位清零，之后由竞拍者用稳定币分批买走抵押品。
+拍卖需要筹集的稳定币 tab = 债务 + keeper 小费，小费在发起时立即铸造给 keeper，
+由竞拍者销毁的稳定币覆盖，所以拍卖筹满 tab 时稳定币总量不变。
+起拍价 = 预言机价格 × AUCTION_PRICE_PREMIUM，随后线性下降，见 Auction::price。
+**/
+pub fn process_start_auction(ctx: Context<StartAuction>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.config_account.debt.accrue(now)?;
+
+    let health_factor = calculate_health_factor(
+        &ctx.accounts.collateral_account,
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+    )?;
+    require!(
+        health_factor < ctx.accounts.config_account.min_health_factor,
+        CustomError::AboveMinimumHealthFactor
+    );
+
+    // 全部债务从仓位和原生SOL的债务总量中移出，转为拍卖的 tab
+    let normalized_debt = ctx.accounts.collateral_account.normalized_debt;
+    let debt = ctx.accounts.config_account.debt.debt_amount(normalized_debt)?;
+    ctx.accounts.config_account.debt.remove_debt(normalized_debt, debt)?;
+    let tip = auction_keeper_tip(debt);
+    let tab = debt.checked_add(tip).ok_or(CustomError::MathOverflow)?;
+
+    // 起拍价：1 SOL 的美元价值 × 溢价
+    let price = get_usd_value(&LAMPORTS_PER_SOL, &ctx.accounts.price_update)?;
+    let start_price = auction_start_price(price as u128)?;
+
+    // 没收全部SOL抵押品，转入拍卖账户保管
+    let lot = ctx.accounts.sol_account.lamports();
+    withdraw_sol_internal(
+        &ctx.accounts.sol_account,
+        &ctx.accounts.auction_account.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.collateral_account.depositor,
+        ctx.accounts.collateral_account.bump_sol_account,
+        lot,
+    )?;
+
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    collateral_account.lamport_balance = 0;
+    collateral_account.normalized_debt = 0;
+
+    let auction_account = &mut ctx.accounts.auction_account;
+    auction_account.collateral_account = collateral_account.key();
+    auction_account.collateral_type = Pubkey::default();
+    auction_account.keeper = ctx.accounts.keeper.key();
+    auction_account.lot = lot;
+    auction_account.tab = tab;
+    auction_account.start_price = start_price;
+    auction_account.start_time = now;
+    auction_account.bump = ctx.bumps.auction_account;
+
+    // 小费是新铸造的稳定币，同样受全局债务上限约束
+    check_debt_ceiling(&ctx.accounts.config_account, &ctx.accounts.mint_account, tip)?;
+    if tip > 0 {
+        mint_tokens_internal(
+            &ctx.accounts.mint_account,
+            &ctx.accounts.keeper_token_account,
+            &ctx.accounts.token_program,
+            ctx.accounts.config_account.bump_mint_account,
+            tip,
+        )?;
+    }
+
+    emit!(AuctionStarted {
+        collateral_account: collateral_account.key(),
+        keeper: ctx.accounts.keeper.key(),
+        lot,
+        tab,
+        tip,
+        start_price,
+    });
+    Ok(())
+}
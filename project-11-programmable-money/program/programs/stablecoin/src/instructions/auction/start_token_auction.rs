@@ -0,0 +1,169 @@
+use crate::{
+    auction_keeper_tip, auction_start_price, calculate_token_health_factor, check_debt_ceiling, error::CustomError,
+    events::AuctionStarted, get_price_in_usd, mint_tokens_internal, withdraw_tokens_internal, Auction, Collateral,
+    CollateralType, Config, SEED_AUCTION_ACCOUNT, SEED_AUCTION_VAULT_ACCOUNT, SEED_COLLATERAL_ACCOUNT,
+    SEED_COLLATERAL_TYPE_ACCOUNT, SEED_CONFIG_ACCOUNT, SEED_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct StartTokenAuction<'info> {
+    // keeper 账户 - 发现不健康仓位并发起拍卖的用户，支付拍卖账户租金并获得小费
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    // 价格更新账户 - 包含该抵押品类型的Pyth价格数据，用于检查健康因子和计算起拍价
+    pub price_update: Account<'info, PriceUpdateV2>,
+    // 配置账户 - 提供最小健康因子
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 抵押品类型注册账户
+    #[account(
+        mut, // 可变，因为要计提稳定费并减少该类型的债务总量
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 被清算用户在该抵押品类型下的仓位
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_type.key().as_ref(), collateral_account.depositor.as_ref()],
+        bump = collateral_account.bump,
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    // 被清算仓位的代币存储账户 - 全部抵押品将转入拍卖的保管账户
+    #[account(
+        mut,
+        seeds = [SEED_VAULT_ACCOUNT, collateral_type.key().as_ref(), collateral_account.depositor.as_ref()],
+        bump = collateral_account.bump_sol_account,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 拍卖账户 - 每个仓位同时只能有一个拍卖
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + Auction::INIT_SPACE,
+        seeds = [SEED_AUCTION_ACCOUNT, collateral_account.key().as_ref()],
+        bump,
+    )]
+    pub auction_account: Box<Account<'info, Auction>>,
+    // 拍卖的保管代币账户 - 由拍卖账户PDA控制，保管被没收的抵押品代币
+    #[account(
+        init,
+        payer = keeper,
+        seeds = [SEED_AUCTION_VAULT_ACCOUNT, auction_account.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = auction_account,
+        token::token_program = collateral_token_program,
+    )]
+    pub auction_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 稳定币铸造账户 - 铸造 keeper 小费
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // keeper 的代币账户 - 接收小费，不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        associated_token::mint = mint_account,
+        associated_token::authority = keeper,
+        associated_token::token_program = token_program
+    )]
+    pub keeper_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+    // 抵押品代币所属的代币程序（SPL Token 或 Token2022）
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+start_auction 的代币抵押品版本：不健康仓位的全部代币和全部债务转入拍卖，仓位清零。
+抵押品保管在由拍卖账户控制的 [auction_vault, auction] 代币账户中，
+起拍价 = 该类型预言机价格（每个完整代币）× AUCTION_PRICE_PREMIUM。
+**/
+pub fn process_start_token_auction(ctx: Context<StartTokenAuction>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.collateral_type.debt.accrue(now)?;
+
+    let health_factor = calculate_token_health_factor(
+        &ctx.accounts.collateral_account,
+        &ctx.accounts.collateral_type,
+        &ctx.accounts.price_update,
+    )?;
+    require!(
+        health_factor < ctx.accounts.config_account.min_health_factor,
+        CustomError::AboveMinimumHealthFactor
+    );
+
+    // 全部债务从仓位和该类型的债务总量中移出，转为拍卖的 tab
+    let normalized_debt = ctx.accounts.collateral_account.normalized_debt;
+    let debt = ctx.accounts.collateral_type.debt.debt_amount(normalized_debt)?;
+    ctx.accounts.collateral_type.debt.remove_debt(normalized_debt, debt)?;
+    let tip = auction_keeper_tip(debt);
+    let tab = debt.checked_add(tip).ok_or(CustomError::MathOverflow)?;
+
+    let price = get_price_in_usd(&ctx.accounts.collateral_type.price_feed_id, &ctx.accounts.price_update)?;
+    let start_price = auction_start_price(price)?;
+
+    // 没收全部代币抵押品，转入拍卖的保管账户
+    let lot = ctx.accounts.vault.amount;
+    if lot > 0 {
+        withdraw_tokens_internal(
+            &ctx.accounts.vault,
+            &ctx.accounts.auction_vault,
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.collateral_token_program,
+            &ctx.accounts.collateral_account,
+            lot,
+        )?;
+    }
+
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    collateral_account.lamport_balance = 0;
+    collateral_account.normalized_debt = 0;
+
+    let auction_account = &mut ctx.accounts.auction_account;
+    auction_account.collateral_account = collateral_account.key();
+    auction_account.collateral_type = ctx.accounts.collateral_type.key();
+    auction_account.keeper = ctx.accounts.keeper.key();
+    auction_account.lot = lot;
+    auction_account.tab = tab;
+    auction_account.start_price = start_price;
+    auction_account.start_time = now;
+    auction_account.bump = ctx.bumps.auction_account;
+
+    // 小费是新铸造的稳定币，同样受全局债务上限约束
+    check_debt_ceiling(&ctx.accounts.config_account, &ctx.accounts.mint_account, tip)?;
+    if tip > 0 {
+        mint_tokens_internal(
+            &ctx.accounts.mint_account,
+            &ctx.accounts.keeper_token_account,
+            &ctx.accounts.token_program,
+            ctx.accounts.config_account.bump_mint_account,
+            tip,
+        )?;
+    }
+
+    emit!(AuctionStarted {
+        collateral_account: collateral_account.key(),
+        keeper: ctx.accounts.keeper.key(),
+        lot,
+        tab,
+        tip,
+        start_price,
+    });
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::{
+    burn_tokens_internal, error::CustomError, events::AuctionSettled, Auction, Collateral, Config,
+    SEED_AUCTION_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct TakeAuction<'info> {
+    // 竞拍者账户 - 销毁稳定币买入SOL
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused
+    )]
+    pub config_account: Account<'info, Config>,
+    // 拍卖账户 - 卖完或筹满后关闭，租金退还给 keeper
+    #[account(
+        mut,
+        seeds = [SEED_AUCTION_ACCOUNT, collateral_account.key().as_ref()],
+        bump = auction_account.bump,
+        has_one = collateral_account,
+        has_one = keeper,
+        constraint = auction_account.collateral_type == Pubkey::default() @ CustomError::AuctionCollateralMismatch
+    )]
+    pub auction_account: Account<'info, Auction>,
+    // 被清算的仓位 - 用于确定剩余抵押品的所有者
+    #[account(has_one = depositor)]
+    pub collateral_account: Account<'info, Collateral>,
+    // 仓位所有者的钱包 - 拍卖结束时接收剩余的SOL
+    #[account(mut)]
+    pub depositor: SystemAccount<'info>,
+    // 发起拍卖的 keeper - 拍卖结束时取回拍卖账户的租金
+    #[account(mut)]
+    pub keeper: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    // 竞拍者的代币账户 - 支付的稳定币从此账户销毁
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+/**
+按当前拍卖价格买入最多 max_collateral lamports 的SOL（参考 MakerDAO 的 clip.take）。
+价格高于 max_price（每个SOL的美元价格，9位小数）时失败，防止价格在交易确认前变化。
+买入的金额超过剩余 tab 时只按 tab 成交，多出的抵押品留在拍卖中。
+tab 筹满或抵押品卖完时拍卖结束：剩余SOL退还给仓位所有者，
+SOL卖完仍未筹满的 tab 成为协议坏账（记录在 AuctionSettled 事件中）。
+**/
+pub fn process_take_auction(ctx: Context<TakeAuction>, max_collateral: u64, max_price: u64) -> Result<()> {
+    let auction_account = &mut ctx.accounts.auction_account;
+    let price = auction_account.price(Clock::get()?.unix_timestamp);
+    require!(price > 0, CustomError::AuctionExpired);
+    require!(price <= max_price, CustomError::AuctionPriceTooHigh);
+
+    let (amount, cost) = auction_account.bid(max_collateral, price, LAMPORTS_PER_SOL);
+    require!(amount > 0 && cost > 0, CustomError::BidTooSmall);
+
+    msg!("*** AUCTION TAKE ***");
+    msg!("Price         : {:.9}", price as f64 / 1e9);
+    msg!("SOL Bought    : {:.9}", amount as f64 / 1e9);
+    msg!("Tokens Paid   : {:.9}", cost as f64 / 1e9);
+
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.bidder,
+        &ctx.accounts.token_program,
+        cost,
+    )?;
+
+    // 拍卖账户由本程序拥有，直接转出 lamports
+    auction_account.sub_lamports(amount)?;
+    ctx.accounts.bidder.add_lamports(amount)?;
+    auction_account.lot -= amount;
+    auction_account.tab -= cost;
+
+    if auction_account.tab == 0 || auction_account.lot == 0 {
+        let leftover = auction_account.lot;
+        if leftover > 0 {
+            auction_account.sub_lamports(leftover)?;
+            ctx.accounts.depositor.add_lamports(leftover)?;
+        }
+        emit!(AuctionSettled {
+            collateral_account: auction_account.collateral_account,
+            leftover,
+            bad_debt: auction_account.tab,
+        });
+        auction_account.close(ctx.accounts.keeper.to_account_info())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use crate::{
+    burn_tokens_internal, close_auction_vault_internal, error::CustomError, events::AuctionSettled,
+    withdraw_auction_tokens_internal, Auction, Collateral, CollateralType, Config, SEED_AUCTION_ACCOUNT,
+    SEED_AUCTION_VAULT_ACCOUNT, SEED_COLLATERAL_TYPE_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct TakeTokenAuction<'info> {
+    // 竞拍者账户 - 销毁稳定币买入抵押品代币
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 抵押品类型注册账户 - 提供抵押品的小数位数
+    #[account(
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 拍卖账户 - 卖完或筹满后关闭，租金退还给 keeper
+    #[account(
+        mut,
+        seeds = [SEED_AUCTION_ACCOUNT, collateral_account.key().as_ref()],
+        bump = auction_account.bump,
+        has_one = collateral_account,
+        has_one = collateral_type @ CustomError::AuctionCollateralMismatch,
+        has_one = keeper
+    )]
+    pub auction_account: Box<Account<'info, Auction>>,
+    // 拍卖的保管代币账户 - 拍卖结束时关闭
+    #[account(
+        mut,
+        seeds = [SEED_AUCTION_VAULT_ACCOUNT, auction_account.key().as_ref()],
+        bump,
+    )]
+    pub auction_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 被清算的仓位 - 用于确定剩余抵押品的所有者
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    // 仓位所有者的抵押品代币账户 - 拍卖结束时接收剩余的抵押品
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = collateral_account.depositor,
+        token::token_program = collateral_token_program,
+    )]
+    pub depositor_collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 竞拍者的抵押品代币账户 - 接收买入的抵押品
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = bidder,
+        token::token_program = collateral_token_program,
+    )]
+    pub bidder_collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 发起拍卖的 keeper - 拍卖结束时取回拍卖账户和保管账户的租金
+    #[account(mut)]
+    pub keeper: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 竞拍者的代币账户 - 支付的稳定币从此账户销毁
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+    // 抵押品代币所属的代币程序（SPL Token 或 Token2022）
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+take_auction 的代币抵押品版本：按当前拍卖价格买入最多 max_collateral 个最小单位的抵押品代币。
+max_price 为每个完整代币的美元价格（9位小数）。
+tab 筹满或抵押品卖完时拍卖结束：剩余代币退还给仓位所有者的代币账户，保管账户关闭，
+代币卖完仍未筹满的 tab 成为协议坏账（记录在 AuctionSettled 事件中）。
+**/
+pub fn process_take_token_auction(
+    ctx: Context<TakeTokenAuction>,
+    max_collateral: u64,
+    max_price: u64,
+) -> Result<()> {
+    let auction_account = &mut ctx.accounts.auction_account;
+    let price = auction_account.price(Clock::get()?.unix_timestamp);
+    require!(price > 0, CustomError::AuctionExpired);
+    require!(price <= max_price, CustomError::AuctionPriceTooHigh);
+
+    let unit = 10u64
+        .checked_pow(ctx.accounts.collateral_type.mint_decimals as u32)
+        .ok_or(CustomError::MathOverflow)?;
+    let (amount, cost) = auction_account.bid(max_collateral, price, unit);
+    require!(amount > 0 && cost > 0, CustomError::BidTooSmall);
+
+    msg!("*** AUCTION TAKE ***");
+    msg!("Price         : {:.9}", price as f64 / 1e9);
+    msg!("Tokens Bought : {}", amount);
+    msg!("Tokens Paid   : {:.9}", cost as f64 / 1e9);
+
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.bidder,
+        &ctx.accounts.token_program,
+        cost,
+    )?;
+    withdraw_auction_tokens_internal(
+        &ctx.accounts.auction_vault,
+        &ctx.accounts.bidder_collateral_account,
+        &ctx.accounts.collateral_mint,
+        &ctx.accounts.collateral_token_program,
+        auction_account,
+        amount,
+    )?;
+    auction_account.lot -= amount;
+    auction_account.tab -= cost;
+
+    if auction_account.tab == 0 || auction_account.lot == 0 {
+        // 按保管账户的实际余额退还，直接转入保管账户的代币不会阻止关闭
+        let leftover = ctx.accounts.auction_vault.amount - amount;
+        if leftover > 0 {
+            withdraw_auction_tokens_internal(
+                &ctx.accounts.auction_vault,
+                &ctx.accounts.depositor_collateral_account,
+                &ctx.accounts.collateral_mint,
+                &ctx.accounts.collateral_token_program,
+                auction_account,
+                leftover,
+            )?;
+        }
+        emit!(AuctionSettled {
+            collateral_account: auction_account.collateral_account,
+            leftover,
+            bad_debt: auction_account.tab,
+        });
+        close_auction_vault_internal(
+            &ctx.accounts.auction_vault,
+            &ctx.accounts.keeper.to_account_info(),
+            &ctx.accounts.collateral_token_program,
+            auction_account,
+        )?;
+        auction_account.close(ctx.accounts.keeper.to_account_info())?;
+    }
+    Ok(())
+}
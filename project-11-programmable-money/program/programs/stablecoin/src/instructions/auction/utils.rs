@@ -0,0 +1,60 @@
+use crate::{Auction, SEED_AUCTION_ACCOUNT};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+// 从代币拍卖的保管账户 [auction_vault, auction] 转出抵押品，由拍卖账户PDA签名
+pub fn withdraw_auction_tokens_internal<'info>(
+    auction_vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    auction_account: &Account<'info, Auction>,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_AUCTION_ACCOUNT,
+        auction_account.collateral_account.as_ref(),
+        &[auction_account.bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: auction_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: auction_account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+// 拍卖结束时关闭空的保管账户，租金退还给 keeper
+pub fn close_auction_vault_internal<'info>(
+    auction_vault: &InterfaceAccount<'info, TokenAccount>,
+    keeper: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    auction_account: &Account<'info, Auction>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_AUCTION_ACCOUNT,
+        auction_account.collateral_account.as_ref(),
+        &[auction_account.bump],
+    ]];
+
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: auction_vault.to_account_info(),
+            destination: keeper.to_account_info(),
+            authority: auction_account.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
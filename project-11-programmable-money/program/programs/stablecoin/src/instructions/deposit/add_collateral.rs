@@ -0,0 +1,44 @@
+use crate::{
+    deposit_sol_internal, error::CustomError, Collateral, Config, SEED_COLLATERAL_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AddCollateral<'info> {
+    // 存款人账户 - 仓位的所有者，支付存入的SOL
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    // 配置账户 - 暂停或关停后不能再改变仓位
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Account<'info, Config>,
+    // 抵押品账户 - 必须已经通过 deposit_collateral_and_mint 创建
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, depositor.key().as_ref()],
+        bump = collateral_account.bump,
+        has_one = sol_account
+    )]
+    pub collateral_account: Account<'info, Collateral>,
+    // SOL存储账户 - 接收存入的SOL
+    #[account(mut)]
+    pub sol_account: SystemAccount<'info>,
+    // 系统程序 - 处理SOL转账
+    pub system_program: Program<'info, System>,
+}
+
+// 只存入SOL抵押品，不铸造稳定币。抵押品增加只会提高健康因子，所以不需要价格和健康检查，
+// 适合在价格下跌时为仓位补充抵押品。
+pub fn process_add_collateral(ctx: Context<AddCollateral>, amount_collateral: u64) -> Result<()> {
+    ctx.accounts.collateral_account.lamport_balance = ctx.accounts.sol_account.lamports() + amount_collateral;
+    deposit_sol_internal(
+        &ctx.accounts.depositor,
+        &ctx.accounts.sol_account,
+        &ctx.accounts.system_program,
+        amount_collateral,
+    )
+}
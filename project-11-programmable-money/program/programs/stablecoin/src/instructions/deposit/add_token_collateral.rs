@@ -0,0 +1,65 @@
+use crate::{
+    deposit_tokens_internal, error::CustomError, Collateral, CollateralType, Config, SEED_COLLATERAL_ACCOUNT,
+    SEED_COLLATERAL_TYPE_ACCOUNT, SEED_CONFIG_ACCOUNT, SEED_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct AddTokenCollateral<'info> {
+    // 存款人账户 - 仓位的所有者，支付存入的抵押品代币
+    pub depositor: Signer<'info>,
+    // 配置账户 - 暂停或关停后不能再改变仓位
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 抵押品类型注册账户
+    #[account(
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 抵押品账户 - 必须已经通过 deposit_token_collateral_and_mint 创建
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_type.key().as_ref(), depositor.key().as_ref()],
+        bump = collateral_account.bump,
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    // 代币存储账户 - 接收存入的抵押品代币
+    #[account(
+        mut,
+        seeds = [SEED_VAULT_ACCOUNT, collateral_type.key().as_ref(), depositor.key().as_ref()],
+        bump = collateral_account.bump_sol_account,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 存款人的抵押品代币账户 - 抵押品从此账户转出
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = depositor,
+        token::token_program = collateral_token_program,
+    )]
+    pub depositor_collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 抵押品代币所属的代币程序（SPL Token 或 Token2022）
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+// add_collateral 的代币抵押品版本：只存入抵押品代币，不铸造稳定币，不需要价格和健康检查。
+pub fn process_add_token_collateral(ctx: Context<AddTokenCollateral>, amount_collateral: u64) -> Result<()> {
+    ctx.accounts.collateral_account.lamport_balance = ctx.accounts.vault.amount + amount_collateral;
+    deposit_tokens_internal(
+        &ctx.accounts.depositor_collateral_account,
+        &ctx.accounts.vault,
+        &ctx.accounts.collateral_mint,
+        &ctx.accounts.depositor,
+        &ctx.accounts.collateral_token_program,
+        amount_collateral,
+    )
+}
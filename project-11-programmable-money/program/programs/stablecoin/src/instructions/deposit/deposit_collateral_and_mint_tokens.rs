@@ -1,5 +1,5 @@
 use crate::{
-    check_health_factor, deposit_sol_internal, mint_tokens_internal, Collateral, Config,
+    error::CustomError,     check_debt_ceiling, check_health_factor, deposit_sol_internal, mint_tokens_internal, Collateral, Config,
     SEED_COLLATERAL_ACCOUNT, SEED_CONFIG_ACCOUNT, SEED_SOL_ACCOUNT,
 };
 use anchor_lang::prelude::*;
@@ -18,9 +18,12 @@ pub struct DepositCollateralAndMintTokens<'info> {
     // 配置账户 - 存储协议全局参数的PDA账户
     // 验证mint_account是否与配置中的一致
     #[account(
+        mut, // 可变，因为要计提稳定费并累计债务总量
         seeds = [SEED_CONFIG_ACCOUNT], // 使用 "config" 种子验证PDA
         bump = config_account.bump, // 使用存储的bump值验证
-        has_one = mint_account // 验证mint_account匹配
+        has_one = mint_account, // 验证mint_account匹配
+        constraint = !config_account.paused @ CustomError::Paused, // 暂停期间不能铸造或销毁稳定币
+        constraint = !config_account.shutdown @ CustomError::Shutdown // 紧急关停后只能按关停价格结算
     )]
     pub config_account: Box<Account<'info, Config>>,
     // 抵押品账户 - 跟踪用户抵押品状态的PDA账户
@@ -74,13 +77,18 @@ pub fn process_deposit_collateral_and_mint_tokens(
     amount_collateral: u64, // 要存入的SOL数量（lamports）
     amount_to_mint: u64,   // 要铸造的稳定币数量
 ) -> Result<()> {
+    // 先计提稳定费，再按最新的利率指数换算标准化债务
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.debt.accrue(Clock::get()?.unix_timestamp)?;
+    let normalized_debt = config_account.debt.add_debt(amount_to_mint)?;
+
     // 获取抵押品账户的可变引用
     let collateral_account = &mut ctx.accounts.collateral_account;
     // 更新抵押品账户中的余额记录
     // 计算存入后的总lamport余额
     collateral_account.lamport_balance = ctx.accounts.sol_account.lamports() + amount_collateral;
-    // 增加已铸造的稳定币数量
-    collateral_account.amount_minted += amount_to_mint;
+    // 增加标准化债务
+    collateral_account.normalized_debt += normalized_debt;
 
     // 如果是首次使用，初始化抵押品账户的基本信息
     if !collateral_account.is_initialized {
@@ -105,6 +113,8 @@ pub fn process_deposit_collateral_and_mint_tokens(
         &ctx.accounts.system_program,
         amount_collateral,
     )?;
+    // 铸造后的稳定币总供应量不能超过全局债务上限
+    check_debt_ceiling(&ctx.accounts.config_account, &ctx.accounts.mint_account, amount_to_mint)?;
     // 执行稳定币铸造 - 将新铸造的稳定币发送到用户的代币账户
     mint_tokens_internal(
         &ctx.accounts.mint_account,
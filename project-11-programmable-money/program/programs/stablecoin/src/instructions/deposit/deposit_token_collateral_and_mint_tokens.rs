@@ -0,0 +1,150 @@
+use crate::{
+    check_debt_ceiling, check_token_health_factor, deposit_tokens_internal, error::CustomError, mint_tokens_internal,
+    Collateral, CollateralType, Config, SEED_COLLATERAL_ACCOUNT, SEED_COLLATERAL_TYPE_ACCOUNT,
+    SEED_CONFIG_ACCOUNT, SEED_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct DepositTokenCollateralAndMintTokens<'info> {
+    // 存款人账户 - 支付交易费用并签署交易的用户钱包
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    // 配置账户 - 存储协议全局参数的PDA账户
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 抵押品类型注册账户 - 提供价格源、清算阈值和债务上限
+    #[account(
+        mut, // 可变，因为要累计该类型的债务总量
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 抵押品账户 - 用户在该抵押品类型下的仓位，每个类型一个
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = 8 + Collateral::INIT_SPACE,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_type.key().as_ref(), depositor.key().as_ref()],
+        bump,
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    // 代币存储账户 - 由自身PDA控制，保管用户存入的抵押品代币
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        seeds = [SEED_VAULT_ACCOUNT, collateral_type.key().as_ref(), depositor.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = vault,
+        token::token_program = collateral_token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 存款人的抵押品代币账户 - 抵押品从此账户转出
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = depositor,
+        token::token_program = collateral_token_program,
+    )]
+    pub depositor_collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 稳定币铸造账户 - 控制稳定币发行的PDA账户
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 价格更新账户 - 包含该抵押品类型的Pyth价格数据
+    pub price_update: Account<'info, PriceUpdateV2>,
+    // 用户代币账户 - 接收铸造稳定币的关联代币账户
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        associated_token::mint = mint_account,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // Token2022 程序 - 处理稳定币铸造
+    pub token_program: Program<'info, Token2022>,
+    // 抵押品代币所属的代币程序（SPL Token 或 Token2022）
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    // 关联代币程序 - 创建关联代币账户
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    // 系统程序 - 处理账户创建
+    pub system_program: Program<'info, System>,
+}
+
+/**
+与 deposit_collateral_and_mint 相同的流程，抵押品换成已注册的SPL代币：
+先更新仓位和该类型的债务总量（不能超过债务上限），再按该类型的价格源和清算阈值检查健康因子，
+最后把抵押品转入仓位的代币存储账户并铸造稳定币。
+**/
+pub fn process_deposit_token_collateral_and_mint_tokens(
+    ctx: Context<DepositTokenCollateralAndMintTokens>,
+    amount_collateral: u64, // 要存入的抵押品数量（代币基础单位）
+    amount_to_mint: u64,    // 要铸造的稳定币数量
+) -> Result<()> {
+    // 计提稳定费，累计该抵押品类型的债务总量，检查债务上限
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.debt.accrue(Clock::get()?.unix_timestamp)?;
+    let normalized_debt = collateral_type.debt.add_debt(amount_to_mint)?;
+    require!(
+        collateral_type.debt.total_debt()? <= collateral_type.debt_ceiling,
+        CustomError::DebtCeilingExceeded
+    );
+
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    // 存入后的抵押品余额
+    collateral_account.lamport_balance = ctx.accounts.vault.amount + amount_collateral;
+    // 增加标准化债务
+    collateral_account.normalized_debt += normalized_debt;
+
+    // 如果是首次使用，初始化抵押品账户的基本信息
+    if !collateral_account.is_initialized {
+        collateral_account.is_initialized = true;
+        collateral_account.depositor = ctx.accounts.depositor.key();
+        collateral_account.sol_account = ctx.accounts.vault.key();
+        collateral_account.token_account = ctx.accounts.token_account.key();
+        collateral_account.collateral_type = ctx.accounts.collateral_type.key();
+        collateral_account.bump = ctx.bumps.collateral_account;
+        collateral_account.bump_sol_account = ctx.bumps.vault;
+    }
+    // 检查操作后的健康因子是否满足最小要求
+    check_token_health_factor(
+        &ctx.accounts.collateral_account,
+        &ctx.accounts.collateral_type,
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+    )?;
+    // 抵押品代币从用户账户转入仓位的代币存储账户
+    deposit_tokens_internal(
+        &ctx.accounts.depositor_collateral_account,
+        &ctx.accounts.vault,
+        &ctx.accounts.collateral_mint,
+        &ctx.accounts.depositor,
+        &ctx.accounts.collateral_token_program,
+        amount_collateral,
+    )?;
+    // 铸造后的稳定币总供应量不能超过全局债务上限
+    check_debt_ceiling(&ctx.accounts.config_account, &ctx.accounts.mint_account, amount_to_mint)?;
+    // 铸造稳定币到用户的代币账户
+    mint_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.token_program,
+        ctx.accounts.config_account.bump_mint_account,
+        amount_to_mint,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::{
+    check_debt_ceiling, check_health_factor, error::CustomError, mint_tokens_internal, Collateral, Config, SEED_COLLATERAL_ACCOUNT,
+    SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct MintMore<'info> {
+    // 存款人账户 - 仓位的所有者
+    pub depositor: Signer<'info>,
+    // 价格更新账户 - 用于铸造后的健康因子检查
+    pub price_update: Account<'info, PriceUpdateV2>,
+    #[account(
+        mut, // 可变，因为要计提稳定费并累计债务总量
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, depositor.key().as_ref()],
+        bump = collateral_account.bump,
+        has_one = token_account
+    )]
+    pub collateral_account: Account<'info, Collateral>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    // 用户代币账户 - 接收新铸造的稳定币
+    #[account(mut)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// 用现有抵押品再铸造稳定币，不存入新的抵押品。铸造后健康因子必须满足最小要求。
+pub fn process_mint_more(ctx: Context<MintMore>, amount_to_mint: u64) -> Result<()> {
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.debt.accrue(Clock::get()?.unix_timestamp)?;
+    let normalized_debt = config_account.debt.add_debt(amount_to_mint)?;
+    ctx.accounts.collateral_account.normalized_debt += normalized_debt;
+
+    check_health_factor(
+        &ctx.accounts.collateral_account,
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+    )?;
+    // 铸造后的稳定币总供应量不能超过全局债务上限
+    check_debt_ceiling(&ctx.accounts.config_account, &ctx.accounts.mint_account, amount_to_mint)?;
+    mint_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.token_program,
+        ctx.accounts.config_account.bump_mint_account,
+        amount_to_mint,
+    )
+}
@@ -0,0 +1,74 @@
+use crate::{
+    check_debt_ceiling, check_token_health_factor, error::CustomError, mint_tokens_internal, Collateral,
+    CollateralType, Config, SEED_COLLATERAL_ACCOUNT, SEED_COLLATERAL_TYPE_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct MintMoreToken<'info> {
+    // 存款人账户 - 仓位的所有者
+    pub depositor: Signer<'info>,
+    // 价格更新账户 - 包含该抵押品类型的Pyth价格数据，用于铸造后的健康因子检查
+    pub price_update: Account<'info, PriceUpdateV2>,
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 抵押品类型注册账户
+    #[account(
+        mut, // 可变，因为要计提稳定费并累计该类型的债务总量
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_type.key().as_ref(), depositor.key().as_ref()],
+        bump = collateral_account.bump,
+        has_one = token_account
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 用户代币账户 - 接收新铸造的稳定币
+    #[account(mut)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// mint_more 的代币抵押品版本：用现有抵押品再铸造稳定币，不能超过该类型的债务上限，
+// 铸造后健康因子必须满足最小要求。
+pub fn process_mint_more_token(ctx: Context<MintMoreToken>, amount_to_mint: u64) -> Result<()> {
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.debt.accrue(Clock::get()?.unix_timestamp)?;
+    let normalized_debt = collateral_type.debt.add_debt(amount_to_mint)?;
+    require!(
+        collateral_type.debt.total_debt()? <= collateral_type.debt_ceiling,
+        CustomError::DebtCeilingExceeded
+    );
+    ctx.accounts.collateral_account.normalized_debt += normalized_debt;
+
+    check_token_health_factor(
+        &ctx.accounts.collateral_account,
+        &ctx.accounts.collateral_type,
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+    )?;
+    // 铸造后的稳定币总供应量不能超过全局债务上限
+    check_debt_ceiling(&ctx.accounts.config_account, &ctx.accounts.mint_account, amount_to_mint)?;
+    mint_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.token_program,
+        ctx.accounts.config_account.bump_mint_account,
+        amount_to_mint,
+    )
+}
@@ -1,4 +1,14 @@
 pub use deposit_collateral_and_mint_tokens::*;
 pub mod deposit_collateral_and_mint_tokens;
+pub use deposit_token_collateral_and_mint_tokens::*;
+pub mod deposit_token_collateral_and_mint_tokens;
 pub use utils::*;
 pub mod utils;
+pub use add_collateral::*;
+pub mod add_collateral;
+pub use mint_more::*;
+pub mod mint_more;
+pub use add_token_collateral::*;
+pub mod add_token_collateral;
+pub use mint_more_token::*;
+pub mod mint_more_token;
@@ -2,8 +2,8 @@ use crate::SEED_MINT_ACCOUNT;
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
 use anchor_spl::{
-    token_2022::{mint_to, MintTo},
-    token_interface::{Mint, Token2022, TokenAccount},
+    token_2022::{freeze_account, mint_to, thaw_account, FreezeAccount, MintTo, ThawAccount},
+    token_interface::{transfer_checked, Mint, Token2022, TokenAccount, TokenInterface, TransferChecked},
 };
 
 pub fn mint_tokens_internal<'info>(
@@ -29,6 +29,38 @@ pub fn mint_tokens_internal<'info>(
     )
 }
 
+// 冻结或解冻稳定币代币账户，由mint账户PDA（冻结权限）签名
+pub fn set_frozen_internal<'info>(
+    mint_account: &InterfaceAccount<'info, Mint>,
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Program<'info, Token2022>,
+    bump: u8,
+    frozen: bool,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[SEED_MINT_ACCOUNT, &[bump]]];
+    if frozen {
+        freeze_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            FreezeAccount {
+                account: token_account.to_account_info(),
+                mint: mint_account.to_account_info(),
+                authority: mint_account.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    } else {
+        thaw_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            ThawAccount {
+                account: token_account.to_account_info(),
+                mint: mint_account.to_account_info(),
+                authority: mint_account.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+}
+
 pub fn deposit_sol_internal<'info>(
     from: &Signer<'info>,
     to: &SystemAccount<'info>,
@@ -46,3 +78,26 @@ pub fn deposit_sol_internal<'info>(
         amount,
     )
 }
+
+pub fn deposit_tokens_internal<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: authority.to_account_info(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )
+}
@@ -4,5 +4,13 @@ pub use deposit::*;
 pub mod deposit;
 pub use withdraw::*;
 pub mod withdraw;
+pub use psm::*;
+pub mod psm;
+pub use auction::*;
+pub mod auction;
+pub use savings::*;
+pub mod savings;
+pub use shutdown::*;
+pub mod shutdown;
 pub use utils::*;
 pub mod utils;
@@ -0,0 +1,4 @@
+pub use psm_swap::*;
+pub mod psm_swap;
+pub use utils::*;
+pub mod utils;
@@ -0,0 +1,157 @@
+use crate::{
+    burn_tokens_internal, check_debt_ceiling, deposit_tokens_internal, error::CustomError, from_stablecoin_amount,
+    mint_tokens_internal, psm_fee, to_stablecoin_amount, withdraw_reserve_internal, Config, Psm,
+    SEED_CONFIG_ACCOUNT, SEED_PSM_ACCOUNT, SEED_PSM_RESERVE_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct PsmSwap<'info> {
+    // 用户账户 - 签署交易并在两种稳定币之间兑换
+    #[account(mut)]
+    pub user: Signer<'info>,
+    // 配置账户 - 验证mint_account是否与配置中的一致
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // PSM账户 - 提供手续费和债务上限
+    #[account(
+        mut,
+        seeds = [SEED_PSM_ACCOUNT, stable_mint.key().as_ref()],
+        bump = psm.bump,
+    )]
+    pub psm: Box<Account<'info, Psm>>,
+    // 外部稳定币的mint
+    pub stable_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 储备账户 - 保管外部稳定币
+    #[account(
+        mut,
+        seeds = [SEED_PSM_RESERVE_ACCOUNT, stable_mint.key().as_ref()],
+        bump = psm.bump_reserve,
+    )]
+    pub reserve: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 用户的外部稳定币账户
+    #[account(
+        mut,
+        token::mint = stable_mint,
+        token::authority = user,
+        token::token_program = stable_token_program,
+    )]
+    pub user_stable_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 稳定币铸造账户
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 用户的稳定币关联代币账户
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_account,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // Token2022 程序 - 处理稳定币铸造和销毁
+    pub token_program: Program<'info, Token2022>,
+    // 外部稳定币所属的代币程序（SPL Token 或 Token2022）
+    pub stable_token_program: Interface<'info, TokenInterface>,
+    // 关联代币程序 - 创建关联代币账户
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    // 系统程序 - 处理账户创建
+    pub system_program: Program<'info, System>,
+}
+
+/**
+锚定稳定模块（PSM）：在外部稳定币和本稳定币之间按 1:1 兑换，收取 psm.fee 基点的手续费。
+本稳定币价格高于 1 美元时，套利者换入外部稳定币铸造本稳定币卖出；低于 1 美元时买入本稳定币换出外部稳定币，
+从而把价格维持在锚定值附近。手续费留在储备中，储备始终不少于通过PSM铸造的稳定币。
+换入：用户存入 amount 个外部稳定币，获得扣除手续费后等值的本稳定币。
+外部稳定币的小数位多于本稳定币时，只转入能按 1:1 整除换算的部分，余数留在用户账户；
+外部稳定币带转账手续费扩展时，按储备账户实际收到的数量铸造。
+total_minted 按铸造给用户的净额累计，psm_swap_out 按同样的净额减少。
+**/
+pub fn process_psm_swap_in(ctx: Context<PsmSwap>, amount: u64) -> Result<()> {
+    let stable_decimals = ctx.accounts.psm.stable_decimals;
+    let amount = from_stablecoin_amount(to_stablecoin_amount(amount, stable_decimals)?, stable_decimals)?;
+    require!(amount > 0, CustomError::SwapAmountTooSmall);
+
+    // 外部稳定币转入储备账户
+    let reserve_before = ctx.accounts.reserve.amount;
+    deposit_tokens_internal(
+        &ctx.accounts.user_stable_account,
+        &ctx.accounts.reserve,
+        &ctx.accounts.stable_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.stable_token_program,
+        amount,
+    )?;
+    ctx.accounts.reserve.reload()?;
+    let received = ctx.accounts.reserve.amount - reserve_before;
+
+    let psm = &mut ctx.accounts.psm;
+    let gross = to_stablecoin_amount(received, psm.stable_decimals)?;
+    let amount_to_mint = gross - psm_fee(gross, psm.fee);
+    require!(amount_to_mint > 0, CustomError::SwapAmountTooSmall);
+
+    // 检查PSM的债务上限
+    psm.total_minted = psm
+        .total_minted
+        .checked_add(amount_to_mint)
+        .ok_or(CustomError::MathOverflow)?;
+    require!(psm.total_minted <= psm.debt_ceiling, CustomError::DebtCeilingExceeded);
+
+    // 铸造后的稳定币总供应量不能超过全局债务上限
+    check_debt_ceiling(&ctx.accounts.config_account, &ctx.accounts.mint_account, amount_to_mint)?;
+    // 铸造本稳定币给用户
+    mint_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.token_program,
+        ctx.accounts.config_account.bump_mint_account,
+        amount_to_mint,
+    )?;
+    msg!("PSM swap in: {} -> {:.9}", received, amount_to_mint as f64 / 1e9);
+    Ok(())
+}
+
+// 换出：用户销毁 amount 个本稳定币，获得扣除手续费后等值的外部稳定币
+// total_minted 减少扣除手续费后的净额，与换入时累计的口径一致
+pub fn process_psm_swap_out(ctx: Context<PsmSwap>, amount: u64) -> Result<()> {
+    let psm = &mut ctx.accounts.psm;
+    let net = amount - psm_fee(amount, psm.fee);
+    let amount_out = from_stablecoin_amount(net, psm.stable_decimals)?;
+    require!(amount_out > 0, CustomError::SwapAmountTooSmall);
+    require!(
+        amount_out <= ctx.accounts.reserve.amount,
+        CustomError::InsufficientPsmReserve
+    );
+    psm.total_minted = psm.total_minted.saturating_sub(net);
+
+    // 从用户账户销毁本稳定币
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+    // 储备账户转出外部稳定币给用户
+    withdraw_reserve_internal(
+        &ctx.accounts.reserve,
+        &ctx.accounts.user_stable_account,
+        &ctx.accounts.stable_mint,
+        &ctx.accounts.stable_token_program,
+        &ctx.accounts.psm,
+        amount_out,
+    )?;
+    msg!("PSM swap out: {:.9} -> {}", amount as f64 / 1e9, amount_out);
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::{error::CustomError, Psm, BPS_DENOMINATOR, MINT_DECIMALS, SEED_PSM_RESERVE_ACCOUNT};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+// 外部稳定币数量换算为本稳定币数量（按 1:1 价值，只调整小数位，向下取整）
+// Example: 1 USDC (6 decimals) = 1_000_000 -> 1_000_000_000 (9 decimals)
+pub fn to_stablecoin_amount(amount: u64, stable_decimals: u8) -> Result<u64> {
+    let amount = if stable_decimals <= MINT_DECIMALS {
+        amount as u128 * 10u128.pow((MINT_DECIMALS - stable_decimals) as u32)
+    } else {
+        amount as u128 / 10u128.pow((stable_decimals - MINT_DECIMALS) as u32)
+    };
+    u64::try_from(amount).map_err(|_| CustomError::MathOverflow.into())
+}
+
+// 本稳定币数量换算为外部稳定币数量（向下取整）
+pub fn from_stablecoin_amount(amount: u64, stable_decimals: u8) -> Result<u64> {
+    let amount = if stable_decimals <= MINT_DECIMALS {
+        amount as u128 / 10u128.pow((MINT_DECIMALS - stable_decimals) as u32)
+    } else {
+        amount as u128 * 10u128.pow((stable_decimals - MINT_DECIMALS) as u32)
+    };
+    u64::try_from(amount).map_err(|_| CustomError::MathOverflow.into())
+}
+
+// PSM手续费（向上取整，对协议有利）
+pub fn psm_fee(amount: u64, fee: u64) -> u64 {
+    (amount as u128 * fee as u128).div_ceil(BPS_DENOMINATOR) as u64
+}
+
+// 从储备账户转出外部稳定币，由储备账户PDA签名
+pub fn withdraw_reserve_internal<'info>(
+    reserve: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    psm: &Psm,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_PSM_RESERVE_ACCOUNT,
+        psm.stable_mint.as_ref(),
+        &[psm.bump_reserve],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: reserve.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: reserve.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_conversion_round_trips() {
+        // 6 位小数：放大后再缩小没有损失
+        assert_eq!(to_stablecoin_amount(1_000_001, 6).unwrap(), 1_000_001_000);
+        assert_eq!(from_stablecoin_amount(1_000_001_000, 6).unwrap(), 1_000_001);
+        // 12 位小数：换入前截断到能整除的部分，余数 999 不会被换算丢掉
+        let amount = 1_000_000_000_999;
+        let used = from_stablecoin_amount(to_stablecoin_amount(amount, 12).unwrap(), 12).unwrap();
+        assert_eq!(used, 1_000_000_000_000);
+        assert_eq!(to_stablecoin_amount(used, 12).unwrap(), 1_000_000_000);
+    }
+
+    #[test]
+    fn fee_rounds_up() {
+        assert_eq!(psm_fee(1_000_000_000, 10), 1_000_000);
+        assert_eq!(psm_fee(1, 10), 1);
+        assert_eq!(psm_fee(1_000, 0), 0);
+    }
+}
@@ -0,0 +1,6 @@
+pub use savings_deposit::*;
+pub mod savings_deposit;
+pub use savings_withdraw::*;
+pub mod savings_withdraw;
+pub use utils::*;
+pub mod utils;
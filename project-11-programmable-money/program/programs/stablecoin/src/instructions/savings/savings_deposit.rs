@@ -0,0 +1,111 @@
+use crate::{
+    drip_savings, error::CustomError, transfer_stablecoin_internal, Config, Savings, SavingsPosition,
+    SEED_CONFIG_ACCOUNT, SEED_SAVINGS_ACCOUNT, SEED_SAVINGS_POSITION_ACCOUNT, SEED_SAVINGS_VAULT_ACCOUNT,
+    SEED_SURPLUS_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SavingsDeposit<'info> {
+    // 储户账户 - 存入稳定币换取储蓄份额，首次存入时支付份额账户的租金
+    #[account(mut)]
+    pub user: Signer<'info>,
+    // 配置账户 - 盈余账户的所有者，为利息转账签名
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 储蓄模块账户 - 记录储蓄利率和份额总量
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_ACCOUNT],
+        bump = savings_account.bump,
+    )]
+    pub savings_account: Box<Account<'info, Savings>>,
+    // 储蓄金库 - 保管储户的稳定币和已发放的利息
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_VAULT_ACCOUNT],
+        bump = savings_account.bump_vault,
+    )]
+    pub savings_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 协议盈余账户 - 利息的来源
+    #[account(
+        mut,
+        seeds = [SEED_SURPLUS_ACCOUNT],
+        bump,
+    )]
+    pub surplus_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 用户的储蓄份额账户 - 不存在时初始化
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + SavingsPosition::INIT_SPACE,
+        seeds = [SEED_SAVINGS_POSITION_ACCOUNT, user.key().as_ref()],
+        bump,
+    )]
+    pub savings_position: Box<Account<'info, SavingsPosition>>,
+    // 稳定币铸造账户
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 用户的稳定币账户 - 存入的稳定币从此账户转出
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // Token2022 程序 - 处理稳定币转账
+    pub token_program: Program<'info, Token2022>,
+    // 系统程序 - 处理账户创建
+    pub system_program: Program<'info, System>,
+}
+
+/**
+存入稳定币换取储蓄份额。
+先发放截至现在的利息，再按 total_assets / 份额总量 的兑换率计算份额（向下取整），
+所以新储户不会分到存入之前的利息。
+**/
+pub fn process_savings_deposit(ctx: Context<SavingsDeposit>, amount: u64) -> Result<()> {
+    drip_savings(
+        &mut ctx.accounts.savings_account,
+        &ctx.accounts.savings_vault,
+        &ctx.accounts.surplus_account,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.config_account,
+        &ctx.accounts.token_program,
+    )?;
+
+    let shares = ctx.accounts.savings_account.shares_for_amount(amount)?;
+    require!(shares > 0, CustomError::DepositTooSmall);
+
+    transfer_stablecoin_internal(
+        &ctx.accounts.token_account,
+        &ctx.accounts.savings_vault,
+        &ctx.accounts.mint_account,
+        ctx.accounts.user.to_account_info(),
+        &ctx.accounts.token_program,
+        &[],
+        amount,
+    )?;
+
+    let savings_account = &mut ctx.accounts.savings_account;
+    savings_account.total_shares = savings_account
+        .total_shares
+        .checked_add(shares)
+        .ok_or(CustomError::MathOverflow)?;
+    savings_account.total_assets = savings_account
+        .total_assets
+        .checked_add(amount)
+        .ok_or(CustomError::MathOverflow)?;
+
+    let savings_position = &mut ctx.accounts.savings_position;
+    savings_position.owner = ctx.accounts.user.key();
+    savings_position.shares += shares;
+    savings_position.bump = ctx.bumps.savings_position;
+    msg!("Deposited {:.9} for {} shares", amount as f64 / 1e9, shares);
+    Ok(())
+}
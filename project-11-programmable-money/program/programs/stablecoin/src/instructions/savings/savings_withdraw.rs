@@ -0,0 +1,107 @@
+use crate::{
+    drip_savings, error::CustomError, transfer_stablecoin_internal, Config, Savings, SavingsPosition,
+    SEED_CONFIG_ACCOUNT, SEED_SAVINGS_ACCOUNT, SEED_SAVINGS_POSITION_ACCOUNT, SEED_SAVINGS_VAULT_ACCOUNT,
+    SEED_SURPLUS_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct SavingsWithdraw<'info> {
+    // 储户账户 - 赎回份额取回稳定币
+    #[account(mut)]
+    pub user: Signer<'info>,
+    // 配置账户 - 盈余账户的所有者，为利息转账签名
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 储蓄模块账户 - 也是储蓄金库的所有者，为转出签名
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_ACCOUNT],
+        bump = savings_account.bump,
+    )]
+    pub savings_account: Box<Account<'info, Savings>>,
+    // 储蓄金库
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_VAULT_ACCOUNT],
+        bump = savings_account.bump_vault,
+    )]
+    pub savings_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 协议盈余账户 - 利息的来源
+    #[account(
+        mut,
+        seeds = [SEED_SURPLUS_ACCOUNT],
+        bump,
+    )]
+    pub surplus_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 用户的储蓄份额账户
+    #[account(
+        mut,
+        seeds = [SEED_SAVINGS_POSITION_ACCOUNT, user.key().as_ref()],
+        bump = savings_position.bump,
+    )]
+    pub savings_position: Box<Account<'info, SavingsPosition>>,
+    // 稳定币铸造账户
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 用户的稳定币关联代币账户 - 接收赎回的稳定币，不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_account,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // Token2022 程序 - 处理稳定币转账
+    pub token_program: Program<'info, Token2022>,
+    // 关联代币程序 - 创建关联代币账户
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    // 系统程序 - 处理账户创建
+    pub system_program: Program<'info, System>,
+}
+
+/**
+赎回储蓄份额。先发放截至现在的利息，再按 total_assets / 份额总量 的兑换率换算为稳定币（向下取整）。
+**/
+pub fn process_savings_withdraw(ctx: Context<SavingsWithdraw>, shares: u64) -> Result<()> {
+    require!(
+        shares <= ctx.accounts.savings_position.shares,
+        CustomError::InsufficientShares
+    );
+    drip_savings(
+        &mut ctx.accounts.savings_account,
+        &ctx.accounts.savings_vault,
+        &ctx.accounts.surplus_account,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.config_account,
+        &ctx.accounts.token_program,
+    )?;
+
+    let savings_account = &mut ctx.accounts.savings_account;
+    let amount = savings_account.amount_for_shares(shares)?;
+    savings_account.total_shares -= shares;
+    savings_account.total_assets -= amount;
+    ctx.accounts.savings_position.shares -= shares;
+
+    if amount > 0 {
+        transfer_stablecoin_internal(
+            &ctx.accounts.savings_vault,
+            &ctx.accounts.token_account,
+            &ctx.accounts.mint_account,
+            ctx.accounts.savings_account.to_account_info(),
+            &ctx.accounts.token_program,
+            &[&[SEED_SAVINGS_ACCOUNT, &[ctx.accounts.savings_account.bump]]],
+            amount,
+        )?;
+    }
+    msg!("Redeemed {} shares for {:.9}", shares, amount as f64 / 1e9);
+    Ok(())
+}
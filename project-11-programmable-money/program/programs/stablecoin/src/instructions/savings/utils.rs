@@ -0,0 +1,65 @@
+use crate::{error::CustomError, Config, Savings, SEED_CONFIG_ACCOUNT};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, Token2022, TokenAccount, TransferChecked};
+
+// 转账稳定币，signer_seeds 为空时由 authority 直接签名，否则由 authority PDA 签名
+pub fn transfer_stablecoin_internal<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: AccountInfo<'info>,
+    token_program: &Program<'info, Token2022>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+// 按储蓄利率把上次发放以来的利息从盈余账户转入储蓄金库（参考 MakerDAO 的 pot.drip）。
+// 利息只能来自盈余账户，盈余不足时只发放现有的盈余，金库里的稳定币始终足额覆盖所有份额。
+// 之后 total_assets 增加而份额总量不变，每份额可赎回的稳定币随之增长。
+pub fn drip_savings<'info>(
+    savings: &mut Account<'info, Savings>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    surplus: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    config: &Account<'info, Config>,
+    token_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let interest = if savings.total_shares == 0 { 0 } else { savings.interest(now)? };
+    savings.last_accrued = now;
+
+    let amount = interest.min(surplus.amount);
+    if amount == 0 {
+        return Ok(());
+    }
+    transfer_stablecoin_internal(
+        surplus,
+        vault,
+        mint,
+        config.to_account_info(),
+        token_program,
+        &[&[SEED_CONFIG_ACCOUNT, &[config.bump]]],
+        amount,
+    )?;
+    savings.total_assets = savings
+        .total_assets
+        .checked_add(amount)
+        .ok_or(CustomError::MathOverflow)?;
+    msg!("Dripped {:.9} savings interest from surplus", amount as f64 / 1e9);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::{
+    error::CustomError, events::ShutdownStarted, get_usd_value, Config, Shutdown, SEED_CONFIG_ACCOUNT,
+    SEED_SHUTDOWN_ACCOUNT,
+};
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct EmergencyShutdown<'info> {
+    // 协议管理员 - 只有管理员可以紧急关停，支付关停账户的租金
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // 配置账户 - 标记为已关停，之后所有铸造和仓位操作都会失败
+    #[account(
+        mut,
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = authority @ CustomError::Unauthorized,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 关停账户 - 记录关停价格，同时作为原生SOL的结算池
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Shutdown::INIT_SPACE,
+        seeds = [SEED_SHUTDOWN_ACCOUNT],
+        bump,
+    )]
+    pub shutdown_account: Box<Account<'info, Shutdown>>,
+    // 价格更新账户 - 记录关停时的SOL价格
+    pub price_update: Account<'info, PriceUpdateV2>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+紧急关停（参考 MakerDAO 的 end.cage）。关停不可撤销：
+原生SOL仓位的稳定费计提到现在为止，按当前预言机价格记录SOL的关停价格，
+之后铸造、仓位操作、清算、PSM兑换都会失败，只能按关停价格结算仓位并兑付稳定币，流程见 Shutdown。
+进行中的拍卖不受影响，竞拍者仍然可以买入抵押品，价格降到 0 后仍然可以重新起拍（不再铸造小费）。
+**/
+pub fn process_emergency_shutdown(ctx: Context<EmergencyShutdown>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.debt.accrue(now)?;
+    config_account.shutdown = true;
+
+    let sol_price = get_usd_value(&LAMPORTS_PER_SOL, &ctx.accounts.price_update)?;
+    let sol_debt = config_account.debt.total_debt()?;
+    **ctx.accounts.shutdown_account = Shutdown {
+        time: now,
+        sol_price,
+        sol_debt,
+        sol_gap: 0,
+        finalized: false,
+        supply: 0,
+        sol_fix: 0,
+        bump: ctx.bumps.shutdown_account,
+    };
+
+    emit!(ShutdownStarted {
+        time: now,
+        sol_price,
+        sol_debt,
+    });
+    Ok(())
+}
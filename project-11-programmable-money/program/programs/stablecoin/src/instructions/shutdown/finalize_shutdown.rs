@@ -0,0 +1,108 @@
+use crate::{
+    collateral_for_debt, error::CustomError, events::ShutdownFinalized, set_frozen_internal, shutdown_fix, Config,
+    Shutdown, SEED_CONFIG_ACCOUNT, SEED_SHUTDOWN_ACCOUNT, SEED_SURPLUS_ACCOUNT,
+};
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use anchor_spl::{
+    token_2022::{burn, Burn},
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct FinalizeShutdown<'info> {
+    // 调用者 - 盈余账户不存在时支付租金，任何人都可以调用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // 配置账户 - 盈余账户的所有者，为销毁签名
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = config_account.shutdown @ CustomError::NotShutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [SEED_SHUTDOWN_ACCOUNT],
+        bump = shutdown_account.bump,
+        constraint = !shutdown_account.finalized @ CustomError::ShutdownFinalized
+    )]
+    pub shutdown_account: Box<Account<'info, Shutdown>>,
+    // 稳定币铸造账户 - 读取销毁盈余后的总供应量
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 协议盈余账户 - 余额在这里销毁，先于持有者承担损失
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [SEED_SURPLUS_ACCOUNT],
+        bump,
+        token::mint = mint_account,
+        token::authority = config_account,
+        token::token_program = token_program,
+    )]
+    pub surplus_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+关停等待期结束后固定兑付比例（参考 MakerDAO 的 end.thaw 和 end.flow）：
+先销毁协议盈余账户中的稳定币，剩余的总供应量就是可以兑付抵押品的稳定币总量 supply。
+原生SOL结算池应有的抵押品 = 关停时的债务按关停价格换算 − 缺口，每个稳定币兑付 抵押品 / supply。
+代币抵押品类型和PSM的兑付比例在各自第一次兑付时按同样的方式计算。
+等待期内 keeper 应结算所有资不抵债的仓位，之后结算的缺口不再计入兑付比例。
+**/
+pub fn process_finalize_shutdown(ctx: Context<FinalizeShutdown>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= ctx
+            .accounts
+            .shutdown_account
+            .time
+            .saturating_add(ctx.accounts.config_account.shutdown_wait),
+        CustomError::ShutdownWaitNotElapsed
+    );
+
+    let burned_surplus = ctx.accounts.surplus_account.amount;
+    if burned_surplus > 0 {
+        // 默认冻结模式下盈余账户可能还是冻结的，由协议自己解冻
+        if ctx.accounts.surplus_account.is_frozen() {
+            set_frozen_internal(
+                &ctx.accounts.mint_account,
+                &ctx.accounts.surplus_account,
+                &ctx.accounts.token_program,
+                ctx.accounts.config_account.bump_mint_account,
+                false,
+            )?;
+        }
+        burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.mint_account.to_account_info(),
+                    from: ctx.accounts.surplus_account.to_account_info(),
+                    authority: ctx.accounts.config_account.to_account_info(),
+                },
+                &[&[SEED_CONFIG_ACCOUNT, &[ctx.accounts.config_account.bump]]],
+            ),
+            burned_surplus,
+        )?;
+        ctx.accounts.mint_account.reload()?;
+    }
+
+    let supply = ctx.accounts.mint_account.supply;
+    let shutdown_account = &mut ctx.accounts.shutdown_account;
+    let collateral = collateral_for_debt(shutdown_account.sol_debt, shutdown_account.sol_price, LAMPORTS_PER_SOL, false)?
+        .saturating_sub(shutdown_account.sol_gap);
+    shutdown_account.sol_fix = shutdown_fix(collateral, supply);
+    shutdown_account.supply = supply;
+    shutdown_account.finalized = true;
+
+    emit!(ShutdownFinalized {
+        supply,
+        burned_surplus,
+        sol_fix: shutdown_account.sol_fix,
+    });
+    Ok(())
+}
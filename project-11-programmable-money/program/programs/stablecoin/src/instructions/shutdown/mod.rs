@@ -0,0 +1,16 @@
+pub use emergency_shutdown::*;
+pub mod emergency_shutdown;
+pub use record_shutdown_price::*;
+pub mod record_shutdown_price;
+pub use settle_vault::*;
+pub mod settle_vault;
+pub use settle_token_vault::*;
+pub mod settle_token_vault;
+pub use finalize_shutdown::*;
+pub mod finalize_shutdown;
+pub use shutdown_lock::*;
+pub mod shutdown_lock;
+pub use shutdown_redeem::*;
+pub mod shutdown_redeem;
+pub use utils::*;
+pub mod utils;
@@ -0,0 +1,68 @@
+use crate::{
+    error::CustomError, get_price_in_usd, CollateralType, Config, Shutdown, SEED_COLLATERAL_TYPE_ACCOUNT,
+    SEED_CONFIG_ACCOUNT, SEED_SHUTDOWN_ACCOUNT, SEED_SHUTDOWN_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct RecordShutdownPrice<'info> {
+    // 调用者 - 支付结算池的租金，任何人都可以调用
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        constraint = config_account.shutdown @ CustomError::NotShutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 关停账户 - 提供关停时间
+    #[account(
+        seeds = [SEED_SHUTDOWN_ACCOUNT],
+        bump = shutdown_account.bump,
+    )]
+    pub shutdown_account: Box<Account<'info, Shutdown>>,
+    // 抵押品类型注册账户 - 记录关停价格
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 该类型的结算池 - 由自身PDA控制，只能创建一次，所以每个类型只能记录一次价格
+    #[account(
+        init,
+        payer = payer,
+        seeds = [SEED_SHUTDOWN_VAULT_ACCOUNT, collateral_type.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = shutdown_vault,
+        token::token_program = collateral_token_program,
+    )]
+    pub shutdown_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 价格更新账户 - 该抵押品类型的Pyth价格数据
+    pub price_update: Account<'info, PriceUpdateV2>,
+    // 抵押品代币所属的代币程序（SPL Token 或 Token2022）
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+关停后为一个代币抵押品类型记录关停价格（参考 MakerDAO 的 end.cage(ilk)），并创建该类型的结算池。
+该类型的稳定费计提到关停时间为止。Pyth 价格有时效限制，关停后应尽快为每个类型调用（可以与 emergency_shutdown 放在同一笔交易中）。
+**/
+pub fn process_record_shutdown_price(ctx: Context<RecordShutdownPrice>) -> Result<()> {
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.debt.accrue(ctx.accounts.shutdown_account.time)?;
+    let price = get_price_in_usd(&collateral_type.price_feed_id, &ctx.accounts.price_update)?;
+    collateral_type.shutdown_price = u64::try_from(price).map_err(|_| CustomError::MathOverflow)?;
+    msg!(
+        "Shutdown price for {} : {:.9}",
+        collateral_type.mint,
+        collateral_type.shutdown_price as f64 / 1e9
+    );
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::{
+    collateral_for_debt, error::CustomError, events::VaultSettled, withdraw_tokens_internal, Collateral,
+    CollateralType, SEED_COLLATERAL_ACCOUNT, SEED_COLLATERAL_TYPE_ACCOUNT, SEED_SHUTDOWN_VAULT_ACCOUNT,
+    SEED_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct SettleTokenVault<'info> {
+    // 调用者 - 任何人都可以结算任何仓位
+    pub caller: Signer<'info>,
+    // 抵押品类型注册账户 - 必须已经记录关停价格（只有关停后才能记录）
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+        constraint = collateral_type.shutdown_price > 0 @ CustomError::ShutdownPriceNotRecorded
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 被结算的代币抵押品仓位
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_type.key().as_ref(), collateral_account.depositor.as_ref()],
+        bump = collateral_account.bump,
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    // 仓位的代币存储账户
+    #[account(
+        mut,
+        seeds = [SEED_VAULT_ACCOUNT, collateral_type.key().as_ref(), collateral_account.depositor.as_ref()],
+        bump = collateral_account.bump_sol_account,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 该类型的结算池
+    #[account(
+        mut,
+        seeds = [SEED_SHUTDOWN_VAULT_ACCOUNT, collateral_type.key().as_ref()],
+        bump,
+    )]
+    pub shutdown_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 仓位所有者的抵押品代币账户 - 接收剩余的抵押品
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = collateral_account.depositor,
+        token::token_program = collateral_token_program,
+    )]
+    pub depositor_collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 抵押品代币所属的代币程序（SPL Token 或 Token2022）
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+// 与 settle_vault 相同，结算代币抵押品仓位，差额记入该类型的 shutdown_gap
+pub fn process_settle_token_vault(ctx: Context<SettleTokenVault>) -> Result<()> {
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    let debt = collateral_type.debt.debt_amount(collateral_account.normalized_debt)?;
+    let unit = 10u64
+        .checked_pow(collateral_type.mint_decimals as u32)
+        .ok_or(CustomError::MathOverflow)?;
+    let required = collateral_for_debt(debt, collateral_type.shutdown_price, unit, true)?;
+    let balance = ctx.accounts.vault.amount;
+    let seized = required.min(balance);
+    let shortfall = required - seized;
+    let returned = balance - seized;
+
+    collateral_type.shutdown_gap = collateral_type
+        .shutdown_gap
+        .checked_add(shortfall)
+        .ok_or(CustomError::MathOverflow)?;
+    collateral_account.lamport_balance = 0;
+    collateral_account.normalized_debt = 0;
+
+    if seized > 0 {
+        withdraw_tokens_internal(
+            &ctx.accounts.vault,
+            &ctx.accounts.shutdown_vault,
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.collateral_token_program,
+            &ctx.accounts.collateral_account,
+            seized,
+        )?;
+    }
+    if returned > 0 {
+        withdraw_tokens_internal(
+            &ctx.accounts.vault,
+            &ctx.accounts.depositor_collateral_account,
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.collateral_token_program,
+            &ctx.accounts.collateral_account,
+            returned,
+        )?;
+    }
+
+    emit!(VaultSettled {
+        collateral_account: ctx.accounts.collateral_account.key(),
+        seized,
+        returned,
+        shortfall,
+    });
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use crate::{
+    collateral_for_debt, error::CustomError, events::VaultSettled, withdraw_sol_internal, Collateral, Config,
+    Shutdown, SEED_COLLATERAL_ACCOUNT, SEED_CONFIG_ACCOUNT, SEED_SHUTDOWN_ACCOUNT,
+};
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+
+#[derive(Accounts)]
+pub struct SettleVault<'info> {
+    // 调用者 - 任何人都可以结算任何仓位，keeper 应在 finalize_shutdown 之前结算资不抵债的仓位
+    pub caller: Signer<'info>,
+    // 配置账户 - 提供原生SOL仓位的利率指数（关停时已冻结）
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        constraint = config_account.shutdown @ CustomError::NotShutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 关停账户 - 原生SOL的结算池，记录抵押品缺口
+    #[account(
+        mut,
+        seeds = [SEED_SHUTDOWN_ACCOUNT],
+        bump = shutdown_account.bump,
+    )]
+    pub shutdown_account: Box<Account<'info, Shutdown>>,
+    // 被结算的原生SOL仓位
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_account.depositor.as_ref()],
+        bump = collateral_account.bump,
+        has_one = sol_account,
+        has_one = depositor
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    // 仓位的SOL存储账户
+    #[account(mut)]
+    pub sol_account: SystemAccount<'info>,
+    // 仓位所有者的钱包 - 接收剩余的SOL
+    #[account(mut)]
+    pub depositor: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+关停后结算一个原生SOL仓位（参考 MakerDAO 的 end.skim 和 end.free）：
+按关停价格覆盖仓位债务的SOL转入结算池，剩余的SOL退还给仓位所有者，仓位清零。
+抵押品不足以覆盖债务时全部SOL进入结算池，差额记入 sol_gap，由全体稳定币持有者按比例承担。
+**/
+pub fn process_settle_vault(ctx: Context<SettleVault>) -> Result<()> {
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    let debt = ctx
+        .accounts
+        .config_account
+        .debt
+        .debt_amount(collateral_account.normalized_debt)?;
+    let required = collateral_for_debt(debt, ctx.accounts.shutdown_account.sol_price, LAMPORTS_PER_SOL, true)?;
+    let balance = ctx.accounts.sol_account.lamports();
+    let seized = required.min(balance);
+    let shortfall = required - seized;
+    let returned = balance - seized;
+
+    let shutdown_account = &mut ctx.accounts.shutdown_account;
+    shutdown_account.sol_gap = shutdown_account
+        .sol_gap
+        .checked_add(shortfall)
+        .ok_or(CustomError::MathOverflow)?;
+    collateral_account.lamport_balance = 0;
+    collateral_account.normalized_debt = 0;
+
+    if seized > 0 {
+        withdraw_sol_internal(
+            &ctx.accounts.sol_account,
+            &ctx.accounts.shutdown_account.to_account_info(),
+            &ctx.accounts.system_program,
+            &ctx.accounts.collateral_account.depositor,
+            ctx.accounts.collateral_account.bump_sol_account,
+            seized,
+        )?;
+    }
+    if returned > 0 {
+        withdraw_sol_internal(
+            &ctx.accounts.sol_account,
+            &ctx.accounts.depositor.to_account_info(),
+            &ctx.accounts.system_program,
+            &ctx.accounts.collateral_account.depositor,
+            ctx.accounts.collateral_account.bump_sol_account,
+            returned,
+        )?;
+    }
+
+    emit!(VaultSettled {
+        collateral_account: ctx.accounts.collateral_account.key(),
+        seized,
+        returned,
+        shortfall,
+    });
+    Ok(())
+}
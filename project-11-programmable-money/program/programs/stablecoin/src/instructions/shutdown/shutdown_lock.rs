@@ -0,0 +1,73 @@
+use crate::{
+    burn_tokens_internal, error::CustomError, Config, Shutdown, ShutdownClaim, SEED_CONFIG_ACCOUNT,
+    SEED_SHUTDOWN_ACCOUNT, SEED_SHUTDOWN_CLAIM_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct ShutdownLock<'info> {
+    // 稳定币持有者
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 关停账户 - 必须已经 finalize_shutdown
+    #[account(
+        seeds = [SEED_SHUTDOWN_ACCOUNT],
+        bump = shutdown_account.bump,
+        constraint = shutdown_account.finalized @ CustomError::ShutdownNotFinalized
+    )]
+    pub shutdown_account: Box<Account<'info, Shutdown>>,
+    // 用户锁定稳定币的记录
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ShutdownClaim::INIT_SPACE,
+        seeds = [SEED_SHUTDOWN_CLAIM_ACCOUNT, user.key().as_ref()],
+        bump,
+    )]
+    pub claim: Box<Account<'info, ShutdownClaim>>,
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 用户的稳定币关联代币账户 - 锁定的稳定币从此账户销毁
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+锁定稳定币准备兑付（参考 MakerDAO 的 end.pack）。锁定的稳定币直接销毁，不能取回，
+之后用户可以在每个结算池（原生SOL、每个代币抵押品类型、每个PSM）分别兑付最多这么多稳定币对应的抵押品。
+可以多次锁定，累加到同一个记录上。
+**/
+pub fn process_shutdown_lock(ctx: Context<ShutdownLock>, amount: u64) -> Result<()> {
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    let claim = &mut ctx.accounts.claim;
+    claim.owner = ctx.accounts.user.key();
+    claim.amount = claim.amount.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+    claim.bump = ctx.bumps.claim;
+    msg!("Locked {:.9} for shutdown redemption", claim.amount as f64 / 1e9);
+    Ok(())
+}
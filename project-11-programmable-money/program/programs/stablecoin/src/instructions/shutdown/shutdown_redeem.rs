@@ -0,0 +1,237 @@
+use crate::{
+    collateral_for_debt, error::CustomError, record_cash, shutdown_fix, withdraw_reserve_internal,
+    withdraw_shutdown_vault_internal, CollateralType, Psm, Shutdown, ShutdownCash, ShutdownClaim,
+    SEED_COLLATERAL_TYPE_ACCOUNT, SEED_PSM_ACCOUNT, SEED_PSM_RESERVE_ACCOUNT, SEED_SHUTDOWN_ACCOUNT,
+    SEED_SHUTDOWN_CASH_ACCOUNT, SEED_SHUTDOWN_CLAIM_ACCOUNT, SEED_SHUTDOWN_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct ShutdownRedeemSol<'info> {
+    // 稳定币持有者 - 接收兑付的SOL
+    #[account(mut)]
+    pub user: Signer<'info>,
+    // 关停账户 - 原生SOL的结算池
+    #[account(
+        mut,
+        seeds = [SEED_SHUTDOWN_ACCOUNT],
+        bump = shutdown_account.bump,
+        constraint = shutdown_account.finalized @ CustomError::ShutdownNotFinalized
+    )]
+    pub shutdown_account: Box<Account<'info, Shutdown>>,
+    // 用户锁定稳定币的记录
+    #[account(
+        seeds = [SEED_SHUTDOWN_CLAIM_ACCOUNT, user.key().as_ref()],
+        bump = claim.bump,
+    )]
+    pub claim: Box<Account<'info, ShutdownClaim>>,
+    // 用户在原生SOL结算池已兑付的数量
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ShutdownCash::INIT_SPACE,
+        seeds = [SEED_SHUTDOWN_CASH_ACCOUNT, shutdown_account.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub cash: Box<Account<'info, ShutdownCash>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ShutdownRedeemToken<'info> {
+    // 稳定币持有者
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        seeds = [SEED_SHUTDOWN_ACCOUNT],
+        bump = shutdown_account.bump,
+        constraint = shutdown_account.finalized @ CustomError::ShutdownNotFinalized
+    )]
+    pub shutdown_account: Box<Account<'info, Shutdown>>,
+    #[account(
+        seeds = [SEED_SHUTDOWN_CLAIM_ACCOUNT, user.key().as_ref()],
+        bump = claim.bump,
+    )]
+    pub claim: Box<Account<'info, ShutdownClaim>>,
+    // 抵押品类型注册账户 - 第一次兑付时记录兑付比例
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+        constraint = collateral_type.shutdown_price > 0 @ CustomError::ShutdownPriceNotRecorded
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 该类型的结算池
+    #[account(
+        mut,
+        seeds = [SEED_SHUTDOWN_VAULT_ACCOUNT, collateral_type.key().as_ref()],
+        bump,
+    )]
+    pub shutdown_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 用户的抵押品代币关联账户 - 接收兑付的抵押品，不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = user,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub user_collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 用户在该结算池已兑付的数量
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ShutdownCash::INIT_SPACE,
+        seeds = [SEED_SHUTDOWN_CASH_ACCOUNT, collateral_type.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub cash: Box<Account<'info, ShutdownCash>>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ShutdownRedeemPsm<'info> {
+    // 稳定币持有者
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        seeds = [SEED_SHUTDOWN_ACCOUNT],
+        bump = shutdown_account.bump,
+        constraint = shutdown_account.finalized @ CustomError::ShutdownNotFinalized
+    )]
+    pub shutdown_account: Box<Account<'info, Shutdown>>,
+    #[account(
+        seeds = [SEED_SHUTDOWN_CLAIM_ACCOUNT, user.key().as_ref()],
+        bump = claim.bump,
+    )]
+    pub claim: Box<Account<'info, ShutdownClaim>>,
+    // PSM账户 - 第一次兑付时记录兑付比例
+    #[account(
+        mut,
+        seeds = [SEED_PSM_ACCOUNT, stable_mint.key().as_ref()],
+        bump = psm.bump,
+    )]
+    pub psm: Box<Account<'info, Psm>>,
+    pub stable_mint: Box<InterfaceAccount<'info, Mint>>,
+    // PSM储备账户 - 关停后作为结算池
+    #[account(
+        mut,
+        seeds = [SEED_PSM_RESERVE_ACCOUNT, stable_mint.key().as_ref()],
+        bump = psm.bump_reserve,
+    )]
+    pub reserve: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 用户的外部稳定币关联账户 - 不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = stable_mint,
+        associated_token::authority = user,
+        associated_token::token_program = stable_token_program
+    )]
+    pub user_stable_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 用户在该PSM已兑付的数量
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ShutdownCash::INIT_SPACE,
+        seeds = [SEED_SHUTDOWN_CASH_ACCOUNT, psm.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub cash: Box<Account<'info, ShutdownCash>>,
+    pub stable_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+用已锁定的稳定币兑付原生SOL结算池中的SOL（参考 MakerDAO 的 end.cash）。
+每个稳定币兑付 sol_fix / WAD lamports，每个结算池累计兑付的数量不能超过锁定的数量。
+结算池不足（还有仓位没有结算）时失败，结算后可以再次兑付。
+**/
+pub fn process_shutdown_redeem_sol(ctx: Context<ShutdownRedeemSol>, amount: u64) -> Result<()> {
+    ctx.accounts.cash.bump = ctx.bumps.cash;
+    let lamports = record_cash(
+        &ctx.accounts.claim,
+        &mut ctx.accounts.cash,
+        amount,
+        ctx.accounts.shutdown_account.sol_fix,
+    )?;
+
+    // 关停账户由本程序拥有，直接转出 lamports，保留租金
+    let shutdown_info = ctx.accounts.shutdown_account.to_account_info();
+    let rent = Rent::get()?.minimum_balance(shutdown_info.data_len());
+    require!(
+        lamports <= shutdown_info.lamports().saturating_sub(rent),
+        CustomError::InsufficientCollateral
+    );
+    if lamports > 0 {
+        ctx.accounts.shutdown_account.sub_lamports(lamports)?;
+        ctx.accounts.user.add_lamports(lamports)?;
+    }
+    msg!("Redeemed {:.9} for {:.9} SOL", amount as f64 / 1e9, lamports as f64 / 1e9);
+    Ok(())
+}
+
+// 兑付代币抵押品类型的结算池，该类型第一次兑付时按 (关停时的债务 / 关停价格 − 缺口) / supply 计算兑付比例
+pub fn process_shutdown_redeem_token(ctx: Context<ShutdownRedeemToken>, amount: u64) -> Result<()> {
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    if collateral_type.shutdown_fix == 0 {
+        let unit = 10u64
+            .checked_pow(collateral_type.mint_decimals as u32)
+            .ok_or(CustomError::MathOverflow)?;
+        let collateral = collateral_for_debt(collateral_type.debt.total_debt()?, collateral_type.shutdown_price, unit, false)?
+            .saturating_sub(collateral_type.shutdown_gap);
+        collateral_type.shutdown_fix = shutdown_fix(collateral, ctx.accounts.shutdown_account.supply);
+    }
+
+    ctx.accounts.cash.bump = ctx.bumps.cash;
+    let amount_out = record_cash(
+        &ctx.accounts.claim,
+        &mut ctx.accounts.cash,
+        amount,
+        collateral_type.shutdown_fix,
+    )?;
+    if amount_out > 0 {
+        withdraw_shutdown_vault_internal(
+            &ctx.accounts.shutdown_vault,
+            &ctx.accounts.user_collateral_account,
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.collateral_token_program,
+            &ctx.accounts.collateral_type.key(),
+            ctx.bumps.shutdown_vault,
+            amount_out,
+        )?;
+    }
+    msg!("Redeemed {:.9} for {} collateral", amount as f64 / 1e9, amount_out);
+    Ok(())
+}
+
+// 兑付PSM储备中的外部稳定币，第一次兑付时按 储备余额 / supply 计算兑付比例（关停后储备不再变化）
+pub fn process_shutdown_redeem_psm(ctx: Context<ShutdownRedeemPsm>, amount: u64) -> Result<()> {
+    let psm = &mut ctx.accounts.psm;
+    if psm.shutdown_fix == 0 {
+        psm.shutdown_fix = shutdown_fix(ctx.accounts.reserve.amount, ctx.accounts.shutdown_account.supply);
+    }
+
+    ctx.accounts.cash.bump = ctx.bumps.cash;
+    let amount_out = record_cash(&ctx.accounts.claim, &mut ctx.accounts.cash, amount, psm.shutdown_fix)?;
+    if amount_out > 0 {
+        withdraw_reserve_internal(
+            &ctx.accounts.reserve,
+            &ctx.accounts.user_stable_account,
+            &ctx.accounts.stable_mint,
+            &ctx.accounts.stable_token_program,
+            &ctx.accounts.psm,
+            amount_out,
+        )?;
+    }
+    msg!("Redeemed {:.9} for {} external stable", amount as f64 / 1e9, amount_out);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::{error::CustomError, ShutdownCash, ShutdownClaim, SEED_SHUTDOWN_VAULT_ACCOUNT, WAD};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+// 按关停价格覆盖 debt 个稳定币需要的抵押品数量 = debt × unit / price，unit 为一个完整抵押品的基础单位数
+// 结算仓位时向上取整，计算结算池总量时向下取整，保证所有仓位结算后结算池不会不足
+// Example: $3.00 debt, 1 SOL = $150.00
+// 3_000_000_000 * 1_000_000_000 / 150_000_000_000 = 20_000_000 lamports
+pub fn collateral_for_debt(debt: u64, price: u64, unit: u64, round_up: bool) -> Result<u64> {
+    require!(price > 0, CustomError::InvalidPrice);
+    let numerator = debt as u128 * unit as u128;
+    let amount = if round_up {
+        numerator.div_ceil(price as u128)
+    } else {
+        numerator / price as u128
+    };
+    u64::try_from(amount).map_err(|_| CustomError::MathOverflow.into())
+}
+
+// 每个稳定币可兑付的抵押品数量（WAD）= 结算池应有的抵押品 / 可兑付的稳定币总量
+pub fn shutdown_fix(collateral: u64, supply: u64) -> u128 {
+    if supply == 0 {
+        return 0;
+    }
+    collateral as u128 * WAD / supply as u128
+}
+
+// 在一个结算池兑付 amount 个已锁定的稳定币，返回应付的抵押品数量（向下取整）
+// 每个结算池累计兑付的数量不能超过锁定的数量
+pub fn record_cash(claim: &ShutdownClaim, cash: &mut ShutdownCash, amount: u64, fix: u128) -> Result<u64> {
+    let total = cash.amount.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+    require!(total <= claim.amount, CustomError::InsufficientLockedStablecoin);
+    cash.amount = total;
+    let collateral = (amount as u128)
+        .checked_mul(fix)
+        .ok_or(CustomError::MathOverflow)?
+        / WAD;
+    u64::try_from(collateral).map_err(|_| CustomError::MathOverflow.into())
+}
+
+// 从代币抵押品结算池转出，由结算池PDA签名
+pub fn withdraw_shutdown_vault_internal<'info>(
+    shutdown_vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    collateral_type_key: &Pubkey,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[SEED_SHUTDOWN_VAULT_ACCOUNT, collateral_type_key.as_ref(), &[bump]]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: shutdown_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: shutdown_vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
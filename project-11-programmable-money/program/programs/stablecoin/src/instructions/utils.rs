@@ -1,9 +1,22 @@
 use crate::{
-    error::CustomError, Collateral, Config, FEED_ID, MAXIMUM_AGE, PRICE_FEED_DECIMAL_ADJUSTMENT,
+    error::CustomError, Collateral, CollateralType, Config, FEED_ID, HEALTH_FACTOR_PRECISION, MAXIMUM_AGE,
+    MINT_DECIMALS,
 };
 use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use anchor_spl::token_interface::Mint;
 use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
 
+// Check the stablecoin supply after minting amount stays within the protocol-wide debt ceiling
+// 全局债务上限按稳定币总供应量计算，包括所有抵押品类型、PSM和已铸造的稳定费
+pub fn check_debt_ceiling(config: &Config, mint_account: &InterfaceAccount<Mint>, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let supply = mint_account.supply.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+    require!(supply <= config.debt_ceiling, CustomError::GlobalDebtCeilingExceeded);
+    Ok(())
+}
+
 // Check health factor for Collateral account is greater than minimum required health factor
 pub fn check_health_factor(
     collateral: &Account<Collateral>,
@@ -30,43 +43,46 @@ pub fn calculate_health_factor(
     // collateral_value_in_usd = 1_000_000_000
     let collateral_value_in_usd = get_usd_value(&collateral.lamport_balance, price_feed)?;
 
-    // Adjust the collateral value for the liquidation threshold (require overcollateralize)
-    // Example: (1_000_000_000 * 50) / 100 = 500_000_000
-    let collateral_adjusted_for_liquidation_threshold =
-        (collateral_value_in_usd * config.liquidation_threshold) / 100;
-
+    // Actual debt including accrued stability fees
+    let debt = config.debt.debt_amount(collateral.normalized_debt)?;
     msg!(
-        "Minted Amount : {:.9}",
-        collateral.amount_minted as f64 / 1e9
+        "Debt Amount : {:.9}",
+        debt as f64 / 1e9
     );
 
-    if collateral.amount_minted == 0 {
-        msg!("Health Factor Max");
-        return Ok(u64::MAX);
-    }
-
-    // Calculate the health factor
-    // Ratio of (adjusted collateral value) / (amount stablecoins minted)
-    // Example: 500_000_000 / 500_000_000 = 1
-    let health_factor = (collateral_adjusted_for_liquidation_threshold) / collateral.amount_minted;
-
-    msg!("Health Factor : {}", health_factor);
+    let health_factor = health_factor_from_values(collateral_value_in_usd, config.liquidation_threshold, debt);
+    log_health_factor(health_factor);
     Ok(health_factor)
 }
 
-// Given lamports, return USD value based on current SOL price.
-fn get_usd_value(amount_in_lamports: &u64, price_feed: &Account<PriceUpdateV2>) -> Result<u64> {
-    let feed_id = get_feed_id_from_hex(FEED_ID)?;
-    let price = price_feed.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE, &feed_id)?;
+// Health factor in basis points (HEALTH_FACTOR_PRECISION = 1.0), u64::MAX when there is no debt
+// Ratio of (collateral value adjusted for the liquidation threshold) / (debt including stability fees)
+// Example: $1.00 collateral, 50% threshold, $0.40 debt
+// (1_000_000_000 * 50 / 100) * 10_000 / 400_000_000 = 12_500 (1.25)
+pub fn health_factor_from_values(collateral_value_in_usd: u64, liquidation_threshold: u64, debt: u64) -> u64 {
+    if debt == 0 {
+        return u64::MAX;
+    }
+    let health_factor = collateral_value_in_usd as u128 * liquidation_threshold as u128 * HEALTH_FACTOR_PRECISION as u128
+        / (100 * debt as u128);
+    health_factor.min(u64::MAX as u128) as u64
+}
 
-    // Check price is positive
-    require!(price.price > 0, CustomError::InvalidPrice);
+fn log_health_factor(health_factor: u64) {
+    if health_factor == u64::MAX {
+        msg!("Health Factor Max");
+    } else {
+        msg!("Health Factor : {:.4}", health_factor as f64 / HEALTH_FACTOR_PRECISION as f64);
+    }
+}
 
-    // Adjust price to match lamports precision (9 decimals)
+// Given lamports, return USD value based on current SOL price.
+pub fn get_usd_value(amount_in_lamports: &u64, price_feed: &Account<PriceUpdateV2>) -> Result<u64> {
+    // Price of 1 SOL in USD with 9 decimals, scaled by the feed's exponent
     // Example: Assuming 1 SOL = $2.00
-    // price.price = 200_000_000 (from Pyth, 8 decimals)
-    // price_in_usd = 200_000_000 * 10 = 2_000_000_000 (9 decimals)
-    let price_in_usd = price.price as u128 * PRICE_FEED_DECIMAL_ADJUSTMENT;
+    // price.price = 200_000_000, price.exponent = -8
+    // price_in_usd = 200_000_000 * 10^(9 - 8) = 2_000_000_000 (9 decimals)
+    let price_in_usd = get_price_in_usd(&get_feed_id_from_hex(FEED_ID)?, price_feed)?;
 
     // Calculate USD value
     // Example: Convert 0.5 SOL to USD when 1 SOL = $2.00
@@ -86,45 +102,109 @@ fn get_usd_value(amount_in_lamports: &u64, price_feed: &Account<PriceUpdateV2>)
     msg!("SOL/USD Price : {:.9}", price_in_usd as f64 / 1e9);
     msg!("SOL Amount    : {:.9}", *amount_in_lamports as f64 / 1e9);
     msg!("USD Value     : {:.9}", amount_in_usd as f64 / 1e9);
-    // msg!("Price exponent?: {}", price.exponent);
 
-    Ok(amount_in_usd as u64)
+    Ok(amount_in_usd.min(u64::MAX as u128) as u64)
 }
 
-// Given USD amount, return lamports based on current SOL price
-pub fn get_lamports_from_usd(
-    amount_in_usd: &u64,
+// Check health factor for a token Collateral account is greater than minimum required health factor
+pub fn check_token_health_factor(
+    collateral: &Account<Collateral>,
+    collateral_type: &Account<CollateralType>,
+    config: &Account<Config>,
+    price_feed: &Account<PriceUpdateV2>,
+) -> Result<()> {
+    let health_factor = calculate_token_health_factor(collateral, collateral_type, price_feed)?;
+    require!(
+        health_factor >= config.min_health_factor,
+        CustomError::BelowMinimumHealthFactor
+    );
+    Ok(())
+}
+
+// Calculate health factor for a token Collateral account, using the collateral type's
+// price feed and liquidation threshold
+pub fn calculate_token_health_factor(
+    collateral: &Account<Collateral>,
+    collateral_type: &Account<CollateralType>,
     price_feed: &Account<PriceUpdateV2>,
 ) -> Result<u64> {
-    let feed_id = get_feed_id_from_hex(FEED_ID)?;
-    let price = price_feed.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE, &feed_id)?;
+    // Actual debt including accrued stability fees
+    let debt = collateral_type.debt.debt_amount(collateral.normalized_debt)?;
+    if debt == 0 {
+        log_health_factor(u64::MAX);
+        return Ok(u64::MAX);
+    }
+
+    let collateral_value_in_usd = get_token_usd_value(collateral.lamport_balance, collateral_type, price_feed)?;
+    let health_factor =
+        health_factor_from_values(collateral_value_in_usd, collateral_type.liquidation_threshold, debt);
+    log_health_factor(health_factor);
+    Ok(health_factor)
+}
+
+// Given token base units, return USD value (9 decimals) based on the collateral type's price feed
+pub fn get_token_usd_value(
+    amount: u64,
+    collateral_type: &CollateralType,
+    price_feed: &Account<PriceUpdateV2>,
+) -> Result<u64> {
+    let price_in_usd = get_price_in_usd(&collateral_type.price_feed_id, price_feed)?;
+    // Example: 2 tokens with 6 decimals when 1 token = $3.00
+    // (2_000_000 * 3_000_000_000) / 1_000_000 = 6_000_000_000 ($6.00)
+    let amount_in_usd = (amount as u128 * price_in_usd) / 10u128.pow(collateral_type.mint_decimals as u32);
+    Ok(amount_in_usd.min(u64::MAX as u128) as u64)
+}
+
+// Price of one whole token in USD with stablecoin precision (9 decimals)
+// Pyth price = price * 10^exponent, so price_in_usd = price * 10^(9 + exponent)
+pub fn get_price_in_usd(feed_id: &[u8; 32], price_feed: &Account<PriceUpdateV2>) -> Result<u128> {
+    let price = price_feed.get_price_no_older_than(&Clock::get()?, MAXIMUM_AGE, feed_id)?;
 
     // Check price is positive
     require!(price.price > 0, CustomError::InvalidPrice);
 
-    // Adjust price to match lamports precision (9 decimals)
-    // Example: Assuming 1 SOL = $2.00
-    // price.price = 200_000_000 (from Pyth, 8 decimals)
-    // price_in_usd = 200_000_000 * 10 = 2_000_000_000 (9 decimals)
-    let price_in_usd = price.price as u128 * PRICE_FEED_DECIMAL_ADJUSTMENT;
+    let scale = MINT_DECIMALS as i32 + price.exponent;
+    let price_in_usd = if scale >= 0 {
+        price.price as u128 * 10u128.pow(scale as u32)
+    } else {
+        price.price as u128 / 10u128.pow(scale.unsigned_abs())
+    };
+    require!(price_in_usd > 0, CustomError::InvalidPrice);
+    Ok(price_in_usd)
+}
 
-    // Calculate lamports
-    // Example: Convert $0.50 to lamports when 1 SOL = $2.00
-    // amount_in_usd = 500_000_000 (user input, 9 decimals for $0.50)
-    // LAMPORTS_PER_SOL = 1_000_000_000
-    // price_in_usd = 2_000_000_000 (as calculated above)
-    // amount_in_lamports = (500_000_000 * 1_000_000_000) / 2_000_000_000 = 250_000_000 (0.25 SOL)
-    let amount_in_lamports = ((*amount_in_usd as u128) * (LAMPORTS_PER_SOL as u128)) / price_in_usd;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // EXAMPLE LOGS
-    // Program log: *** CONVERT SOL TO USD ***
-    // Program log: Price in USD (for 1 SOL): 136.194634200
-    // Program log: USD Amount: 1.500000000
-    // Program log: SOL Value: 0.011013649
-    msg!("*** CONVERT SOL TO USD ***");
-    msg!("SOL/USD Price : {:.9}", price_in_usd as f64 / 1e9);
-    msg!("USD Amount    : {:.9}", *amount_in_usd as f64 / 1e9);
-    msg!("SOL Value     : {:.9}", amount_in_lamports as f64 / 1e9);
+    // $1.00 以 9 位小数表示
+    const USD: u64 = 1_000_000_000;
+
+    #[test]
+    fn health_factor_keeps_decimals() {
+        // 抵押品 $3，阈值 50%，债务 $1：1.5
+        assert_eq!(health_factor_from_values(3 * USD, 50, USD), 15_000);
+        // 抵押品 $2.4，阈值 50%，债务 $1：1.2，整数除法下会被截断为 1
+        assert_eq!(health_factor_from_values(2_400_000_000, 50, USD), 12_000);
+        assert_eq!(health_factor_from_values(2 * USD, 50, USD), HEALTH_FACTOR_PRECISION);
+    }
 
-    Ok(amount_in_lamports as u64)
+    #[test]
+    fn health_factor_boundaries() {
+        // 刚好等于 1.2 时通过 1.2 的最小值，少 1 个最小单位的抵押品就低于 1.2
+        let min_health_factor = 12_000;
+        assert!(health_factor_from_values(2_400_000_000, 50, USD) >= min_health_factor);
+        assert!(health_factor_from_values(2_399_999_999, 50, USD) < min_health_factor);
+        // 刚好等于 1.0 与刚好低于 1.0
+        assert_eq!(health_factor_from_values(2 * USD, 50, USD), 10_000);
+        assert_eq!(health_factor_from_values(2 * USD - 1, 50, USD), 9_999);
+    }
+
+    #[test]
+    fn health_factor_without_debt_or_collateral() {
+        assert_eq!(health_factor_from_values(USD, 50, 0), u64::MAX);
+        assert_eq!(health_factor_from_values(0, 50, USD), 0);
+        // 极端比值饱和为 u64::MAX 而不是溢出
+        assert_eq!(health_factor_from_values(u64::MAX, 100, 1), u64::MAX);
+    }
 }
@@ -1,6 +1,14 @@
 pub use redeem_collateral_and_burn_tokens::*;
 pub mod redeem_collateral_and_burn_tokens;
-pub use liquidate::*;
-pub mod liquidate;
+pub use redeem_token_collateral_and_burn_tokens::*;
+pub mod redeem_token_collateral_and_burn_tokens;
 pub use utils::*;
 pub mod utils;
+pub use repay_debt::*;
+pub mod repay_debt;
+pub use withdraw_collateral::*;
+pub mod withdraw_collateral;
+pub use repay_token_debt::*;
+pub mod repay_token_debt;
+pub use withdraw_token_collateral::*;
+pub mod withdraw_token_collateral;
@@ -1,5 +1,5 @@
 use crate::{
-    burn_tokens_internal, check_health_factor, withdraw_sol_internal, Collateral, Config,
+    error::CustomError,     burn_tokens_internal, check_health_factor, withdraw_sol_internal, Collateral, Config,
     SEED_COLLATERAL_ACCOUNT, SEED_CONFIG_ACCOUNT,
 };
 use anchor_lang::prelude::*;
@@ -17,9 +17,12 @@ pub struct RedeemCollateralAndBurnTokens<'info> {
     // 配置账户 - 存储协议全局参数的PDA账户
     // 验证mint_account是否与配置中的一致
     #[account(
+        mut, // 可变，因为要计提稳定费并减少债务总量
         seeds = [SEED_CONFIG_ACCOUNT], // 使用 "config" 种子验证PDA
         bump = config_account.bump,  // 使用存储的bump值验证
-        has_one = mint_account  // 验证mint_account匹配配置
+        has_one = mint_account, // 验证mint_account匹配配置
+        constraint = !config_account.paused @ CustomError::Paused, // 暂停期间不能铸造或销毁稳定币
+        constraint = !config_account.shutdown @ CustomError::Shutdown // 紧急关停后只能按关停价格结算
     )]
     pub config_account: Account<'info, Config>,
     // 抵押品账户 - 跟踪用户抵押品状态的PDA账户
@@ -60,12 +63,19 @@ pub fn process_redeem_collateral_and_burn_tokens(
     amount_collateral: u64, // 要赎回的SOL数量（lamports）
     amount_to_burn: u64, // 要销毁的稳定币数量
 ) -> Result<()> {
+    // 先计提稳定费，销毁的稳定币按最新的利率指数偿还债务（含稳定费）
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.debt.accrue(Clock::get()?.unix_timestamp)?;
+    let normalized_debt = config_account
+        .debt
+        .remove_debt(ctx.accounts.collateral_account.normalized_debt, amount_to_burn)?;
+
     let collateral_account = &mut ctx.accounts.collateral_account;
     // 更新抵押品账户中的余额记录
     // 计算提取后的剩余lamport余额
     collateral_account.lamport_balance = ctx.accounts.sol_account.lamports() - amount_collateral;
-    // 减少已铸造的稳定币数量
-    collateral_account.amount_minted -= amount_to_burn;
+    // 减少标准化债务
+    collateral_account.normalized_debt -= normalized_debt;
     // 检查操作后的健康因子是否满足最小要求
     // 确保提取抵押品后仍有足够的抵押率
     check_health_factor(
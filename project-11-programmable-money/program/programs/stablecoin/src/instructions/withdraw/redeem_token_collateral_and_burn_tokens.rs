@@ -0,0 +1,123 @@
+use crate::{
+    burn_tokens_internal, check_token_health_factor, error::CustomError, withdraw_tokens_internal,
+    Collateral, CollateralType, Config, SEED_COLLATERAL_ACCOUNT, SEED_COLLATERAL_TYPE_ACCOUNT,
+    SEED_CONFIG_ACCOUNT, SEED_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct RedeemTokenCollateralAndBurnTokens<'info> {
+    // 存款人账户 - 签署交易的用户钱包，必须是仓位的所有者
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    // 价格更新账户 - 包含该抵押品类型的Pyth价格数据
+    pub price_update: Account<'info, PriceUpdateV2>,
+    // 配置账户 - 存储协议全局参数的PDA账户
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 抵押品类型注册账户
+    #[account(
+        mut, // 可变，因为要减少该类型的债务总量
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 抵押品账户 - 用户在该抵押品类型下的仓位
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_type.key().as_ref(), depositor.key().as_ref()],
+        bump = collateral_account.bump,
+        has_one = token_account
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    // 代币存储账户 - 抵押品从此账户转回用户
+    #[account(
+        mut,
+        seeds = [SEED_VAULT_ACCOUNT, collateral_type.key().as_ref(), depositor.key().as_ref()],
+        bump = collateral_account.bump_sol_account,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 存款人的抵押品代币账户 - 接收赎回的抵押品
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = depositor,
+        token::token_program = collateral_token_program,
+    )]
+    pub depositor_collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 稳定币铸造账户 - 控制稳定币发行和销毁的PDA账户
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 用户代币账户 - 稳定币将从此账户销毁
+    #[account(mut)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // Token2022 程序 - 处理稳定币销毁
+    pub token_program: Program<'info, Token2022>,
+    // 抵押品代币所属的代币程序（SPL Token 或 Token2022）
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+redeem_collateral_and_burn_tokens 的代币抵押品版本：更新仓位和该类型的债务总量，
+检查健康因子后销毁稳定币，并把抵押品从代币存储账户转回用户。
+**/
+pub fn process_redeem_token_collateral_and_burn_tokens(
+    ctx: Context<RedeemTokenCollateralAndBurnTokens>,
+    amount_collateral: u64, // 要赎回的抵押品数量（代币基础单位）
+    amount_to_burn: u64,    // 要销毁的稳定币数量
+) -> Result<()> {
+    // 先计提稳定费，销毁的稳定币按最新的利率指数偿还债务（含稳定费）
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.debt.accrue(Clock::get()?.unix_timestamp)?;
+    let normalized_debt = collateral_type
+        .debt
+        .remove_debt(ctx.accounts.collateral_account.normalized_debt, amount_to_burn)?;
+
+    let collateral_account = &mut ctx.accounts.collateral_account;
+    // 提取后的剩余抵押品余额
+    collateral_account.lamport_balance = ctx
+        .accounts
+        .vault
+        .amount
+        .checked_sub(amount_collateral)
+        .ok_or(CustomError::InsufficientCollateral)?;
+    // 减少标准化债务
+    collateral_account.normalized_debt -= normalized_debt;
+
+    // 检查操作后的健康因子是否满足最小要求
+    check_token_health_factor(
+        &ctx.accounts.collateral_account,
+        &ctx.accounts.collateral_type,
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+    )?;
+    // 从用户代币账户销毁稳定币
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.depositor,
+        &ctx.accounts.token_program,
+        amount_to_burn,
+    )?;
+    // 抵押品从代币存储账户转回用户
+    withdraw_tokens_internal(
+        &ctx.accounts.vault,
+        &ctx.accounts.depositor_collateral_account,
+        &ctx.accounts.collateral_mint,
+        &ctx.accounts.collateral_token_program,
+        &ctx.accounts.collateral_account,
+        amount_collateral,
+    )?;
+
+    Ok(())
+}
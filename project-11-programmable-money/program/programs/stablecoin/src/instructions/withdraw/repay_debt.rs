@@ -0,0 +1,57 @@
+use crate::{
+    burn_tokens_internal, error::CustomError, Collateral, Config, SEED_COLLATERAL_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct RepayDebt<'info> {
+    // 还款人账户 - 可以是仓位所有者，也可以是替别人还款的第三方
+    pub payer: Signer<'info>,
+    #[account(
+        mut, // 可变，因为要计提稳定费并减少债务总量
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Account<'info, Config>,
+    // 被还款的原生SOL仓位
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_account.depositor.as_ref()],
+        bump = collateral_account.bump,
+    )]
+    pub collateral_account: Account<'info, Collateral>,
+    #[account(mut)]
+    pub mint_account: InterfaceAccount<'info, Mint>,
+    // 还款人的代币账户 - 稳定币从此账户销毁
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// 销毁稳定币偿还仓位的债务（含稳定费），不取回抵押品。
+// 任何人都可以替别人还款，例如在仓位被清算前救援。债务减少只会提高健康因子，所以不需要健康检查。
+pub fn process_repay_debt(ctx: Context<RepayDebt>, amount_to_burn: u64) -> Result<()> {
+    let config_account = &mut ctx.accounts.config_account;
+    config_account.debt.accrue(Clock::get()?.unix_timestamp)?;
+    let normalized_debt = config_account
+        .debt
+        .remove_debt(ctx.accounts.collateral_account.normalized_debt, amount_to_burn)?;
+    ctx.accounts.collateral_account.normalized_debt -= normalized_debt;
+
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+        amount_to_burn,
+    )
+}
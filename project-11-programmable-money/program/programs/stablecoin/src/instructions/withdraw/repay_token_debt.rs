@@ -0,0 +1,65 @@
+use crate::{
+    burn_tokens_internal, error::CustomError, Collateral, CollateralType, Config, SEED_COLLATERAL_ACCOUNT,
+    SEED_COLLATERAL_TYPE_ACCOUNT, SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct RepayTokenDebt<'info> {
+    // 还款人账户 - 可以是仓位所有者，也可以是替别人还款的第三方
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        has_one = mint_account,
+        constraint = !config_account.paused @ CustomError::Paused,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 抵押品类型注册账户
+    #[account(
+        mut, // 可变，因为要计提稳定费并减少该类型的债务总量
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 被还款的代币抵押品仓位
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_type.key().as_ref(), collateral_account.depositor.as_ref()],
+        bump = collateral_account.bump,
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    #[account(mut)]
+    pub mint_account: Box<InterfaceAccount<'info, Mint>>,
+    // 还款人的代币账户 - 稳定币从此账户销毁
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// repay_debt 的代币抵押品版本：销毁稳定币偿还仓位的债务（含稳定费），任何人都可以替别人还款。
+pub fn process_repay_token_debt(ctx: Context<RepayTokenDebt>, amount_to_burn: u64) -> Result<()> {
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.debt.accrue(Clock::get()?.unix_timestamp)?;
+    let normalized_debt = collateral_type
+        .debt
+        .remove_debt(ctx.accounts.collateral_account.normalized_debt, amount_to_burn)?;
+    ctx.accounts.collateral_account.normalized_debt -= normalized_debt;
+
+    burn_tokens_internal(
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_account,
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+        amount_to_burn,
+    )
+}
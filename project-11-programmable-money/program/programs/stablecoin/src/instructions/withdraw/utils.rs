@@ -1,9 +1,9 @@
-use crate::SEED_SOL_ACCOUNT;
+use crate::{Collateral, SEED_SOL_ACCOUNT, SEED_VAULT_ACCOUNT};
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
 use anchor_spl::{
     token_2022::{burn, Burn},
-    token_interface::{Mint, Token2022, TokenAccount},
+    token_interface::{transfer_checked, Mint, Token2022, TokenAccount, TokenInterface, TransferChecked},
 };
 
 pub fn withdraw_sol_internal<'info>(
@@ -48,3 +48,34 @@ pub fn burn_tokens_internal<'info>(
         amount,
     )
 }
+
+pub fn withdraw_tokens_internal<'info>(
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    collateral: &Collateral,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SEED_VAULT_ACCOUNT,
+        collateral.collateral_type.as_ref(),
+        collateral.depositor.as_ref(),
+        &[collateral.bump_sol_account],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
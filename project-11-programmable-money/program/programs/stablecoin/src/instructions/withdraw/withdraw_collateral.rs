@@ -0,0 +1,57 @@
+use crate::{
+    check_health_factor, error::CustomError, withdraw_sol_internal, Collateral, Config, SEED_COLLATERAL_ACCOUNT,
+    SEED_CONFIG_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct WithdrawCollateral<'info> {
+    // 存款人账户 - 仓位的所有者，接收取回的SOL
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    // 价格更新账户 - 用于取回后的健康因子检查
+    pub price_update: Account<'info, PriceUpdateV2>,
+    #[account(
+        mut, // 可变，因为要计提稳定费，健康因子按包含稳定费的债务计算
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, depositor.key().as_ref()],
+        bump = collateral_account.bump,
+        has_one = sol_account
+    )]
+    pub collateral_account: Account<'info, Collateral>,
+    #[account(mut)]
+    pub sol_account: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// 只取回SOL抵押品，不偿还债务。取回后健康因子必须满足最小要求。
+pub fn process_withdraw_collateral(ctx: Context<WithdrawCollateral>, amount_collateral: u64) -> Result<()> {
+    ctx.accounts.config_account.debt.accrue(Clock::get()?.unix_timestamp)?;
+
+    ctx.accounts.collateral_account.lamport_balance = ctx
+        .accounts
+        .sol_account
+        .lamports()
+        .checked_sub(amount_collateral)
+        .ok_or(CustomError::InsufficientCollateral)?;
+    check_health_factor(
+        &ctx.accounts.collateral_account,
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+    )?;
+    withdraw_sol_internal(
+        &ctx.accounts.sol_account,
+        &ctx.accounts.depositor.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.depositor.key(),
+        ctx.accounts.collateral_account.bump_sol_account,
+        amount_collateral,
+    )
+}
@@ -0,0 +1,82 @@
+use crate::{
+    check_token_health_factor, error::CustomError, withdraw_tokens_internal, Collateral, CollateralType, Config,
+    SEED_COLLATERAL_ACCOUNT, SEED_COLLATERAL_TYPE_ACCOUNT, SEED_CONFIG_ACCOUNT, SEED_VAULT_ACCOUNT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+#[derive(Accounts)]
+pub struct WithdrawTokenCollateral<'info> {
+    // 存款人账户 - 仓位的所有者
+    pub depositor: Signer<'info>,
+    // 价格更新账户 - 包含该抵押品类型的Pyth价格数据，用于取回后的健康因子检查
+    pub price_update: Account<'info, PriceUpdateV2>,
+    #[account(
+        seeds = [SEED_CONFIG_ACCOUNT],
+        bump = config_account.bump,
+        constraint = !config_account.shutdown @ CustomError::Shutdown
+    )]
+    pub config_account: Box<Account<'info, Config>>,
+    // 抵押品类型注册账户
+    #[account(
+        mut, // 可变，因为要计提稳定费，健康因子按包含稳定费的债务计算
+        seeds = [SEED_COLLATERAL_TYPE_ACCOUNT, collateral_mint.key().as_ref()],
+        bump = collateral_type.bump,
+    )]
+    pub collateral_type: Box<Account<'info, CollateralType>>,
+    // 抵押品代币的mint
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [SEED_COLLATERAL_ACCOUNT, collateral_type.key().as_ref(), depositor.key().as_ref()],
+        bump = collateral_account.bump,
+    )]
+    pub collateral_account: Box<Account<'info, Collateral>>,
+    // 代币存储账户 - 抵押品从此账户转回用户
+    #[account(
+        mut,
+        seeds = [SEED_VAULT_ACCOUNT, collateral_type.key().as_ref(), depositor.key().as_ref()],
+        bump = collateral_account.bump_sol_account,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 存款人的抵押品代币账户 - 接收取回的抵押品
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = depositor,
+        token::token_program = collateral_token_program,
+    )]
+    pub depositor_collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 抵押品代币所属的代币程序（SPL Token 或 Token2022）
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+// withdraw_collateral 的代币抵押品版本：只取回抵押品代币，不偿还债务。取回后健康因子必须满足最小要求。
+pub fn process_withdraw_token_collateral(
+    ctx: Context<WithdrawTokenCollateral>,
+    amount_collateral: u64,
+) -> Result<()> {
+    ctx.accounts.collateral_type.debt.accrue(Clock::get()?.unix_timestamp)?;
+
+    ctx.accounts.collateral_account.lamport_balance = ctx
+        .accounts
+        .vault
+        .amount
+        .checked_sub(amount_collateral)
+        .ok_or(CustomError::InsufficientCollateral)?;
+    check_token_health_factor(
+        &ctx.accounts.collateral_account,
+        &ctx.accounts.collateral_type,
+        &ctx.accounts.config_account,
+        &ctx.accounts.price_update,
+    )?;
+    withdraw_tokens_internal(
+        &ctx.accounts.vault,
+        &ctx.accounts.depositor_collateral_account,
+        &ctx.accounts.collateral_mint,
+        &ctx.accounts.collateral_token_program,
+        &ctx.accounts.collateral_account,
+        amount_collateral,
+    )
+}
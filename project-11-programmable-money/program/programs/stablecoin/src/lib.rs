@@ -6,6 +6,7 @@ use instructions::*;
 use state::*;
 mod constants;
 mod error;
+mod events;
 mod instructions;
 mod state;
 
@@ -59,9 +60,16 @@ initialize_config
     设置系统管理员权限
     初始化协议基础参数
 update_config
-    更新系统配置参数，特别是最小健康因子
-    只有系统管理员可以调用
-    调整风险参数以维护系统稳定性
+    更新系统配置参数：最小健康因子、清算阈值、清算奖励和原生SOL的稳定费
+    只有系统管理员（Config.authority 签名）可以调用，写入前检查参数范围
+    发出 ConfigUpdated 事件记录修改前后的参数；紧急关停后不能再调用
+transfer_config_authority / accept_config_authority
+    两步转移管理员权限，新管理员签名接受后生效
+Token-2022 扩展 (initialize_config 参数 / set_paused / set_account_allowed / seize_tokens)
+    创建mint时可选启用：链上元数据（名称、符号、URI）、永久代理（配置账户PDA）、默认冻结
+    暂停权限可以暂停所有铸造和销毁稳定币的指令
+    管理员通过永久代理没收任意账户的稳定币（例如法院命令），协议的盈余账户和储蓄金库除外
+    默认冻结模式下新代币账户是冻结的，管理员完成KYC后通过 set_account_allowed 解冻
 deposit_collateral_and_mint
     功能：核心功能函数，允许用户存入 SOL 抵押品并铸造稳定币
     接收用户的 SOL 作为抵押品
@@ -74,23 +82,78 @@ redeem_collateral_and_burn_tokens
     检查操作后的健康因子是否符合要求
     销毁指定数量的稳定币
     将相应的 SOL 转回给用户
-liquidate
-    功能：清算机制，当用户头寸健康因子过低时，允许清算人清算该头寸 lib.rs:40-42
-    检查目标头寸是否符合清算条件
-    清算人销毁稳定币获得抵押品和清算奖励
-    维护系统整体偿付能力
+add_collateral / mint_more / repay_debt / withdraw_collateral
+    分开管理原生SOL仓位：只存入抵押品、只铸造、只还款、只取回抵押品
+    mint_more 和 withdraw_collateral 之后检查健康因子；repay_debt 可以由任何人替仓位所有者还款（救援仓位）
+    代币抵押品仓位使用 add_token_collateral / mint_more_token / repay_token_debt / withdraw_token_collateral
+start_auction / take_auction / restart_auction
+    功能：荷兰式拍卖清算原生SOL仓位（参考 MakerDAO Liquidations 2.0）
+    keeper 对健康因子过低的仓位调用 start_auction，仓位的全部SOL和债务转入拍卖账户 [auction, collateral_account]，
+    keeper 获得债务 1% 的小费（计入拍卖需要筹集的 tab）
+    价格从 预言机价格 × 120% 开始在 1 小时内线性下降，竞拍者用稳定币按当前价格买入SOL
+    tab 筹满或SOL卖完时拍卖结束，剩余SOL退还给仓位所有者；价格降到 0 时可以 restart_auction 重新起拍，
+    调用者获得剩余 tab 1% 的小费；所有小费铸造前都检查全局债务上限；关停后仍然可以成交和重新起拍，但不再铸造小费
+register_collateral_type
+    注册除原生SOL之外的抵押品代币，只有系统管理员可以调用
+    每种类型有自己的 mint、Pyth 价格源、清算阈值和债务上限（CollateralType 账户，种子 [collateral_type, mint]）
+deposit_token_collateral_and_mint / redeem_token_collateral_and_burn_tokens /
+start_token_auction / take_token_auction / restart_token_auction
+    代币抵押品版本的存入铸造、赎回销毁和拍卖清算
+    代币拍卖没收的抵押品保管在由拍卖账户控制的代币账户 [auction_vault, auction] 中，价格按该类型的价格源计算
+    每个用户每种类型一个 Collateral 仓位（种子 [collateral, collateral_type, depositor]），
+    抵押品代币保管在由PDA控制的代币账户 [vault, collateral_type, depositor] 中
+    以该类型为抵押的稳定币总量不能超过债务上限
+锚定稳定模块 (register_psm / update_psm / psm_swap_in / psm_swap_out)
+    管理员为外部稳定币（例如USDC）开通PSM，设置手续费和债务上限
+    任何人都可以按 1:1（扣除手续费）在外部稳定币和本稳定币之间兑换，外部稳定币保管在储备账户 [psm_reserve, mint]
+    套利者通过这条路径把稳定币价格维持在锚定值附近
+稳定费 (mint_surplus)
+    每个抵押品类型（原生SOL在 Config 上）按年化稳定费累计利率指数 rate_index，
+    仓位只保存标准化债务，实际债务 = normalized_debt × rate_index，赎回和清算都按实际债务计算
+    mint_surplus 把已计提的稳定费铸造到协议盈余账户 [surplus]（由配置账户PDA控制），任何人都可以调用
+储蓄模块 (initialize_savings / update_savings_rate / savings_deposit / savings_withdraw)
+    持有者存入稳定币换取份额，储蓄利率由系统管理员设置，利息从协议盈余账户发放到储蓄金库 [savings_vault]
+    份额按 total_assets / 份额总量 的兑换率赎回，兑换率随利息增长；total_assets 在程序内记账，
+    直接转入金库的稳定币不会改变兑换率
+全局债务上限 (update_config 的 debt_ceiling)
+    铸造后的稳定币总供应量不能超过 Config.debt_ceiling，适用于所有铸造稳定币的用户指令和 psm_swap_in
+紧急关停 (emergency_shutdown / record_shutdown_price / settle_vault / settle_token_vault / finalize_shutdown /
+         shutdown_lock / shutdown_redeem_sol / shutdown_redeem_token / shutdown_redeem_psm)
+    参考 MakerDAO 的 End 模块：管理员关停后停止所有铸造和仓位操作，按记录的预言机价格结算
+    任何人都可以结算仓位，覆盖债务的抵押品进入结算池，剩余抵押品退还给仓位所有者
+    等待期结束后 finalize_shutdown 固定兑付比例，持有者锁定稳定币后从每个结算池按比例兑付抵押品
 */
 
 #[program]
 pub mod stablecoin {
     use super::*;
 
-    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
-        process_initialize_config(ctx)
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: InitializeConfigParams) -> Result<()> {
+        process_initialize_config(ctx, params)
     }
 
-    pub fn update_config(ctx: Context<UpdateConfig>, min_health_factor: u64) -> Result<()> {
-        process_update_config(ctx, min_health_factor)
+    pub fn update_config(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+        process_update_config(ctx, params)
+    }
+
+    pub fn transfer_config_authority(ctx: Context<UpdateConfig>, new_authority: Pubkey) -> Result<()> {
+        process_transfer_config_authority(ctx, new_authority)
+    }
+
+    pub fn accept_config_authority(ctx: Context<AcceptConfigAuthority>) -> Result<()> {
+        process_accept_config_authority(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        process_set_paused(ctx, paused)
+    }
+
+    pub fn set_account_allowed(ctx: Context<SetAccountAllowed>, allowed: bool) -> Result<()> {
+        process_set_account_allowed(ctx, allowed)
+    }
+
+    pub fn seize_tokens(ctx: Context<SeizeTokens>, amount: u64) -> Result<()> {
+        process_seize_tokens(ctx, amount)
     }
 
     pub fn deposit_collateral_and_mint(
@@ -109,7 +172,157 @@ pub mod stablecoin {
         process_redeem_collateral_and_burn_tokens(ctx, amount_collateral, amount_to_burn)
     }
 
-    pub fn liquidate(ctx: Context<Liquidate>, amount_to_burn: u64) -> Result<()> {
-        process_liquidate(ctx, amount_to_burn)
+    pub fn add_collateral(ctx: Context<AddCollateral>, amount_collateral: u64) -> Result<()> {
+        process_add_collateral(ctx, amount_collateral)
+    }
+
+    pub fn mint_more(ctx: Context<MintMore>, amount_to_mint: u64) -> Result<()> {
+        process_mint_more(ctx, amount_to_mint)
+    }
+
+    pub fn repay_debt(ctx: Context<RepayDebt>, amount_to_burn: u64) -> Result<()> {
+        process_repay_debt(ctx, amount_to_burn)
+    }
+
+    pub fn withdraw_collateral(ctx: Context<WithdrawCollateral>, amount_collateral: u64) -> Result<()> {
+        process_withdraw_collateral(ctx, amount_collateral)
+    }
+
+    pub fn add_token_collateral(ctx: Context<AddTokenCollateral>, amount_collateral: u64) -> Result<()> {
+        process_add_token_collateral(ctx, amount_collateral)
+    }
+
+    pub fn mint_more_token(ctx: Context<MintMoreToken>, amount_to_mint: u64) -> Result<()> {
+        process_mint_more_token(ctx, amount_to_mint)
+    }
+
+    pub fn repay_token_debt(ctx: Context<RepayTokenDebt>, amount_to_burn: u64) -> Result<()> {
+        process_repay_token_debt(ctx, amount_to_burn)
+    }
+
+    pub fn withdraw_token_collateral(ctx: Context<WithdrawTokenCollateral>, amount_collateral: u64) -> Result<()> {
+        process_withdraw_token_collateral(ctx, amount_collateral)
+    }
+
+    pub fn start_auction(ctx: Context<StartAuction>) -> Result<()> {
+        process_start_auction(ctx)
+    }
+
+    pub fn take_auction(ctx: Context<TakeAuction>, max_collateral: u64, max_price: u64) -> Result<()> {
+        process_take_auction(ctx, max_collateral, max_price)
+    }
+
+    pub fn restart_auction(ctx: Context<RestartAuction>) -> Result<()> {
+        process_restart_auction(ctx)
+    }
+
+    pub fn register_collateral_type(
+        ctx: Context<RegisterCollateralType>,
+        price_feed_id: String,
+        liquidation_threshold: u64,
+        debt_ceiling: u64,
+        stability_fee: u64,
+    ) -> Result<()> {
+        process_register_collateral_type(ctx, price_feed_id, liquidation_threshold, debt_ceiling, stability_fee)
+    }
+
+    pub fn mint_surplus(ctx: Context<MintSurplus>) -> Result<()> {
+        process_mint_surplus(ctx)
+    }
+
+    pub fn register_psm(ctx: Context<RegisterPsm>, fee: u64, debt_ceiling: u64) -> Result<()> {
+        process_register_psm(ctx, fee, debt_ceiling)
+    }
+
+    pub fn update_psm(ctx: Context<UpdatePsm>, fee: u64, debt_ceiling: u64) -> Result<()> {
+        process_update_psm(ctx, fee, debt_ceiling)
+    }
+
+    pub fn psm_swap_in(ctx: Context<PsmSwap>, amount: u64) -> Result<()> {
+        process_psm_swap_in(ctx, amount)
+    }
+
+    pub fn psm_swap_out(ctx: Context<PsmSwap>, amount: u64) -> Result<()> {
+        process_psm_swap_out(ctx, amount)
+    }
+
+    pub fn initialize_savings(ctx: Context<InitializeSavings>, savings_rate: u64) -> Result<()> {
+        process_initialize_savings(ctx, savings_rate)
+    }
+
+    pub fn update_savings_rate(ctx: Context<UpdateSavingsRate>, savings_rate: u64) -> Result<()> {
+        process_update_savings_rate(ctx, savings_rate)
+    }
+
+    pub fn savings_deposit(ctx: Context<SavingsDeposit>, amount: u64) -> Result<()> {
+        process_savings_deposit(ctx, amount)
+    }
+
+    pub fn savings_withdraw(ctx: Context<SavingsWithdraw>, shares: u64) -> Result<()> {
+        process_savings_withdraw(ctx, shares)
+    }
+
+    pub fn emergency_shutdown(ctx: Context<EmergencyShutdown>) -> Result<()> {
+        process_emergency_shutdown(ctx)
+    }
+
+    pub fn record_shutdown_price(ctx: Context<RecordShutdownPrice>) -> Result<()> {
+        process_record_shutdown_price(ctx)
+    }
+
+    pub fn settle_vault(ctx: Context<SettleVault>) -> Result<()> {
+        process_settle_vault(ctx)
+    }
+
+    pub fn settle_token_vault(ctx: Context<SettleTokenVault>) -> Result<()> {
+        process_settle_token_vault(ctx)
+    }
+
+    pub fn finalize_shutdown(ctx: Context<FinalizeShutdown>) -> Result<()> {
+        process_finalize_shutdown(ctx)
+    }
+
+    pub fn shutdown_lock(ctx: Context<ShutdownLock>, amount: u64) -> Result<()> {
+        process_shutdown_lock(ctx, amount)
+    }
+
+    pub fn shutdown_redeem_sol(ctx: Context<ShutdownRedeemSol>, amount: u64) -> Result<()> {
+        process_shutdown_redeem_sol(ctx, amount)
+    }
+
+    pub fn shutdown_redeem_token(ctx: Context<ShutdownRedeemToken>, amount: u64) -> Result<()> {
+        process_shutdown_redeem_token(ctx, amount)
+    }
+
+    pub fn shutdown_redeem_psm(ctx: Context<ShutdownRedeemPsm>, amount: u64) -> Result<()> {
+        process_shutdown_redeem_psm(ctx, amount)
+    }
+
+    pub fn deposit_token_collateral_and_mint(
+        ctx: Context<DepositTokenCollateralAndMintTokens>,
+        amount_collateral: u64,
+        amount_to_mint: u64,
+    ) -> Result<()> {
+        process_deposit_token_collateral_and_mint_tokens(ctx, amount_collateral, amount_to_mint)
+    }
+
+    pub fn redeem_token_collateral_and_burn_tokens(
+        ctx: Context<RedeemTokenCollateralAndBurnTokens>,
+        amount_collateral: u64,
+        amount_to_burn: u64,
+    ) -> Result<()> {
+        process_redeem_token_collateral_and_burn_tokens(ctx, amount_collateral, amount_to_burn)
+    }
+
+    pub fn start_token_auction(ctx: Context<StartTokenAuction>) -> Result<()> {
+        process_start_token_auction(ctx)
+    }
+
+    pub fn take_token_auction(ctx: Context<TakeTokenAuction>, max_collateral: u64, max_price: u64) -> Result<()> {
+        process_take_token_auction(ctx, max_collateral, max_price)
+    }
+
+    pub fn restart_token_auction(ctx: Context<RestartTokenAuction>) -> Result<()> {
+        process_restart_token_auction(ctx)
     }
 }
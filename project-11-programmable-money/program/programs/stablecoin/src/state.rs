@@ -1,26 +1,58 @@
 use anchor_lang::prelude::*;
+use crate::{
+    error::CustomError, AUCTION_DURATION, AUCTION_KEEPER_TIP, AUCTION_PRICE_PREMIUM, BPS_DENOMINATOR, HEALTH_FACTOR_PRECISION,
+    MAX_LIQUIDATION_BONUS, MAX_STABILITY_FEE, SECONDS_PER_YEAR, WAD,
+};
 
 #[account]
 #[derive(InitSpace, Debug)]
 pub struct Collateral {
     // 抵押品存款人的钱包地址 - 标识谁拥有这个抵押品账户
     pub depositor: Pubkey,     // depositor wallet address
-    // 存款人的SOL抵押品PDA账户地址 - SOL将存入此账户作为抵押品
-    pub sol_account: Pubkey,   // depositor pda collateral account (deposit SOL to this account)
+    // 存款人的抵押品PDA账户地址 - 原生SOL仓位为 [sol, depositor] 系统账户，
+    // 代币仓位为 [vault, collateral_type, depositor] 代币账户（由自身PDA控制）
+    pub sol_account: Pubkey,   // depositor pda collateral account (SOL account, or token vault for token collateral)
     // 存款人的关联代币账户地址 - 稳定币将铸造到此账户
     pub token_account: Pubkey, // depositor ata token account (mint stablecoins to this account)
-    // 当前SOL账户的lamport余额 - 用于健康因子计算
-    // lamport是SOL的最小单位（1 SOL = 10^9 lamports）
-    pub lamport_balance: u64, // current lamport balance of depositor sol_account (for health check calculation)
-    // 当前已铸造的稳定币数量 - 用于健康因子计算
-    // 以基础单位表示，已调整小数精度
-    pub amount_minted: u64, // current amount stablecoins minted, base unit adjusted for decimal precision (for health check calculation)
+    // 当前抵押品余额 - 用于健康因子计算
+    // 原生SOL仓位为lamport余额（1 SOL = 10^9 lamports），代币仓位为代币最小单位数量
+    pub lamport_balance: u64, // current collateral balance of depositor sol_account, in lamports or token base units (for health check calculation)
+    // 标准化债务 - 铸造数量除以铸造时的累计利率指数
+    // 实际债务 = 标准化债务 × 当前利率指数，随稳定费增长（见 DebtState）
+    pub normalized_debt: u64, // debt divided by the rate index at mint time, actual debt = normalized_debt * rate_index (for health check calculation)
     // 此抵押品账户PDA的bump种子值 - 用于PDA验证
     pub bump: u8,           // store bump seed for this collateral account PDA
-    // SOL账户PDA的bump种子值 - 用于PDA验证
+    // SOL账户（或代币存储账户）PDA的bump种子值 - 用于PDA签名
     pub bump_sol_account: u8, // store bump seed for the  sol_account PDA
     // 账户数据是否已初始化的标志 - 防止重复初始化覆盖某些字段
     pub is_initialized: bool, // indicate if account data has already been initialized (for check to prevent overriding certain fields)
+    // 抵押品类型注册账户地址 - 原生SOL仓位为默认公钥，参数来自 Config
+    pub collateral_type: Pubkey, // CollateralType registry account, default pubkey for the native SOL position
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct CollateralType {
+    // 抵押品代币的mint地址
+    pub mint: Pubkey,               // collateral token mint
+    // 抵押品代币的小数位数 - 用于价格换算
+    pub mint_decimals: u8,          // decimals of the collateral mint
+    // 该代币的Pyth价格源ID（<TOKEN>/USD）
+    pub price_feed_id: [u8; 32],    // pyth <TOKEN>/USD feed id
+    // 清算阈值 - 与 Config.liquidation_threshold 含义相同，例如 50 表示需要 200% 的抵押率
+    pub liquidation_threshold: u64, // determines how much extra collateral is required for this type
+    // 债务上限 - 以该类型为抵押铸造的稳定币总量（含稳定费）上限
+    pub debt_ceiling: u64,          // max stablecoins outstanding against this collateral type
+    // 该类型的稳定费和债务总量
+    pub debt: DebtState,            // stability fee and total debt of this collateral type
+    // 此注册账户PDA的bump种子值 - 用于PDA验证
+    pub bump: u8,                   // store bump seed for this collateral type PDA
+    // 紧急关停时记录的价格（每个完整代币的美元价格，9位小数），0 表示未记录
+    pub shutdown_price: u64,        // USD price per whole token recorded after shutdown, 0 until recorded
+    // 关停结算时抵押品不足以覆盖债务的部分（代币基础单位）
+    pub shutdown_gap: u64,          // collateral short of covering settled debt, in token base units
+    // 每个稳定币可兑付的抵押品数量（WAD），首次兑付时计算，0 表示尚未计算
+    pub shutdown_fix: u128,         // collateral paid per stablecoin unit (WAD), set on the first redeem
 }
 
 #[account]
@@ -36,11 +68,398 @@ pub struct Config {
     // 清算奖励百分比 - 清算者执行清算时获得的额外lamport奖励
     // 例如：10表示清算者获得10%的额外奖励
     pub liquidation_bonus: u64,     // % bonus lamports to liquidator for liquidating an account
-    // 最小健康因子 - 如果低于此值，抵押品账户可被清算
-    // 健康因子 = (抵押品价值 * 清算阈值) / 已铸造稳定币价值
-    pub min_health_factor: u64, // minimum health factor, if below min then Collateral account can be liquidated
+    // 最小健康因子（基点，10_000 = 1.0） - 如果低于此值，抵押品账户可被清算
+    // 健康因子 = (抵押品价值 * 清算阈值) / 债务价值
+    pub min_health_factor: u64, // minimum health factor in bps (10_000 = 1.0), below it the Collateral account can be liquidated
+    // 原生SOL仓位的稳定费和债务总量
+    pub debt: DebtState,        // stability fee and total debt of native SOL positions
     // 此配置账户PDA的bump种子值 - 用于PDA验证
     pub bump: u8,               // store bump seed for this config account
     // 稳定币铸造账户PDA的bump种子值 - 用于PDA验证
     pub bump_mint_account: u8,  // store bump seed for the stablecoin mint account PDA
+    // 待接受的新管理员 - 两步转移管理员权限，默认公钥表示没有待处理的转移
+    pub pending_authority: Pubkey, // proposed new authority, must sign accept_config_authority to take over
+    // 暂停权限 - 可以暂停所有铸造和销毁稳定币的指令
+    pub pause_authority: Pubkey,   // can halt every instruction that mints or burns the stablecoin
+    // 是否已暂停
+    pub paused: bool,              // mint and burn instructions fail while true
+    // mint 是否启用了永久代理（代理为配置账户PDA，由管理员通过 seize_tokens 使用）
+    pub permanent_delegate: bool,  // mint has the permanent delegate extension, delegated to this config PDA
+    // mint 是否启用了默认冻结（新代币账户需要管理员通过 set_account_allowed 解冻）
+    pub default_frozen: bool,      // new token accounts start frozen until allowed by the authority
+    // 全局债务上限 - 铸造后的稳定币总供应量不能超过此值
+    pub debt_ceiling: u64,         // max total stablecoin supply after any user mint
+    // 紧急关停后等待多少秒才能 finalize_shutdown
+    pub shutdown_wait: i64,        // seconds between emergency_shutdown and finalize_shutdown
+    // 是否已紧急关停
+    pub shutdown: bool,            // set by emergency_shutdown, never cleared
+}
+
+impl Config {
+    // 当前可由 update_config 修改的参数
+    pub fn params(&self) -> ConfigParams {
+        ConfigParams {
+            min_health_factor: self.min_health_factor,
+            liquidation_threshold: self.liquidation_threshold,
+            liquidation_bonus: self.liquidation_bonus,
+            stability_fee: self.debt.stability_fee,
+            debt_ceiling: self.debt_ceiling,
+            shutdown_wait: self.shutdown_wait,
+        }
+    }
+
+    // 参数范围检查：
+    //     最小健康因子 ≥ HEALTH_FACTOR_PRECISION（1.0），低于 1.0 时债务超过按清算阈值折算的抵押品价值仍不能被清算
+    //     清算阈值在 (0, 100] 之间，清算奖励不超过 MAX_LIQUIDATION_BONUS
+    //     阈值 × (100 + 奖励) ≤ 100 × 100：健康因子刚跌破 1 时，抵押品足够支付债务加清算奖励
+    //     稳定费不超过 MAX_STABILITY_FEE
+    //     关停等待时间不为负
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_health_factor >= HEALTH_FACTOR_PRECISION,
+            CustomError::InvalidConfigParams
+        );
+        validate_liquidation_params(self.liquidation_threshold, self.liquidation_bonus)?;
+        require!(
+            self.debt.stability_fee <= MAX_STABILITY_FEE,
+            CustomError::InvalidConfigParams
+        );
+        require!(self.shutdown_wait >= 0, CustomError::InvalidConfigParams);
+        Ok(())
+    }
+}
+
+// 清算阈值和清算奖励的范围检查，Config 和 CollateralType 共用
+pub fn validate_liquidation_params(liquidation_threshold: u64, liquidation_bonus: u64) -> Result<()> {
+    require!(
+        liquidation_threshold > 0 && liquidation_threshold <= 100,
+        CustomError::InvalidConfigParams
+    );
+    require!(
+        liquidation_bonus <= MAX_LIQUIDATION_BONUS,
+        CustomError::InvalidConfigParams
+    );
+    require!(
+        liquidation_threshold * (100 + liquidation_bonus) <= 100 * 100,
+        CustomError::InvalidConfigParams
+    );
+    Ok(())
+}
+
+// update_config 可修改的参数，None 表示保持不变
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct UpdateConfigParams {
+    pub min_health_factor: Option<u64>,
+    pub liquidation_threshold: Option<u64>,
+    pub liquidation_bonus: Option<u64>,
+    pub stability_fee: Option<u64>,
+    pub debt_ceiling: Option<u64>,
+    pub shutdown_wait: Option<i64>,
+}
+
+// initialize_config 的参数：创建稳定币mint时可选启用的 Token-2022 扩展
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct InitializeConfigParams {
+    // 链上元数据（名称、符号、URI），保存在mint账户本身
+    pub metadata: Option<MintMetadata>,
+    // 暂停权限，不传时为管理员
+    pub pause_authority: Option<Pubkey>,
+    // 启用永久代理扩展
+    pub permanent_delegate: bool,
+    // 启用默认冻结扩展（KYC部署使用允许名单）
+    pub default_frozen: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MintMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+// ConfigUpdated 事件中记录的参数快照
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfigParams {
+    pub min_health_factor: u64,
+    pub liquidation_threshold: u64,
+    pub liquidation_bonus: u64,
+    pub stability_fee: u64,
+    pub debt_ceiling: u64,
+    pub shutdown_wait: i64,
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Psm {
+    // 外部稳定币（例如USDC）的mint地址
+    pub stable_mint: Pubkey,    // approved external stablecoin mint
+    // 外部稳定币的小数位数 - 用于与本稳定币 1:1 换算
+    pub stable_decimals: u8,    // decimals of the external stablecoin
+    // 储备账户地址 - 保管用户换入的外部稳定币
+    pub reserve: Pubkey,        // reserve token account holding the external stablecoin
+    // 兑换手续费（基点），换入和换出都收取
+    pub fee: u64,               // swap fee in bps, charged in both directions
+    // 债务上限 - 通过该PSM铸造的稳定币总量上限
+    pub debt_ceiling: u64,      // max stablecoins outstanding minted through this PSM
+    // 当前通过该PSM铸造的稳定币总量
+    pub total_minted: u64,      // stablecoins currently outstanding minted through this PSM
+    // 此PSM账户PDA的bump种子值
+    pub bump: u8,               // store bump seed for this psm account PDA
+    // 储备账户PDA的bump种子值 - 用于储备账户签名
+    pub bump_reserve: u8,       // store bump seed for the reserve PDA
+    // 紧急关停后每个稳定币可兑付的外部稳定币数量（WAD），首次兑付时计算，0 表示尚未计算
+    pub shutdown_fix: u128,     // external stable paid per stablecoin unit after shutdown (WAD)
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Savings {
+    // 年化储蓄利率（基点），由协议管理员设置
+    pub savings_rate: u64,      // annual savings rate in bps, set by Config.authority
+    // 所有用户的份额总量
+    pub total_shares: u64,      // total shares issued to savers
+    // 份额对应的稳定币总量 - 存入、赎回和发放利息时更新，不读取金库余额，
+    // 直接转入金库的稳定币不会改变兑换率（防止首个储户通过捐赠抬高份额价格）
+    pub total_assets: u64,      // stablecoins owed to savers, tracked internally rather than read from the vault
+    // 上次从盈余账户发放利息的时间戳
+    pub last_accrued: i64,      // unix timestamp of the last drip from surplus
+    // 此储蓄账户PDA的bump种子值 - 也用于金库转出签名
+    pub bump: u8,               // store bump seed for this savings PDA
+    // 金库PDA的bump种子值
+    pub bump_vault: u8,         // store bump seed for the savings vault PDA
+}
+
+impl Savings {
+    // 从上次发放到 now 按储蓄利率应付的利息（向下取整）
+    pub fn interest(&self, now: i64) -> Result<u64> {
+        let elapsed = (now - self.last_accrued).max(0) as u128;
+        (self.total_assets as u128 * self.savings_rate as u128 * elapsed / (BPS_DENOMINATOR * SECONDS_PER_YEAR))
+            .try_into()
+            .map_err(|_| error!(CustomError::MathOverflow))
+    }
+
+    // 存入 amount 获得的份额（向下取整），没有份额时 1:1
+    pub fn shares_for_amount(&self, amount: u64) -> Result<u64> {
+        if self.total_shares == 0 || self.total_assets == 0 {
+            return Ok(amount);
+        }
+        (amount as u128 * self.total_shares as u128 / self.total_assets as u128)
+            .try_into()
+            .map_err(|_| error!(CustomError::MathOverflow))
+    }
+
+    // 赎回 shares 得到的稳定币数量（向下取整）
+    pub fn amount_for_shares(&self, shares: u64) -> Result<u64> {
+        if self.total_shares == 0 {
+            return Ok(0);
+        }
+        (shares as u128 * self.total_assets as u128 / self.total_shares as u128)
+            .try_into()
+            .map_err(|_| error!(CustomError::MathOverflow))
+    }
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct SavingsPosition {
+    // 份额所有者的钱包地址
+    pub owner: Pubkey,          // saver wallet address
+    // 持有的储蓄份额
+    pub shares: u64,            // savings shares held
+    // 此账户PDA的bump种子值
+    pub bump: u8,               // store bump seed for this savings position PDA
+}
+
+/**
+紧急关停（参考 MakerDAO 的 End 模块）。emergency_shutdown 创建本账户并停止所有铸造和仓位操作：
+    1. 记录原生SOL的价格，各代币抵押品类型通过 record_shutdown_price 记录价格
+    2. 任何人可以 settle_vault 结算仓位：按关停价格覆盖债务的抵押品进入结算池，剩余抵押品退还给仓位所有者
+    3. 等待 shutdown_wait 秒后 finalize_shutdown：销毁协议盈余，固定可兑付的稳定币总量 supply
+    4. 持有者通过 shutdown_lock 锁定（销毁）稳定币，再从每个结算池按比例兑付抵押品
+原生SOL的结算池就是本账户自身的 lamports（扣除租金），代币抵押品为 [shutdown_vault, collateral_type]，PSM为其储备账户。
+**/
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Shutdown {
+    // 紧急关停的时间戳，原生SOL和各抵押品类型的稳定费计提到此时为止
+    pub time: i64,                  // unix timestamp of emergency_shutdown
+    // 关停时的SOL价格（每个SOL的美元价格，9位小数）
+    pub sol_price: u64,             // USD price per SOL at shutdown, 9 decimals
+    // 关停时原生SOL仓位的债务总量
+    pub sol_debt: u64,              // total debt of native SOL positions at shutdown
+    // 原生SOL仓位结算时抵押品不足以覆盖债务的部分（lamports）
+    pub sol_gap: u64,               // lamports short of covering settled SOL debt
+    // 是否已完成 finalize_shutdown
+    pub finalized: bool,            // set by finalize_shutdown
+    // 可兑付抵押品的稳定币总量（finalize_shutdown 时的总供应量）
+    pub supply: u64,                // stablecoin supply fixed by finalize_shutdown
+    // 每个稳定币可兑付的lamports（WAD）
+    pub sol_fix: u128,              // lamports paid per stablecoin unit (WAD)
+    // 此账户PDA的bump种子值
+    pub bump: u8,                   // store bump seed for this shutdown PDA
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct ShutdownClaim {
+    // 锁定稳定币的用户
+    pub owner: Pubkey,              // holder wallet address
+    // 已锁定（销毁）的稳定币数量，每个结算池最多兑付这么多
+    pub amount: u64,                // stablecoins locked, the most the holder can redeem from each pool
+    // 此账户PDA的bump种子值
+    pub bump: u8,                   // store bump seed for this claim PDA
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct ShutdownCash {
+    // 已经在该结算池兑付的稳定币数量，不能超过 ShutdownClaim.amount
+    pub amount: u64,                // locked stablecoins already redeemed from this pool
+    // 此账户PDA的bump种子值
+    pub bump: u8,                   // store bump seed for this cash PDA
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Auction {
+    // 被清算的抵押品账户
+    pub collateral_account: Pubkey, // collateral account being liquidated
+    // 抵押品类型注册账户 - 原生SOL拍卖为默认公钥，代币拍卖的抵押品保管在 [auction_vault, auction] 代币账户中
+    pub collateral_type: Pubkey,    // CollateralType of the position, default pubkey for native SOL
+    // 发起拍卖的 keeper - 拍卖结束后取回账户租金
+    pub keeper: Pubkey,             // keeper who started the auction, receives the rent back
+    // 剩余待售的抵押品（lamports 或代币基础单位）
+    pub lot: u64,                   // collateral left for sale, in lamports or token base units
+    // 剩余需要筹集的稳定币（债务 + keeper 小费）
+    pub tab: u64,                   // stablecoins still to raise (debt + keeper tip)
+    // 起拍价（每个完整抵押品单位的美元价格，9位小数）
+    pub start_price: u64,           // starting USD price per whole SOL or token, 9 decimals
+    // 起拍时间戳
+    pub start_time: i64,            // unix timestamp the price started decaying from
+    // 此拍卖账户PDA的bump种子值
+    pub bump: u8,                   // store bump seed for this auction PDA
+}
+
+impl Auction {
+    // 当前价格：从起拍价开始在 AUCTION_DURATION 秒内线性降到 0
+    pub fn price(&self, now: i64) -> u64 {
+        let elapsed = (now - self.start_time).max(0);
+        if elapsed >= AUCTION_DURATION {
+            return 0;
+        }
+        (self.start_price as u128 * (AUCTION_DURATION - elapsed) as u128 / AUCTION_DURATION as u128) as u64
+    }
+
+    // 按价格 price（每 unit 个最小单位的美元价格）买入最多 max_collateral，返回 (抵押品数量, 稳定币成本)
+    // 成本向上取整，避免按 0 价格买走少量抵押品；超过剩余 tab 时只按 tab 成交，多出的抵押品留在拍卖中
+    pub fn bid(&self, max_collateral: u64, price: u64, unit: u64) -> (u64, u64) {
+        let amount = max_collateral.min(self.lot);
+        let cost = (amount as u128 * price as u128).div_ceil(unit as u128);
+        if cost < self.tab as u128 {
+            return (amount, cost as u64);
+        }
+        let amount = ((self.tab as u128 * unit as u128 / price as u128) as u64).min(self.lot);
+        (amount, self.tab)
+    }
+}
+
+// keeper 小费：amount 的 AUCTION_KEEPER_TIP 基点，计入拍卖需要筹集的 tab
+pub fn auction_keeper_tip(amount: u64) -> u64 {
+    (amount as u128 * AUCTION_KEEPER_TIP as u128 / BPS_DENOMINATOR) as u64
+}
+
+// 起拍价 = 预言机价格 × AUCTION_PRICE_PREMIUM
+pub fn auction_start_price(price: u128) -> Result<u64> {
+    (price * AUCTION_PRICE_PREMIUM as u128 / BPS_DENOMINATOR)
+        .try_into()
+        .map_err(|_| error!(CustomError::MathOverflow))
 }
+
+/**
+稳定费：按年化费率累计的利率指数，参考 MakerDAO 的 rate 累加器。
+每个抵押品类型（原生SOL在 Config 上）有一个 DebtState：
+    仓位只保存标准化债务 normalized_debt，实际债务 = normalized_debt × rate_index / WAD
+    每次涉及债务的指令开始时调用 accrue，rate_index 按 stability_fee × 经过时间 增长
+    增长部分对应的稳定币记入 pending_fees，由 mint_surplus 铸造到协议盈余账户
+**/
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Debug)]
+pub struct DebtState {
+    // 年化稳定费（基点），例如 200 表示 2%
+    pub stability_fee: u64,         // annualized stability fee in bps
+    // 累计利率指数（WAD），初始为 1.0
+    pub rate_index: u128,           // cumulative rate index, WAD, starts at 1.0
+    // 上次计息时间戳
+    pub last_accrued: i64,          // unix timestamp of the last accrual
+    // 所有仓位的标准化债务之和
+    pub total_normalized_debt: u64, // sum of normalized_debt over all positions
+    // 已计提但尚未铸造到盈余账户的稳定费
+    pub pending_fees: u64,          // accrued fees not yet minted to the surplus account
+}
+
+impl DebtState {
+    pub fn new(stability_fee: u64, now: i64) -> Self {
+        DebtState {
+            stability_fee,
+            rate_index: WAD,
+            last_accrued: now,
+            total_normalized_debt: 0,
+            pending_fees: 0,
+        }
+    }
+
+    // 把上次计息以来的稳定费计入利率指数和 pending_fees
+    pub fn accrue(&mut self, now: i64) -> Result<()> {
+        let elapsed = now - self.last_accrued;
+        if elapsed <= 0 {
+            return Ok(());
+        }
+        // 指数增长 = rate_index × stability_fee × elapsed / (10000 × 一年秒数)
+        let growth = self
+            .rate_index
+            .checked_mul(self.stability_fee as u128 * elapsed as u128)
+            .ok_or(CustomError::MathOverflow)?
+            / (BPS_DENOMINATOR * SECONDS_PER_YEAR);
+        let old_debt = self.total_debt()?;
+        self.rate_index = self.rate_index.checked_add(growth).ok_or(CustomError::MathOverflow)?;
+        let fees = self.total_debt()?.saturating_sub(old_debt);
+        self.pending_fees = self.pending_fees.checked_add(fees).ok_or(CustomError::MathOverflow)?;
+        self.last_accrued = now;
+        Ok(())
+    }
+
+    // 标准化债务换算为实际债务（向上取整，对协议有利）
+    pub fn debt_amount(&self, normalized_debt: u64) -> Result<u64> {
+        let debt = (normalized_debt as u128 * self.rate_index).div_ceil(WAD);
+        u64::try_from(debt).map_err(|_| CustomError::MathOverflow.into())
+    }
+
+    // 所有仓位的实际债务之和
+    pub fn total_debt(&self) -> Result<u64> {
+        self.debt_amount(self.total_normalized_debt)
+    }
+
+    // 新铸造 amount 个稳定币，返回增加的标准化债务（向上取整）
+    pub fn add_debt(&mut self, amount: u64) -> Result<u64> {
+        let normalized = u64::try_from((amount as u128 * WAD).div_ceil(self.rate_index))
+            .map_err(|_| CustomError::MathOverflow)?;
+        self.total_normalized_debt = self
+            .total_normalized_debt
+            .checked_add(normalized)
+            .ok_or(CustomError::MathOverflow)?;
+        Ok(normalized)
+    }
+
+    // 偿还 amount 个稳定币，返回减少的标准化债务（向下取整）
+    // 还清全部债务时直接清零，避免留下无法偿还的零头
+    pub fn remove_debt(&mut self, normalized_debt: u64, amount: u64) -> Result<u64> {
+        let debt = self.debt_amount(normalized_debt)?;
+        require!(amount <= debt, CustomError::AmountExceedsDebt);
+        let normalized = if amount == debt {
+            normalized_debt
+        } else {
+            ((amount as u128 * WAD) / self.rate_index) as u64
+        };
+        self.total_normalized_debt = self.total_normalized_debt.saturating_sub(normalized);
+        Ok(normalized)
+    }
+}
\ No newline at end of file
@@ -2,6 +2,17 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Stablecoin } from "../target/types/stablecoin";
 import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
+import {
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  getMint,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  transferChecked,
+} from "@solana/spl-token";
+import { assert } from "chai";
 
 describe("stablecoin", () => {
   const provider = anchor.AnchorProvider.env();
@@ -25,9 +36,32 @@ describe("stablecoin", () => {
     program.programId
   );
 
+  // 代币抵押品和PSM测试创建的 mint，紧急关停测试中结算和兑付
+  let collateralMint: anchor.web3.PublicKey;
+  let usdcMint: anchor.web3.PublicKey;
+
+  // update_config 参数，未指定的字段传 null 保持不变
+  function configParams(overrides: Record<string, unknown> = {}) {
+    return {
+      minHealthFactor: null,
+      liquidationThreshold: null,
+      liquidationBonus: null,
+      stabilityFee: null,
+      debtCeiling: null,
+      shutdownWait: null,
+      ...overrides,
+    };
+  }
+
   it("Is initialized!", async () => {
+    // 启用元数据和永久代理；默认冻结需要单独部署测试（配置只能初始化一次）
     const tx = await program.methods
-      .initializeConfig()
+      .initializeConfig({
+        metadata: { name: "USD Stablecoin", symbol: "USDS", uri: "" },
+        pauseAuthority: null,
+        permanentDelegate: true,
+        defaultFrozen: false,
+      })
       .accounts({})
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Your transaction signature", tx);
@@ -59,29 +93,1005 @@ describe("stablecoin", () => {
     console.log("Your transaction signature", tx);
   });
 
-  // Increase minimum health threshold to test liquidate
+  // Increase minimum health threshold to test liquidation auctions
   it("Update Config", async () => {
     const tx = await program.methods
-      .updateConfig(new anchor.BN(100))
+      .updateConfig(configParams({ minHealthFactor: new anchor.BN(100_000_000) }))
       .accounts({})
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Your transaction signature", tx);
   });
 
-  it("Liquidate", async () => {
-    const amountToBurn = 500_000_000;
-    const tx = await program.methods
-      .liquidate(new anchor.BN(amountToBurn))
+  // 荷兰式拍卖清算：同一个钱包发起拍卖，再用持有的稳定币买入一部分抵押品
+  // 钱包的稳定币（含小费）不足以覆盖计提了稳定费的 tab，只成交一部分并检查剩余的 tab
+  it("Start and Take Auction", async () => {
+    const [auctionAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), collateralAccount.toBuffer()],
+      program.programId
+    );
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .startAuction()
       .accounts({ collateralAccount, priceUpdate: solUsdPriceFeedAccount })
-      .rpc({ skipPreflight: true, commitment: "confirmed" });
-    console.log("Your transaction signature", tx);
+      .rpc({ commitment: "confirmed" });
+
+    const auction = await program.account.auction.fetch(auctionAccount);
+    const collateral = await program.account.collateral.fetch(collateralAccount);
+    assert.isTrue(auction.lot.gtn(0));
+    assert.ok(auction.collateralType.equals(anchor.web3.PublicKey.default));
+    // tab = 债务 + 1% 小费
+    assert.isTrue(auction.tab.gtn(500_000_000));
+    assert.equal(collateral.lamportBalance.toNumber(), 0);
+    assert.equal(collateral.normalizedDebt.toNumber(), 0);
+
+    // 价格高于 max_price 时失败
+    try {
+      await program.methods
+        .takeAuction(auction.lot, new anchor.BN(1))
+        .accounts({
+          auctionAccount,
+          depositor: wallet.publicKey,
+          keeper: wallet.publicKey,
+        })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("bid above max price should fail");
+    } catch (err) {
+      assert.include(String(err), "AuctionPriceTooHigh");
+    }
+
+    // 0.0001 SOL 的成本远小于 tab，按当前价格全部成交
+    const amount = 100_000;
+    const balanceBefore = await connection.getTokenAccountBalance(tokenAccount, "confirmed");
+    await program.methods
+      .takeAuction(new anchor.BN(amount), new anchor.BN("18446744073709551615"))
+      .accounts({
+        auctionAccount,
+        depositor: wallet.publicKey,
+        keeper: wallet.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+    const balanceAfter = await connection.getTokenAccountBalance(tokenAccount, "confirmed");
+    const paid = new anchor.BN(balanceBefore.value.amount).sub(new anchor.BN(balanceAfter.value.amount));
+    assert.isTrue(paid.gtn(0));
+
+    // 拍卖仍在进行：lot 和 tab 按成交数量减少
+    const remaining = await program.account.auction.fetch(auctionAccount);
+    assert.equal(remaining.lot.toString(), auction.lot.subn(amount).toString());
+    assert.equal(remaining.tab.toString(), auction.tab.sub(paid).toString());
   });
 
   it("Update Config", async () => {
     const tx = await program.methods
-      .updateConfig(new anchor.BN(1))
+      .updateConfig(configParams({ minHealthFactor: new anchor.BN(10_000) }))
       .accounts({})
       .rpc({ skipPreflight: true, commitment: "confirmed" });
     console.log("Your transaction signature", tx);
   });
+
+  // 分开管理仓位：只存入、只铸造、第三方还款、只取回
+  it("Manage position without a full redeem", async () => {
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .addCollateral(new anchor.BN(1_000_000_000))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .mintMore(new anchor.BN(1_000_000_000))
+      .accounts({ priceUpdate: solUsdPriceFeedAccount, tokenAccount })
+      .rpc({ commitment: "confirmed" });
+    let collateral = await program.account.collateral.fetch(collateralAccount);
+    assert.equal(collateral.lamportBalance.toNumber(), 1_000_000_000);
+    assert.isTrue(collateral.normalizedDebt.gtn(0));
+
+    // 第三方用自己的稳定币替仓位所有者还款
+    const rescuer = anchor.web3.Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(rescuer.publicKey, 1_000_000_000),
+      "confirmed"
+    );
+    const rescuerAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      mintAccount,
+      rescuer.publicKey,
+      false,
+      "confirmed",
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await transferChecked(
+      connection,
+      wallet.payer,
+      tokenAccount,
+      mintAccount,
+      rescuerAccount.address,
+      wallet.payer,
+      500_000_000,
+      9,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const debtBefore = collateral.normalizedDebt;
+    await program.methods
+      .repayDebt(new anchor.BN(500_000_000))
+      .accounts({ payer: rescuer.publicKey, collateralAccount })
+      .signers([rescuer])
+      .rpc({ commitment: "confirmed" });
+    collateral = await program.account.collateral.fetch(collateralAccount);
+    assert.isTrue(collateral.normalizedDebt.lt(debtBefore));
+
+    // 取回过多抵押品会让健康因子低于最小值
+    try {
+      await program.methods
+        .withdrawCollateral(new anchor.BN(999_000_000))
+        .accounts({ priceUpdate: solUsdPriceFeedAccount })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("withdrawal below min health factor should fail");
+    } catch (err) {
+      assert.include(String(err), "BelowMinimumHealthFactor");
+    }
+    await program.methods
+      .withdrawCollateral(new anchor.BN(100_000_000))
+      .accounts({ priceUpdate: solUsdPriceFeedAccount })
+      .rpc({ commitment: "confirmed" });
+    collateral = await program.account.collateral.fetch(collateralAccount);
+    assert.equal(collateral.lamportBalance.toNumber(), 900_000_000);
+  });
+
+  // 代币抵押品：用一个测试代币注册抵押品类型，价格源借用 SOL/USD
+  it("Register Collateral Type and Mint USDS against it", async () => {
+    collateralMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      9
+    );
+    const depositorCollateralAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      collateralMint,
+      wallet.publicKey
+    );
+    await mintTo(
+      connection,
+      wallet.payer,
+      collateralMint,
+      depositorCollateralAccount.address,
+      wallet.payer,
+      10_000_000_000
+    );
+
+    const debtCeiling = 2_000_000_000;
+    await program.methods
+      .registerCollateralType(
+        SOL_PRICE_FEED_ID,
+        new anchor.BN(50),
+        new anchor.BN(debtCeiling),
+        new anchor.BN(500)
+      )
+      .accounts({ collateralMint })
+      .rpc({ commitment: "confirmed" });
+
+    const [collateralType] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral_type"), collateralMint.toBuffer()],
+      program.programId
+    );
+    const [tokenCollateralAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("collateral"),
+        collateralType.toBuffer(),
+        wallet.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .depositTokenCollateralAndMint(
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(1_000_000_000)
+      )
+      .accounts({
+        collateralMint,
+        depositorCollateralAccount: depositorCollateralAccount.address,
+        priceUpdate: solUsdPriceFeedAccount,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const position = await program.account.collateral.fetch(tokenCollateralAccount);
+    assert.ok(position.collateralType.equals(collateralType));
+    assert.equal(position.lamportBalance.toNumber(), 1_000_000_000);
+    // 刚铸造时利率指数约为 1.0，标准化债务不超过铸造数量
+    assert.isAtMost(position.normalizedDebt.toNumber(), 1_000_000_000);
+
+    // 超过该类型的债务上限
+    try {
+      await program.methods
+        .depositTokenCollateralAndMint(
+          new anchor.BN(5_000_000_000),
+          new anchor.BN(debtCeiling)
+        )
+        .accounts({
+          collateralMint,
+          depositorCollateralAccount: depositorCollateralAccount.address,
+          priceUpdate: solUsdPriceFeedAccount,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("expected DebtCeilingExceeded");
+    } catch (err) {
+      assert.include(String(err), "DebtCeilingExceeded");
+    }
+
+    await program.methods
+      .redeemTokenCollateralAndBurnTokens(
+        new anchor.BN(500_000_000),
+        new anchor.BN(500_000_000)
+      )
+      .accounts({
+        collateralMint,
+        depositorCollateralAccount: depositorCollateralAccount.address,
+        tokenAccount: position.tokenAccount,
+        priceUpdate: solUsdPriceFeedAccount,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const type = await program.account.collateralType.fetch(collateralType);
+    assert.isAtMost(type.debt.totalNormalizedDebt.toNumber(), 500_000_000);
+  });
+
+  // 代币抵押品的拍卖清算：另一个用户的代币仓位被没收，钱包作为 keeper 和竞拍者买入
+  it("Start and Take Token Auction", async () => {
+    const borrower = anchor.web3.Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(borrower.publicKey, 1_000_000_000),
+      "confirmed"
+    );
+    const borrowerCollateralAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      collateralMint,
+      borrower.publicKey
+    );
+    await mintTo(
+      connection,
+      wallet.payer,
+      collateralMint,
+      borrowerCollateralAccount.address,
+      wallet.payer,
+      1_000_000_000
+    );
+    await program.methods
+      .depositTokenCollateralAndMint(
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(400_000_000)
+      )
+      .accounts({
+        depositor: borrower.publicKey,
+        collateralMint,
+        depositorCollateralAccount: borrowerCollateralAccount.address,
+        priceUpdate: solUsdPriceFeedAccount,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([borrower])
+      .rpc({ commitment: "confirmed" });
+
+    const [collateralType] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral_type"), collateralMint.toBuffer()],
+      program.programId
+    );
+    const [borrowerPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), collateralType.toBuffer(), borrower.publicKey.toBuffer()],
+      program.programId
+    );
+    const [auctionAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), borrowerPosition.toBuffer()],
+      program.programId
+    );
+    const [auctionVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_vault"), auctionAccount.toBuffer()],
+      program.programId
+    );
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const walletCollateralAccount = getAssociatedTokenAddressSync(collateralMint, wallet.publicKey);
+
+    // 提高最小健康因子让仓位可以被清算
+    await program.methods
+      .updateConfig(configParams({ minHealthFactor: new anchor.BN(100_000_000) }))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    const typeBefore = await program.account.collateralType.fetch(collateralType);
+    await program.methods
+      .startTokenAuction()
+      .accounts({
+        collateralMint,
+        collateralAccount: borrowerPosition,
+        priceUpdate: solUsdPriceFeedAccount,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const auction = await program.account.auction.fetch(auctionAccount);
+    assert.ok(auction.collateralType.equals(collateralType));
+    assert.equal(auction.lot.toNumber(), 1_000_000_000);
+    assert.isTrue(auction.tab.gtn(400_000_000));
+    const position = await program.account.collateral.fetch(borrowerPosition);
+    assert.equal(position.lamportBalance.toNumber(), 0);
+    assert.equal(position.normalizedDebt.toNumber(), 0);
+    const typeAfter = await program.account.collateralType.fetch(collateralType);
+    assert.isTrue(typeAfter.debt.totalNormalizedDebt.lt(typeBefore.debt.totalNormalizedDebt));
+    const vaultBalance = await connection.getTokenAccountBalance(auctionVault, "confirmed");
+    assert.equal(vaultBalance.value.amount, "1000000000");
+
+    // 原生SOL拍卖的指令不能处理代币拍卖
+    try {
+      await program.methods
+        .takeAuction(auction.lot, new anchor.BN("18446744073709551615"))
+        .accounts({
+          auctionAccount,
+          collateralAccount: borrowerPosition,
+          depositor: borrower.publicKey,
+          keeper: wallet.publicKey,
+        })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("taking a token auction as SOL should fail");
+    } catch (err) {
+      assert.include(String(err), "AuctionCollateralMismatch");
+    }
+
+    // 全部抵押品的价值远超 tab，只按 tab 成交，剩余代币退还给仓位所有者
+    const stableBefore = await connection.getTokenAccountBalance(tokenAccount, "confirmed");
+    const bidderBefore = await connection.getTokenAccountBalance(walletCollateralAccount, "confirmed");
+    await program.methods
+      .takeTokenAuction(auction.lot, new anchor.BN("18446744073709551615"))
+      .accounts({
+        collateralMint,
+        collateralAccount: borrowerPosition,
+        depositorCollateralAccount: borrowerCollateralAccount.address,
+        bidderCollateralAccount: walletCollateralAccount,
+        keeper: wallet.publicKey,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+    const stableAfter = await connection.getTokenAccountBalance(tokenAccount, "confirmed");
+    const bidderAfter = await connection.getTokenAccountBalance(walletCollateralAccount, "confirmed");
+    const leftover = await connection.getTokenAccountBalance(borrowerCollateralAccount.address, "confirmed");
+    const paid = new anchor.BN(stableBefore.value.amount).sub(new anchor.BN(stableAfter.value.amount));
+    const bought = Number(bidderAfter.value.amount) - Number(bidderBefore.value.amount);
+    assert.equal(paid.toString(), auction.tab.toString());
+    assert.isAbove(bought, 0);
+    assert.isAbove(Number(leftover.value.amount), 0);
+    assert.equal(bought + Number(leftover.value.amount), 1_000_000_000);
+    assert.isNull(await connection.getAccountInfo(auctionAccount));
+    assert.isNull(await connection.getAccountInfo(auctionVault));
+
+    await program.methods
+      .updateConfig(configParams({ minHealthFactor: new anchor.BN(10_000) }))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+  });
+
+  // 分开管理代币抵押品仓位：只存入、只铸造、还款、只取回
+  it("Manage token position without a full redeem", async () => {
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const [collateralType] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral_type"), collateralMint.toBuffer()],
+      program.programId
+    );
+    const [tokenCollateralAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), collateralType.toBuffer(), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    const depositorCollateralAccount = getAssociatedTokenAddressSync(collateralMint, wallet.publicKey);
+    const before = await program.account.collateral.fetch(tokenCollateralAccount);
+
+    await program.methods
+      .addTokenCollateral(new anchor.BN(100_000_000))
+      .accounts({ collateralMint, depositorCollateralAccount, collateralTokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    let position = await program.account.collateral.fetch(tokenCollateralAccount);
+    assert.equal(position.lamportBalance.toNumber(), before.lamportBalance.toNumber() + 100_000_000);
+    assert.equal(position.normalizedDebt.toString(), before.normalizedDebt.toString());
+
+    await program.methods
+      .mintMoreToken(new anchor.BN(100_000_000))
+      .accounts({ collateralMint, priceUpdate: solUsdPriceFeedAccount, tokenAccount })
+      .rpc({ commitment: "confirmed" });
+    position = await program.account.collateral.fetch(tokenCollateralAccount);
+    assert.isTrue(position.normalizedDebt.gt(before.normalizedDebt));
+
+    const debtBefore = position.normalizedDebt;
+    await program.methods
+      .repayTokenDebt(new anchor.BN(100_000_000))
+      .accounts({ collateralMint, collateralAccount: tokenCollateralAccount })
+      .rpc({ commitment: "confirmed" });
+    position = await program.account.collateral.fetch(tokenCollateralAccount);
+    assert.isTrue(position.normalizedDebt.lt(debtBefore));
+
+    // 取回全部抵押品会让健康因子低于最小值
+    try {
+      await program.methods
+        .withdrawTokenCollateral(position.lamportBalance)
+        .accounts({
+          collateralMint,
+          depositorCollateralAccount,
+          priceUpdate: solUsdPriceFeedAccount,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("withdrawal below min health factor should fail");
+    } catch (err) {
+      assert.include(String(err), "BelowMinimumHealthFactor");
+    }
+    await program.methods
+      .withdrawTokenCollateral(new anchor.BN(100_000_000))
+      .accounts({
+        collateralMint,
+        depositorCollateralAccount,
+        priceUpdate: solUsdPriceFeedAccount,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+    position = await program.account.collateral.fetch(tokenCollateralAccount);
+    assert.equal(position.lamportBalance.toNumber(), before.lamportBalance.toNumber());
+  });
+
+  // 稳定费：利率指数只增不减，mint_surplus 把已计提的稳定费铸造到盈余账户
+  it("Accrue Stability Fee and Mint Surplus", async () => {
+    const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    const [surplusAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("surplus")],
+      program.programId
+    );
+    const before = await program.account.config.fetch(configAccount);
+
+    await program.methods
+      .mintSurplus()
+      .accounts({ collateralType: null })
+      .rpc({ commitment: "confirmed" });
+
+    const after = await program.account.config.fetch(configAccount);
+    assert.isTrue(after.debt.rateIndex.gte(before.debt.rateIndex));
+    assert.equal(after.debt.pendingFees.toNumber(), 0);
+    const surplus = await connection.getTokenAccountBalance(surplusAccount);
+    assert.isAtLeast(Number(surplus.value.amount), before.debt.pendingFees.toNumber());
+  });
+
+  it("Update Config rejects non-authority and invalid params", async () => {
+    const attacker = anchor.web3.Keypair.generate();
+    try {
+      await program.methods
+        .updateConfig(configParams({ minHealthFactor: new anchor.BN(100) }))
+        .accounts({ authority: attacker.publicKey })
+        .signers([attacker])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("expected Unauthorized");
+    } catch (err) {
+      assert.include(String(err), "Unauthorized");
+    }
+
+    // 最小健康因子低于 1.0
+    try {
+      await program.methods
+        .updateConfig(configParams({ minHealthFactor: new anchor.BN(9_999) }))
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+      assert.fail("expected InvalidConfigParams");
+    } catch (err) {
+      assert.include(String(err), "InvalidConfigParams");
+    }
+
+    // 阈值 × (100 + 奖励) 超过 100 × 100
+    try {
+      await program.methods
+        .updateConfig(
+          configParams({
+            liquidationThreshold: new anchor.BN(95),
+            liquidationBonus: new anchor.BN(10),
+          })
+        )
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+      assert.fail("expected InvalidConfigParams");
+    } catch (err) {
+      assert.include(String(err), "InvalidConfigParams");
+    }
+  });
+
+  it("Config authority transfer takes two steps", async () => {
+    const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    const newAuthority = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .transferConfigAuthority(newAuthority.publicKey)
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    // 接受之前旧管理员仍然有效
+    let config = await program.account.config.fetch(configAccount);
+    assert.ok(config.authority.equals(wallet.publicKey));
+    assert.ok(config.pendingAuthority.equals(newAuthority.publicKey));
+
+    await program.methods
+      .acceptConfigAuthority()
+      .accounts({ pendingAuthority: newAuthority.publicKey })
+      .signers([newAuthority])
+      .rpc({ commitment: "confirmed" });
+    config = await program.account.config.fetch(configAccount);
+    assert.ok(config.authority.equals(newAuthority.publicKey));
+
+    // 转回原管理员，后续测试继续使用
+    await program.methods
+      .transferConfigAuthority(wallet.publicKey)
+      .accounts({ authority: newAuthority.publicKey })
+      .signers([newAuthority])
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .acceptConfigAuthority()
+      .accounts({ pendingAuthority: wallet.publicKey })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  // PSM：用一个 6 位小数的测试代币代替 USDC
+  it("PSM swaps an external stable 1:1 minus fee", async () => {
+    usdcMint = await createMint(connection, wallet.payer, wallet.publicKey, null, 6);
+    const userUsdc = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    );
+    await mintTo(connection, wallet.payer, usdcMint, userUsdc.address, wallet.payer, 10_000_000);
+    const [reserve] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("psm_reserve"), usdcMint.toBuffer()],
+      program.programId
+    );
+
+    // 手续费 10 基点，债务上限 5 个稳定币
+    await program.methods
+      .registerPsm(new anchor.BN(10), new anchor.BN(5_000_000_000))
+      .accounts({ stableMint: usdcMint, stableTokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+
+    const accounts = {
+      stableMint: usdcMint,
+      userStableAccount: userUsdc.address,
+      stableTokenProgram: TOKEN_PROGRAM_ID,
+    };
+    // 1 USDC -> 0.999 稳定币
+    await program.methods
+      .psmSwapIn(new anchor.BN(1_000_000))
+      .accounts(accounts)
+      .rpc({ commitment: "confirmed" });
+    const reserveBalance = await connection.getTokenAccountBalance(reserve);
+    assert.equal(reserveBalance.value.amount, "1000000");
+    const [psmAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("psm"), usdcMint.toBuffer()],
+      program.programId
+    );
+    let psm = await program.account.psm.fetch(psmAccount);
+    assert.equal(psm.totalMinted.toNumber(), 999_000_000);
+
+    // 超过债务上限
+    try {
+      await program.methods
+        .psmSwapIn(new anchor.BN(6_000_000))
+        .accounts(accounts)
+        .rpc({ commitment: "confirmed" });
+      assert.fail("expected DebtCeilingExceeded");
+    } catch (err) {
+      assert.include(String(err), "DebtCeilingExceeded");
+    }
+
+    // 0.5 稳定币 -> 0.4995 USDC，手续费留在储备中
+    await program.methods
+      .psmSwapOut(new anchor.BN(500_000_000))
+      .accounts(accounts)
+      .rpc({ commitment: "confirmed" });
+    const reserveAfter = await connection.getTokenAccountBalance(reserve);
+    assert.equal(reserveAfter.value.amount, "500500");
+    // total_minted 按扣除手续费后的净额减少，与换入的口径一致
+    psm = await program.account.psm.fetch(psmAccount);
+    assert.equal(psm.totalMinted.toNumber(), 999_000_000 - 499_500_000);
+  });
+
+  // 储蓄模块：存入稳定币获得份额，赎回时兑换率不低于存入时
+  it("Savings deposit and withdraw", async () => {
+    const [savingsPosition] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("savings_position"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initializeSavings(new anchor.BN(500))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+
+    const attacker = anchor.web3.Keypair.generate();
+    try {
+      await program.methods
+        .updateSavingsRate(new anchor.BN(10_000))
+        .accounts({ authority: attacker.publicKey })
+        .signers([attacker])
+        .rpc({ commitment: "confirmed" });
+      assert.fail("non-authority should not set the savings rate");
+    } catch (err) {
+      assert.include(String(err), "Unauthorized");
+    }
+
+    const amount = 100_000_000;
+    await program.methods
+      .savingsDeposit(new anchor.BN(amount))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    let position = await program.account.savingsPosition.fetch(savingsPosition);
+    // 金库为空时 1:1 发放份额
+    assert.equal(position.shares.toNumber(), amount);
+
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const [savingsVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("savings_vault")],
+      program.programId
+    );
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // 直接转入金库的稳定币不改变兑换率，之后的存入仍然约 1:1 获得份额（只差几秒的利息）
+    await transferChecked(
+      connection,
+      wallet.payer,
+      tokenAccount,
+      mintAccount,
+      savingsVault,
+      wallet.payer,
+      50_000_000,
+      9,
+      [],
+      { commitment: "confirmed" },
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .savingsDeposit(new anchor.BN(amount))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    position = await program.account.savingsPosition.fetch(savingsPosition);
+    assert.isAtLeast(position.shares.toNumber() - amount, amount * 0.99);
+    const savings = await program.account.savings.fetch(
+      anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("savings")], program.programId)[0]
+    );
+    assert.isAtLeast(savings.totalAssets.toNumber(), 2 * amount);
+    assert.isBelow(savings.totalAssets.toNumber(), 2 * amount + 50_000_000);
+
+    const before = await connection.getTokenAccountBalance(tokenAccount);
+    await program.methods
+      .savingsWithdraw(position.shares)
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    const after = await connection.getTokenAccountBalance(tokenAccount);
+    assert.isAtLeast(
+      Number(after.value.amount) - Number(before.value.amount),
+      2 * amount
+    );
+  });
+
+  it("Pause halts minting and the authority can seize tokens", async () => {
+    await program.methods.setPaused(true).accounts({}).rpc({ commitment: "confirmed" });
+    try {
+      await program.methods
+        .depositCollateralAndMint(new anchor.BN(100_000_000), new anchor.BN(1_000_000))
+        .accounts({ priceUpdate: solUsdPriceFeedAccount })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("minting while paused should fail");
+    } catch (err) {
+      assert.include(String(err), "Paused");
+    }
+    try {
+      await program.methods
+        .addCollateral(new anchor.BN(100_000_000))
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+      assert.fail("adding collateral while paused should fail");
+    } catch (err) {
+      assert.include(String(err), "Paused");
+    }
+    await program.methods.setPaused(false).accounts({}).rpc({ commitment: "confirmed" });
+
+    // 默认冻结没有启用时不能使用允许名单
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    try {
+      await program.methods
+        .setAccountAllowed(true)
+        .accounts({ tokenAccount })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("allow-list requires default frozen mode");
+    } catch (err) {
+      assert.include(String(err), "ExtensionNotEnabled");
+    }
+
+    // 永久代理：从另一个账户没收稳定币
+    const holder = anchor.web3.Keypair.generate();
+    const holderAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      mintAccount,
+      holder.publicKey,
+      false,
+      "confirmed",
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await transferChecked(
+      connection,
+      wallet.payer,
+      tokenAccount,
+      mintAccount,
+      holderAccount.address,
+      wallet.payer,
+      1_000_000,
+      9,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .seizeTokens(new anchor.BN(1_000_000))
+      .accounts({ from: holderAccount.address, to: tokenAccount })
+      .rpc({ commitment: "confirmed" });
+    const seized = await connection.getTokenAccountBalance(holderAccount.address);
+    assert.equal(seized.value.amount, "0");
+
+    // 协议自己的盈余账户不能没收
+    const [surplusAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("surplus")],
+      program.programId
+    );
+    try {
+      await program.methods
+        .seizeTokens(new anchor.BN(1))
+        .accounts({ from: surplusAccount, to: tokenAccount })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("seizing from the surplus account should fail");
+    } catch (err) {
+      assert.include(String(err), "ProtocolAccount");
+    }
+  });
+
+  // 紧急关停必须是最后一个测试：关停后所有铸造和仓位操作都会失败
+  it("Debt ceiling and emergency shutdown", async () => {
+    const [mintAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint")],
+      program.programId
+    );
+    const [shutdownAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("shutdown")],
+      program.programId
+    );
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mintAccount,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // 全局债务上限设为当前总供应量，之后任何铸造都会超过上限
+    const { supply } = await getMint(connection, mintAccount, "confirmed", TOKEN_2022_PROGRAM_ID);
+    await program.methods
+      .updateConfig(configParams({ debtCeiling: new anchor.BN(supply.toString()) }))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    try {
+      await program.methods
+        .mintMore(new anchor.BN(1_000))
+        .accounts({ priceUpdate: solUsdPriceFeedAccount, tokenAccount })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("minting above the global debt ceiling should fail");
+    } catch (err) {
+      assert.include(String(err), "GlobalDebtCeilingExceeded");
+    }
+
+    // 测试中不等待，关停后立即可以 finalize
+    await program.methods
+      .updateConfig(configParams({ shutdownWait: new anchor.BN(0) }))
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .emergencyShutdown()
+      .accounts({ priceUpdate: solUsdPriceFeedAccount })
+      .rpc({ commitment: "confirmed" });
+    try {
+      await program.methods
+        .withdrawCollateral(new anchor.BN(1_000))
+        .accounts({ priceUpdate: solUsdPriceFeedAccount })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("withdrawing collateral after shutdown should fail");
+    } catch (err) {
+      assert.include(String(err), "Shutdown");
+    }
+    // 关停后不能再修改稳定费和等待期
+    try {
+      await program.methods
+        .updateConfig(configParams({ shutdownWait: new anchor.BN(1) }))
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+      assert.fail("updating the config after shutdown should fail");
+    } catch (err) {
+      assert.include(String(err), "Shutdown");
+    }
+
+    // "Start and Take Auction" 的拍卖在关停时仍在进行：仍然可以成交，重新起拍只受价格限制
+    const [auctionAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction"), collateralAccount.toBuffer()],
+      program.programId
+    );
+    const auction = await program.account.auction.fetch(auctionAccount);
+    await program.methods
+      .takeAuction(new anchor.BN(100_000), new anchor.BN("18446744073709551615"))
+      .accounts({
+        auctionAccount,
+        depositor: wallet.publicKey,
+        keeper: wallet.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+    const taken = await program.account.auction.fetch(auctionAccount);
+    assert.equal(taken.lot.toString(), auction.lot.subn(100_000).toString());
+    assert.isTrue(taken.tab.lt(auction.tab));
+    try {
+      await program.methods
+        .restartAuction()
+        .accounts({ auctionAccount, priceUpdate: solUsdPriceFeedAccount })
+        .rpc({ commitment: "confirmed" });
+      assert.fail("restarting an auction before its price reaches 0 should fail");
+    } catch (err) {
+      assert.include(String(err), "AuctionNotExpired");
+    }
+
+    await program.methods
+      .recordShutdownPrice()
+      .accounts({
+        collateralMint,
+        priceUpdate: solUsdPriceFeedAccount,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    // 结算仓位：覆盖债务的抵押品进入结算池，剩余退还给仓位所有者
+    const shutdown = await program.account.shutdown.fetch(shutdownAccount);
+    const poolBefore = await connection.getBalance(shutdownAccount, "confirmed");
+    await program.methods
+      .settleVault()
+      .accounts({ collateralAccount, depositor: wallet.publicKey })
+      .rpc({ commitment: "confirmed" });
+    const settled = await program.account.collateral.fetch(collateralAccount);
+    assert.equal(settled.lamportBalance.toNumber(), 0);
+    assert.equal(settled.normalizedDebt.toNumber(), 0);
+    assert.isAtLeast(await connection.getBalance(shutdownAccount, "confirmed"), poolBefore);
+
+    const [collateralType] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral_type"), collateralMint.toBuffer()],
+      program.programId
+    );
+    const [tokenCollateralAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral"), collateralType.toBuffer(), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    const depositorCollateralAccount = getAssociatedTokenAddressSync(collateralMint, wallet.publicKey);
+    await program.methods
+      .settleTokenVault()
+      .accounts({
+        collateralMint,
+        collateralAccount: tokenCollateralAccount,
+        depositorCollateralAccount,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .finalizeShutdown()
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    const finalized = await program.account.shutdown.fetch(shutdownAccount);
+    assert.isTrue(finalized.finalized);
+    assert.isTrue(finalized.solPrice.eq(shutdown.solPrice));
+
+    // 锁定全部稳定币，再从每个结算池按比例兑付
+    const balance = await connection.getTokenAccountBalance(tokenAccount);
+    const locked = new anchor.BN(balance.value.amount);
+    await program.methods
+      .shutdownLock(locked)
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+
+    const solBefore = await connection.getBalance(shutdownAccount, "confirmed");
+    await program.methods
+      .shutdownRedeemSol(locked)
+      .accounts({})
+      .rpc({ commitment: "confirmed" });
+    assert.isBelow(await connection.getBalance(shutdownAccount, "confirmed"), solBefore);
+
+    const collateralBefore = await connection.getTokenAccountBalance(depositorCollateralAccount);
+    await program.methods
+      .shutdownRedeemToken(locked)
+      .accounts({ collateralMint, collateralTokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+    const collateralAfter = await connection.getTokenAccountBalance(depositorCollateralAccount);
+    assert.isAbove(Number(collateralAfter.value.amount), Number(collateralBefore.value.amount));
+
+    await program.methods
+      .shutdownRedeemPsm(locked)
+      .accounts({ stableMint: usdcMint, stableTokenProgram: TOKEN_PROGRAM_ID })
+      .rpc({ commitment: "confirmed" });
+
+    // 每个结算池最多兑付锁定的数量
+    try {
+      await program.methods
+        .shutdownRedeemSol(new anchor.BN(1))
+        .accounts({})
+        .rpc({ commitment: "confirmed" });
+      assert.fail("redeeming more than locked should fail");
+    } catch (err) {
+      assert.include(String(err), "InsufficientLockedStablecoin");
+    }
+  });
 });
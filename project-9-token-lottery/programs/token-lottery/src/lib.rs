@@ -4,7 +4,10 @@ use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface}
+    token_interface::{
+        approve, mint_to, transfer_checked, Approve, Mint, MintTo, TokenAccount,
+        TokenInterface, TransferChecked,
+    }
 };
 use switchboard_on_demand::accounts::RandomnessAccountData;
 use anchor_spl::metadata::{
@@ -14,10 +17,14 @@ use anchor_spl::metadata::{
     CreateMasterEditionV3,
     SignMetadata,
     SetAndVerifySizedCollectionItem,
+    FreezeDelegatedAccount,
+    ThawDelegatedAccount,
     create_master_edition_v3,
     create_metadata_accounts_v3,
     sign_metadata,
     set_and_verify_sized_collection_item,
+    freeze_delegated_account,
+    thaw_delegated_account,
 };
 use mpl_token_metadata::types::{
     CollectionDetails,
@@ -34,6 +41,9 @@ pub const NAME: &str = "Token Lottery Ticket #";
 pub const URI: &str = "Token Lottery";
 #[constant]
 pub const SYMBOL: &str = "TICKET";
+// 版税基点上限（10000 = 100%）
+#[constant]
+pub const MAX_ROYALTY_BPS: u16 = 10_000;
 
 /*
 
@@ -122,13 +132,28 @@ pub mod token_lottery {
 
     use super::*;
     // 初始化抽奖配置（抽奖时间段、票价、初始状态）
-    pub fn initialize_config(ctx: Context<InitializeConifg>, start: u64, end: u64, price: u64) -> Result<()> {
+    pub fn initialize_config(
+        ctx: Context<InitializeConifg>,
+        start: u64,
+        end: u64,
+        price: u64,
+        ticket_policy: TicketPolicy,
+        royalty_bps: u16,
+    ) -> Result<()> {
+        // 只有转售窗口模式才会收取版税，且不能超过 100%
+        require!(royalty_bps <= MAX_ROYALTY_BPS, ErrorCode::InvalidRoyalty);
+        require!(
+            royalty_bps == 0 || ticket_policy == TicketPolicy::ResaleWindow,
+            ErrorCode::InvalidRoyalty
+        );
         ctx.accounts.token_lottery.bump = ctx.bumps.token_lottery;
         ctx.accounts.token_lottery.lottery_start = start;
         ctx.accounts.token_lottery.lottery_end = end;
         ctx.accounts.token_lottery.price = price;
         ctx.accounts.token_lottery.authority = ctx.accounts.payer.key();
         ctx.accounts.token_lottery.randomness_account = Pubkey::default();
+        ctx.accounts.token_lottery.ticket_policy = ticket_policy;
+        ctx.accounts.token_lottery.royalty_bps = royalty_bps;
 
         ctx.accounts.token_lottery.ticket_num = 0;
         ctx.accounts.token_lottery.winner_chosen = false;
@@ -333,7 +358,7 @@ pub mod token_lottery {
                 name: ticket_name,          // 票名称，如 Ticket0
                 symbol: SYMBOL.to_string(), // NFT 的 symbol（例如 "TICKET"）
                 uri: URI.to_string(),       // 指向 JSON 元数据的 URL（存储图像、描述等）
-                seller_fee_basis_points: 0, // 没有转售版税
+                seller_fee_basis_points: ctx.accounts.token_lottery.royalty_bps, // 转售版税（仅 ResaleWindow 模式非 0）
                 creators: None,
                 collection: None,           // 稍后再设置 collection
                 uses: None,
@@ -382,12 +407,152 @@ pub mod token_lottery {
             ),
             None,
         )?;
+        // 非自由转让模式：把 collection_mint PDA 设为票据账户的 delegate，
+        // 再通过 Metaplex 冻结该账户（master edition 创建后 freeze authority 已移交给 edition PDA）
+        if ctx.accounts.token_lottery.ticket_policy != TicketPolicy::Transferable {
+            approve(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Approve {
+                        to: ctx.accounts.destination.to_account_info(),
+                        delegate: ctx.accounts.collection_mint.to_account_info(),
+                        authority: ctx.accounts.payer.to_account_info(),
+                    },
+                ),
+                1,
+            )?;
+            freeze_delegated_account(CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                FreezeDelegatedAccount {
+                    metadata: ctx.accounts.token_metadata_program.to_account_info(),
+                    delegate: ctx.accounts.collection_mint.to_account_info(),
+                    token_account: ctx.accounts.destination.to_account_info(),
+                    edition: ctx.accounts.master_edition.to_account_info(),
+                    mint: ctx.accounts.ticket_mint.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                &signer_seeds,
+            ))?;
+        }
         // 当前抽奖票数 +1（供下一票编号使用）
         ctx.accounts.token_lottery.ticket_num += 1;
 
         Ok(())
     }
 
+    // 二级市场转售门票（仅 ResaleWindow 模式）
+    /**
+        门票在购票时已被冻结，这是唯一的转让途径：
+        1、只能在 lottery_end 之前、开奖之前转售
+        2、collection_mint PDA 作为 delegate 解冻卖家账户并把门票转给买家
+        3、买家授权 collection_mint PDA 为 delegate 后重新冻结买家账户
+        4、买家支付 price lamports：版税（price * royalty_bps / 10000）进入奖池，其余付给卖家
+    **/
+    pub fn transfer_ticket(ctx: Context<TransferTicket>, _ticket_id: u64, price: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(
+            ctx.accounts.token_lottery.ticket_policy == TicketPolicy::ResaleWindow,
+            ErrorCode::TicketNotTransferable
+        );
+        // 转售窗口在 lottery_end 关闭（lottery_end 当个 slot 已经可以开奖），开奖后也不允许再转让
+        require!(
+            clock.slot < ctx.accounts.token_lottery.lottery_end
+                && !ctx.accounts.token_lottery.winner_chosen,
+            ErrorCode::ResaleWindowClosed
+        );
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"collection_mint".as_ref(),
+            &[ctx.bumps.collection_mint],
+        ]];
+        // Step 1: 解冻卖家的票据账户
+        thaw_delegated_account(CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            ThawDelegatedAccount {
+                metadata: ctx.accounts.token_metadata_program.to_account_info(),
+                delegate: ctx.accounts.collection_mint.to_account_info(),
+                token_account: ctx.accounts.seller_token_account.to_account_info(),
+                edition: ctx.accounts.master_edition.to_account_info(),
+                mint: ctx.accounts.ticket_mint.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            &signer_seeds,
+        ))?;
+        // Step 2: collection_mint PDA 以 delegate 身份把门票转给买家
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.seller_token_account.to_account_info(),
+                    mint: ctx.accounts.ticket_mint.to_account_info(),
+                    to: ctx.accounts.buyer_token_account.to_account_info(),
+                    authority: ctx.accounts.collection_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+            ctx.accounts.ticket_mint.decimals,
+        )?;
+        // Step 3: 买家授权 delegate 并重新冻结
+        approve(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Approve {
+                    to: ctx.accounts.buyer_token_account.to_account_info(),
+                    delegate: ctx.accounts.collection_mint.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+        freeze_delegated_account(CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            FreezeDelegatedAccount {
+                metadata: ctx.accounts.token_metadata_program.to_account_info(),
+                delegate: ctx.accounts.collection_mint.to_account_info(),
+                token_account: ctx.accounts.buyer_token_account.to_account_info(),
+                edition: ctx.accounts.master_edition.to_account_info(),
+                mint: ctx.accounts.ticket_mint.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            &signer_seeds,
+        ))?;
+        // Step 4: 结算转售价格，版税进入奖池
+        let royalty = (price as u128)
+            .checked_mul(ctx.accounts.token_lottery.royalty_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / MAX_ROYALTY_BPS as u128;
+        let royalty = royalty as u64;
+        let seller_proceeds = price - royalty;
+        if royalty > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.buyer.to_account_info(),
+                        to: ctx.accounts.token_lottery.to_account_info(),
+                    },
+                ),
+                royalty,
+            )?;
+            ctx.accounts.token_lottery.lottery_pot_amount += royalty;
+        }
+        if seller_proceeds > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.buyer.to_account_info(),
+                        to: ctx.accounts.seller.to_account_info(),
+                    },
+                ),
+                seller_proceeds,
+            )?;
+        }
+
+        Ok(())
+    }
+
     // 提交随机数结果，记录 randomness 来源
     /**
         该函数是整个抽奖流程中的 “提交随机数阶段”，由管理员调用：
@@ -591,6 +756,67 @@ pub struct ClaimPrize<'info> {
     pub token_metadata_program: Program<'info, Metadata>,
 }
 
+#[derive(Accounts)]
+#[instruction(ticket_id: u64)]
+pub struct TransferTicket<'info> {
+    // 卖家（当前门票持有者），需要签名
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    // 买家，需要签名并支付转售价格
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    // 抽奖状态账户，读取转让策略、版税并累加奖池
+    #[account(
+        mut,
+        seeds = [b"token_lottery".as_ref()],
+        bump = token_lottery.bump,
+    )]
+    pub token_lottery: Box<Account<'info, TokenLottery>>,
+    // 被转售的票据 NFT mint（种子为票号）
+    #[account(
+        seeds = [ticket_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub ticket_mint: Box<InterfaceAccount<'info, Mint>>,
+    // 卖家持有门票的 token account
+    #[account(
+        mut,
+        associated_token::mint = ticket_mint,
+        associated_token::authority = seller,
+        associated_token::token_program = token_program,
+    )]
+    pub seller_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 买家接收门票的 token account（不存在则创建）
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = ticket_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program,
+    )]
+    pub buyer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    // 票据 NFT 的 Master Edition（当前的 freeze authority）
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(), b"edition"],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    /// CHECK: This account is validated by the metaplex program
+    pub master_edition: UncheckedAccount<'info>,
+    // Collection 的 mint 账户（作为票据账户的 delegate 签名解冻/转账/冻结）
+    #[account(
+        seeds = [b"collection_mint".as_ref()],
+        bump,
+    )]
+    pub collection_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
 #[derive(Accounts)]
 pub struct CommitWinner<'info> {
     // 调用者（必须是管理员），提交随机数结果的 signer
@@ -837,6 +1063,21 @@ pub struct TokenLottery {
     pub randomness_account: Pubkey,
     // 抽奖发起者 / 管理员（只有该地址可以开奖、提交 randomness）
     pub authority: Pubkey,
+    // 门票转让策略（初始化时选择，之后不可修改）
+    pub ticket_policy: TicketPolicy,
+    // 转售版税（基点），仅 ResaleWindow 模式生效，版税进入奖池
+    pub royalty_bps: u16,
+}
+
+// 门票转让策略
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TicketPolicy {
+    // 自由转让，谁持有中奖票谁领奖
+    Transferable,
+    // 灵魂绑定：购票后票据账户被冻结，永不可转让
+    Soulbound,
+    // 冻结门票，但允许在 lottery_end 之前通过 transfer_ticket 转售并收取版税
+    ResaleWindow,
 }
 
 #[error_code]
@@ -880,6 +1121,22 @@ pub enum ErrorCode {
     /// 当前 NFT 不是中奖票（ticket 名称或 collection 验证失败）
     #[msg("Incorrect ticket")]
     IncorrectTicket,
+
+    /// 版税超过 100%，或在非转售模式下设置了版税
+    #[msg("Invalid royalty")]
+    InvalidRoyalty,
+
+    /// 当前转让策略不允许转售门票
+    #[msg("Ticket is not transferable")]
+    TicketNotTransferable,
+
+    /// 转售窗口已关闭（已过 lottery_end 或已开奖）
+    #[msg("Resale window closed")]
+    ResaleWindowClosed,
+
+    /// 数值计算溢出
+    #[msg("Math overflow")]
+    MathOverflow,
 }
 
 
@@ -3,7 +3,13 @@ import * as sb from "@switchboard-xyz/on-demand";
 import { Program } from "@coral-xyz/anchor";
 import { TokenLottery } from "../target/types/token_lottery";
 import { TOKEN_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
-import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  createAssociatedTokenAccountIdempotentInstruction,
+  createTransferCheckedInstruction,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { assert } from "chai";
 import NodeWallet from "@coral-xyz/anchor/dist/cjs/nodewallet";
 
 async function switchboardRandomness() {
@@ -59,6 +65,18 @@ describe("token-lottery", () => {
   const rngKp = anchor.web3.Keypair.generate();
 
   const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+  const TICKET_PRICE = 10000;
+  // 配置只能初始化一次，Soulbound 模式需要在新的本地验证器上单独运行：TICKET_POLICY=soulbound anchor test
+  const SOULBOUND = process.env.TICKET_POLICY === "soulbound";
+  const ROYALTY_BPS = SOULBOUND ? 0 : 1000; // 10%，仅 resaleWindow 模式可非 0
+  const tokenLotteryAddress = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('token_lottery')],
+    program.programId,
+  )[0];
+  const ticketMintAddress = (ticketId: number) => anchor.web3.PublicKey.findProgramAddressSync(
+    [new anchor.BN(ticketId).toArrayLike(Buffer, 'le', 8)],
+    program.programId,
+  )[0];
   const apiKey = "c5730fdb-3471-42ff-92ad-97256fa83871";
 
   // 没有 api-key 可以在这个网站注册获取一个 免费的 有速率限制 每秒钟几个 https://www.helius.dev/
@@ -143,8 +161,10 @@ describe("token-lottery", () => {
 
     const initConfigIx = await program.methods.initializeConfig(
       new anchor.BN(0),
-      new anchor.BN(slot + 10),
-      new anchor.BN(10000),
+      new anchor.BN(slot + 50), // 留出转售窗口（转售必须在 lottery_end 之前完成）
+      new anchor.BN(TICKET_PRICE),
+      SOULBOUND ? { soulbound: {} } : { resaleWindow: {} }, // 门票转让策略：transferable / soulbound / resaleWindow
+      ROYALTY_BPS, // 转售版税（基点）
     ).instruction();
 
     const initLotteryIx = await program.methods.initializeLottery()
@@ -176,6 +196,86 @@ describe("token-lottery", () => {
     await buyTicket();
   });
 
+  // 非 transferable 模式下门票账户在购票时被冻结（soulbound 与 resaleWindow 相同），绕过程序直接转账必须失败
+  it("Frozen ticket refuses a direct transfer", async () => {
+    const receiver = anchor.web3.Keypair.generate();
+    const ticketMint = ticketMintAddress(0);
+    const source = getAssociatedTokenAddressSync(ticketMint, wallet.publicKey);
+    const destination = getAssociatedTokenAddressSync(ticketMint, receiver.publicKey);
+
+    const tx = new anchor.web3.Transaction()
+      .add(createAssociatedTokenAccountIdempotentInstruction(
+        wallet.publicKey, destination, receiver.publicKey, ticketMint))
+      .add(createTransferCheckedInstruction(source, ticketMint, destination, wallet.publicKey, 1, 0));
+    try {
+      await provider.sendAndConfirm(tx);
+      assert.fail("frozen ticket should not be transferable");
+    } catch (err) {
+      assert.include(String(err), "frozen");
+    }
+    const sourceAccount = await getAccount(connection, source);
+    assert.equal(sourceAccount.amount.toString(), "1");
+    assert.isTrue(sourceAccount.isFrozen);
+  });
+
+  // Soulbound 模式下门票不能通过 transfer_ticket 转让
+  (SOULBOUND ? it : it.skip)("Soulbound ticket refuses a resale", async () => {
+    const buyer = anchor.web3.Keypair.generate();
+    try {
+      await program.methods.transferTicket(new anchor.BN(0), new anchor.BN(TICKET_PRICE))
+        .accounts({
+          seller: wallet.publicKey,
+          buyer: buyer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+      assert.fail("soulbound ticket should not be transferable");
+    } catch (err) {
+      assert.include(String(err), "TicketNotTransferable");
+    }
+    const sellerAccount = await getAccount(connection, getAssociatedTokenAddressSync(ticketMintAddress(0), wallet.publicKey));
+    assert.equal(sellerAccount.amount.toString(), "1");
+  });
+
+  (SOULBOUND ? it.skip : it)("Resells a ticket and pays the royalty into the pot", async () => {
+    const buyer = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({
+        fromPubkey: wallet.publicKey,
+        toPubkey: buyer.publicKey,
+        lamports: anchor.web3.LAMPORTS_PER_SOL,
+      })
+    ));
+
+    // 转售价格由买卖双方决定，可以低于原始票价
+    const resalePrice = TICKET_PRICE / 2;
+    const potBefore = (await program.account.tokenLottery.fetch(tokenLotteryAddress)).lotteryPotAmount;
+    await program.methods.transferTicket(new anchor.BN(0), new anchor.BN(resalePrice))
+      .accounts({
+        seller: wallet.publicKey,
+        buyer: buyer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([buyer])
+      .rpc();
+
+    // 门票转到买家名下，并重新冻结
+    const ticketMint = ticketMintAddress(0);
+    const sellerAccount = await getAccount(connection, getAssociatedTokenAddressSync(ticketMint, wallet.publicKey));
+    const buyerAccount = await getAccount(connection, getAssociatedTokenAddressSync(ticketMint, buyer.publicKey));
+    assert.equal(sellerAccount.amount.toString(), "0");
+    assert.equal(buyerAccount.amount.toString(), "1");
+    assert.isTrue(buyerAccount.isFrozen);
+
+    // 版税进入奖池
+    const potAfter = (await program.account.tokenLottery.fetch(tokenLotteryAddress)).lotteryPotAmount;
+    assert.equal(
+      potAfter.sub(potBefore).toNumber(),
+      resalePrice * ROYALTY_BPS / 10000,
+    );
+  });
+
   it("Is committing and revealing a winner", async () => {
     const queue_addr = "A43DyUGA7s8eXPxqEjJY6EBu1KKbNgfxF8h17VAHn13w"; // switchboard
     const queue = new anchor.web3.PublicKey("A43DyUGA7s8eXPxqEjJY6EBu1KKbNgfxF8h17VAHn13w");
@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{transfer, Mint, Token, TokenAccount, Transfer}};
+
+use crate::{error::AmmError, math::{add_liquidity_delta, compute_swap_step, mul_div, sqrt_price_at_tick, tick_at_sqrt_price, MAX_SQRT_PRICE_X64, MAX_TICK, MIN_SQRT_PRICE_X64, MIN_TICK, Q64}, state::{ClPool, TickArray}};
+
+#[derive(Accounts)]
+pub struct ClSwap<'info> {
+    #[account(mut)]
+    signer: Signer<'info>,
+    mint_a: Account<'info, Mint>,
+    mint_b: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = signer,
+        associated_token::mint = mint_a
+    )]
+    signer_ata_a: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::authority = signer,
+        associated_token::mint = mint_b
+    )]
+    signer_ata_b: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::authority = pool,
+        associated_token::mint = mint_a
+    )]
+    pool_ata_a: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::authority = pool,
+        associated_token::mint = mint_b
+    )]
+    pool_ata_b: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"cl_pool", mint_a.key().as_ref(), mint_b.key().as_ref(), pool.fee.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pool: Account<'info, ClPool>,
+    token_program: Program<'info, Token>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ClSwap<'info> {
+    /*
+        Exact input swap that walks across initialized ticks.
+
+        tick_arrays are the tick arrays in the swap direction, starting with the one holding the current tick,
+        with no gaps. The swap stops early if it runs out of arrays, and only the input used is paid.
+    */
+    pub fn cl_swap(&mut self, amount_in: u64, min_amount_out: u64, a_to_b: bool, tick_arrays: &'info [AccountInfo<'info>]) -> Result<()> {
+        require_gt!(amount_in, 0);
+        let tick_spacing = self.pool.tick_spacing;
+        let ticks_in_array = TickArray::ticks_in_array(tick_spacing);
+
+        let mut expected_start = TickArray::start_index_for(self.pool.tick_current, tick_spacing);
+        let mut loaders = Vec::with_capacity(tick_arrays.len());
+        for info in tick_arrays.iter() {
+            require!(info.is_writable, AmmError::InvalidTickArray);
+            let loader = AccountLoader::<TickArray>::try_from(info)?;
+            {
+                let tick_array = loader.load()?;
+                require_keys_eq!(tick_array.pool, self.pool.key(), AmmError::InvalidTickArray);
+                require_eq!(tick_array.start_tick_index, expected_start, AmmError::InvalidTickArray);
+            }
+            expected_start = if a_to_b { expected_start - ticks_in_array } else { expected_start + ticks_in_array };
+            loaders.push(loader);
+        }
+
+        let pool = &mut self.pool;
+        let mut amount_remaining = amount_in;
+        let mut amount_out: u64 = 0;
+        let mut array_index = 0;
+
+        while amount_remaining > 0 && array_index < loaders.len() {
+            let mut tick_array = loaders[array_index].load_mut()?;
+
+            // Next tick to stop at, and whether it has liquidity to cross
+            let (tick_next, initialized) = match tick_array.next_initialized_tick(pool.tick_current, tick_spacing, a_to_b) {
+                Some(tick) => (tick, true),
+                None if array_index + 1 < loaders.len() => {
+                    // Nothing left in this array, keep the price and look in the next one
+                    array_index += 1;
+                    continue;
+                }
+                // Last array: stop at its edge
+                None if a_to_b => (tick_array.start_tick_index, false),
+                None => (tick_array.start_tick_index + ticks_in_array - tick_spacing as i32, false),
+            };
+            let tick_next = tick_next.clamp(MIN_TICK, MAX_TICK);
+            let sqrt_price_target = sqrt_price_at_tick(tick_next).ok_or(AmmError::InvalidTickRange)?;
+
+            // The target may equal the current price, e.g. after an a to b swap stopped on a tick.
+            // That zero size step still runs, so the tick is crossed back.
+            if (a_to_b && sqrt_price_target > pool.sqrt_price_x64) || (!a_to_b && sqrt_price_target < pool.sqrt_price_x64) {
+                break;
+            }
+
+            let step = compute_swap_step(pool.sqrt_price_x64, sqrt_price_target, pool.liquidity, amount_remaining, pool.fee, a_to_b)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+
+            amount_remaining = amount_remaining
+                .checked_sub(step.amount_in)
+                .and_then(|amount| amount.checked_sub(step.fee_amount))
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            amount_out = amount_out.checked_add(step.amount_out).ok_or(ProgramError::ArithmeticOverflow)?;
+
+            // Fees go to the liquidity active in this step
+            if pool.liquidity > 0 {
+                let fee_growth = mul_div(step.fee_amount as u128, Q64, pool.liquidity, false).ok_or(ProgramError::ArithmeticOverflow)?;
+                if a_to_b {
+                    pool.fee_growth_global_a_x64 = pool.fee_growth_global_a_x64.wrapping_add(fee_growth);
+                } else {
+                    pool.fee_growth_global_b_x64 = pool.fee_growth_global_b_x64.wrapping_add(fee_growth);
+                }
+            }
+
+            if step.sqrt_price_next_x64 == sqrt_price_target {
+                if initialized {
+                    let liquidity_net = tick_array
+                        .tick_mut(tick_next, tick_spacing)?
+                        .cross(pool.fee_growth_global_a_x64, pool.fee_growth_global_b_x64);
+                    let liquidity_delta = if a_to_b { -liquidity_net } else { liquidity_net };
+                    pool.liquidity = add_liquidity_delta(pool.liquidity, liquidity_delta).ok_or(ProgramError::ArithmeticOverflow)?;
+                }
+                pool.sqrt_price_x64 = sqrt_price_target;
+                pool.tick_current = if a_to_b { tick_next - 1 } else { tick_next };
+                if !initialized || pool.sqrt_price_x64 == MIN_SQRT_PRICE_X64 || pool.sqrt_price_x64 == MAX_SQRT_PRICE_X64 {
+                    // Reached the edge of the last array or the price limits
+                    break;
+                }
+            } else {
+                pool.sqrt_price_x64 = step.sqrt_price_next_x64;
+                pool.tick_current = tick_at_sqrt_price(pool.sqrt_price_x64).ok_or(ProgramError::ArithmeticOverflow)?;
+            }
+        }
+
+        // Check slippage
+        require_gte!(amount_out, min_amount_out);
+
+        let amount_in = amount_in - amount_remaining;
+
+        let (signer_in, signer_out, pool_in, pool_out) = if a_to_b {
+            (
+                self.signer_ata_a.to_account_info(),
+                self.signer_ata_b.to_account_info(),
+                self.pool_ata_a.to_account_info(),
+                self.pool_ata_b.to_account_info(),
+            )
+        } else {
+            (
+                self.signer_ata_b.to_account_info(),
+                self.signer_ata_a.to_account_info(),
+                self.pool_ata_b.to_account_info(),
+                self.pool_ata_a.to_account_info(),
+            )
+        };
+
+        // Deposit Token In Amount
+        let accounts = Transfer {
+            from: signer_in,
+            to: pool_in,
+            authority: self.signer.to_account_info()
+        };
+
+        let ctx = CpiContext::new(
+            self.token_program.to_account_info(), 
+            accounts
+        );
+        
+        transfer(ctx, amount_in)?;
+
+        // Withdraw Token Out Amount
+        let accounts = Transfer {
+            from: pool_out,
+            to: signer_out,
+            authority: self.pool.to_account_info(),
+        };
+
+        let binding = self.pool.fee.to_le_bytes();
+
+        let signer_seeds: [&[&[u8]];1] = [&[&b"cl_pool"[..], self.mint_a.to_account_info().key.as_ref(), self.mint_b.to_account_info().key.as_ref(), binding.as_ref(), &[self.pool.bump]]];
+
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(), 
+            accounts,
+            &signer_seeds
+        );
+        
+        transfer(ctx, amount_out)
+    }
+}
@@ -52,7 +52,7 @@ impl<'info> Initialize<'info> {
     pub fn initialize(&mut self, fee: u16, bump: u8, lp_bump: u8) -> Result<()> {
         self.pool.set_inner(Pool {
             mint_a: self.mint_a.key(),
-            mint_b: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
             fee,
             bump,
             lp_bump,
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAccount}};
+
+use crate::{error::AmmError, math::{tick_at_sqrt_price, MAX_TICK}, state::ClPool, ANCHOR_DISCRIMINATOR_SIZE};
+
+#[derive(Accounts)]
+#[instruction(fee: u16)]
+pub struct InitializeClPool<'info> {
+    #[account(mut)]
+    signer: Signer<'info>,
+    mint_a: Account<'info, Mint>,
+    mint_b: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = signer,
+        associated_token::authority = pool,
+        associated_token::mint = mint_a
+    )]
+    pool_ata_a: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = signer,
+        associated_token::authority = pool,
+        associated_token::mint = mint_b
+    )]
+    pool_ata_b: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = signer,
+        space = ANCHOR_DISCRIMINATOR_SIZE + ClPool::INIT_SPACE,
+        seeds = [b"cl_pool", mint_a.key().as_ref(), mint_b.key().as_ref(), fee.to_le_bytes().as_ref()],
+        bump
+    )]
+    pool: Account<'info, ClPool>,
+    token_program: Program<'info, Token>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeClPool<'info> {
+    pub fn initialize_cl_pool(&mut self, fee: u16, tick_spacing: u16, sqrt_price_x64: u128, bump: u8) -> Result<()> {
+        require!(self.mint_a.key() != self.mint_b.key(), AmmError::IdenticalMints);
+        require!(fee < 10_000, AmmError::InvalidFee);
+        require!(tick_spacing > 0 && tick_spacing as i32 <= MAX_TICK, AmmError::InvalidTickSpacing);
+
+        let tick_current = tick_at_sqrt_price(sqrt_price_x64).ok_or(AmmError::InvalidSqrtPrice)?;
+
+        self.pool.set_inner(ClPool {
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            fee,
+            tick_spacing,
+            sqrt_price_x64,
+            tick_current,
+            liquidity: 0,
+            fee_growth_global_a_x64: 0,
+            fee_growth_global_b_x64: 0,
+            bump,
+        });
+        Ok(())
+    }
+}
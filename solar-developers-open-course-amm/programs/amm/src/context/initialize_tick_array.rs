@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, math::{MAX_TICK, MIN_TICK}, state::{ClPool, TickArray}, ANCHOR_DISCRIMINATOR_SIZE};
+
+#[derive(Accounts)]
+#[instruction(start_tick_index: i32)]
+pub struct InitializeTickArray<'info> {
+    #[account(mut)]
+    signer: Signer<'info>,
+    pool: Account<'info, ClPool>,
+    #[account(
+        init,
+        payer = signer,
+        space = ANCHOR_DISCRIMINATOR_SIZE + std::mem::size_of::<TickArray>(),
+        seeds = [b"tick_array", pool.key().as_ref(), start_tick_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    tick_array: AccountLoader<'info, TickArray>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeTickArray<'info> {
+    pub fn initialize_tick_array(&mut self, start_tick_index: i32) -> Result<()> {
+        // Arrays are aligned, so every tick belongs to exactly one of them
+        require_eq!(TickArray::start_index_for(start_tick_index, self.pool.tick_spacing), start_tick_index, AmmError::InvalidTickArray);
+        require_gte!(start_tick_index, TickArray::start_index_for(MIN_TICK, self.pool.tick_spacing), AmmError::InvalidTickArray);
+        require_gte!(MAX_TICK, start_tick_index, AmmError::InvalidTickArray);
+
+        let mut tick_array = self.tick_array.load_init()?;
+        tick_array.pool = self.pool.key();
+        tick_array.start_tick_index = start_tick_index;
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{transfer, Mint, Token, TokenAccount, Transfer}};
+
+use crate::{error::AmmError, math::{amount_a_delta, amount_b_delta, add_liquidity_delta, sqrt_price_at_tick}, state::{ClPool, Position, TickArray}};
+
+#[derive(Accounts)]
+pub struct ModifyLiquidity<'info> {
+    #[account(mut)]
+    signer: Signer<'info>,
+    mint_a: Account<'info, Mint>,
+    mint_b: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"cl_pool", mint_a.key().as_ref(), mint_b.key().as_ref(), pool.fee.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pool: Account<'info, ClPool>,
+    #[account(
+        mut,
+        seeds = [b"position", position.position_mint.as_ref()],
+        bump = position.bump,
+        has_one = pool
+    )]
+    position: Account<'info, Position>,
+    // Holding the position NFT is what owns the position
+    #[account(
+        constraint = signer_ata_position.mint == position.position_mint,
+        constraint = signer_ata_position.owner == signer.key(),
+        constraint = signer_ata_position.amount == 1
+    )]
+    signer_ata_position: Account<'info, TokenAccount>,
+    // May be the same account when both ticks are in one array
+    #[account(mut)]
+    tick_array_lower: AccountLoader<'info, TickArray>,
+    #[account(mut)]
+    tick_array_upper: AccountLoader<'info, TickArray>,
+    #[account(
+        mut,
+        associated_token::authority = signer,
+        associated_token::mint = mint_a
+    )]
+    signer_ata_a: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::authority = signer,
+        associated_token::mint = mint_b
+    )]
+    signer_ata_b: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::authority = pool,
+        associated_token::mint = mint_a
+    )]
+    pool_ata_a: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::authority = pool,
+        associated_token::mint = mint_b
+    )]
+    pool_ata_b: Account<'info, TokenAccount>,
+    token_program: Program<'info, Token>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ModifyLiquidity<'info> {
+    pub fn increase_liquidity(&mut self, liquidity: u128, max_token_a: u64, max_token_b: u64) -> Result<()> {
+        require_gt!(liquidity, 0);
+        let liquidity_delta: i128 = liquidity.try_into().map_err(|_| ProgramError::ArithmeticOverflow)?;
+
+        let (amount_a, amount_b) = self.modify_position(liquidity_delta, true)?;
+
+        // Check slippage A
+        require_gte!(max_token_a, amount_a);
+
+        // Check slippage B
+        require_gte!(max_token_b, amount_b);
+
+        // Deposit Token A Amount
+        let accounts = Transfer {
+            from: self.signer_ata_a.to_account_info(),
+            to: self.pool_ata_a.to_account_info(),
+            authority: self.signer.to_account_info(),
+        };
+
+        let ctx = CpiContext::new(
+            self.token_program.to_account_info(), 
+            accounts
+        );
+        
+        transfer(ctx, amount_a)?;
+
+        // Deposit Token B Amount
+        let accounts = Transfer {
+            from: self.signer_ata_b.to_account_info(),
+            to: self.pool_ata_b.to_account_info(),
+            authority: self.signer.to_account_info(),
+        };
+
+        let ctx = CpiContext::new(
+            self.token_program.to_account_info(), 
+            accounts
+        );
+        
+        transfer(ctx, amount_b)
+    }
+
+    pub fn decrease_liquidity(&mut self, liquidity: u128, min_token_a: u64, min_token_b: u64) -> Result<()> {
+        require_gt!(liquidity, 0);
+        require_gte!(self.position.liquidity, liquidity);
+        let liquidity_delta: i128 = liquidity.try_into().map_err(|_| ProgramError::ArithmeticOverflow)?;
+
+        let (amount_a, amount_b) = self.modify_position(-liquidity_delta, false)?;
+
+        // Check slippage A
+        require_gte!(amount_a, min_token_a);
+
+        // Check slippage B
+        require_gte!(amount_b, min_token_b);
+
+        self.withdraw(amount_a, amount_b)
+    }
+
+    pub fn collect_fees(&mut self) -> Result<()> {
+        // Credit fees earned up to now without changing the liquidity
+        self.modify_position(0, false)?;
+
+        let (amount_a, amount_b) = (self.position.fees_owed_a, self.position.fees_owed_b);
+        self.position.fees_owed_a = 0;
+        self.position.fees_owed_b = 0;
+
+        self.withdraw(amount_a, amount_b)
+    }
+
+    /*
+        Update both bound ticks, the position and the active liquidity, and return the token amounts for the change
+
+        price below the range: only a
+        price inside the range: a from price to upper, b from lower to price
+        price above the range: only b
+
+        Deposits round up and withdrawals round down, in the pool's favour.
+    */
+    fn modify_position(&mut self, liquidity_delta: i128, round_up: bool) -> Result<(u64, u64)> {
+        let (tick_lower, tick_upper) = (self.position.tick_lower, self.position.tick_upper);
+        let tick_spacing = self.pool.tick_spacing;
+
+        let (fee_growth_inside_a, fee_growth_inside_b) = {
+            let mut tick_array = self.tick_array_lower.load_mut()?;
+            require_keys_eq!(tick_array.pool, self.pool.key(), AmmError::InvalidTickArray);
+            let lower = tick_array.tick_mut(tick_lower, tick_spacing)?;
+            lower.update(tick_lower, &self.pool, liquidity_delta, false)?;
+            let lower = *lower;
+            drop(tick_array);
+
+            let mut tick_array = self.tick_array_upper.load_mut()?;
+            require_keys_eq!(tick_array.pool, self.pool.key(), AmmError::InvalidTickArray);
+            let upper = tick_array.tick_mut(tick_upper, tick_spacing)?;
+            upper.update(tick_upper, &self.pool, liquidity_delta, true)?;
+
+            self.pool.fee_growth_inside(&lower, tick_lower, upper, tick_upper)
+        };
+
+        self.position.update(liquidity_delta, fee_growth_inside_a, fee_growth_inside_b)?;
+
+        let pool = &mut self.pool;
+        let sqrt_price_lower = sqrt_price_at_tick(tick_lower).ok_or(AmmError::InvalidTickRange)?;
+        let sqrt_price_upper = sqrt_price_at_tick(tick_upper).ok_or(AmmError::InvalidTickRange)?;
+        let liquidity = liquidity_delta.unsigned_abs();
+
+        let (amount_a, amount_b) = if pool.tick_current < tick_lower {
+            (amount_a_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up), Some(0))
+        } else if pool.tick_current < tick_upper {
+            pool.liquidity = add_liquidity_delta(pool.liquidity, liquidity_delta).ok_or(ProgramError::ArithmeticOverflow)?;
+            (
+                amount_a_delta(pool.sqrt_price_x64, sqrt_price_upper, liquidity, round_up),
+                amount_b_delta(sqrt_price_lower, pool.sqrt_price_x64, liquidity, round_up),
+            )
+        } else {
+            (Some(0), amount_b_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up))
+        };
+
+        let amount_a = amount_a.and_then(|amount| amount.try_into().ok()).ok_or(ProgramError::ArithmeticOverflow)?;
+        let amount_b = amount_b.and_then(|amount| amount.try_into().ok()).ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok((amount_a, amount_b))
+    }
+
+    fn withdraw(&self, amount_a: u64, amount_b: u64) -> Result<()> {
+        let binding = self.pool.fee.to_le_bytes();
+
+        let signer_seeds: [&[&[u8]];1] = [&[&b"cl_pool"[..], self.mint_a.to_account_info().key.as_ref(), self.mint_b.to_account_info().key.as_ref(), binding.as_ref(), &[self.pool.bump]]];
+
+        // Withdraw Token A Amount
+        let accounts = Transfer {
+            from: self.pool_ata_a.to_account_info(),
+            to: self.signer_ata_a.to_account_info(),
+            authority: self.pool.to_account_info(),
+        };
+
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(), 
+            accounts,
+            &signer_seeds
+        );
+        
+        transfer(ctx, amount_a)?;
+
+        // Withdraw Token B Amount
+        let accounts = Transfer {
+            from: self.pool_ata_b.to_account_info(),
+            to: self.signer_ata_b.to_account_info(),
+            authority: self.pool.to_account_info(),
+        };
+
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(), 
+            accounts,
+            &signer_seeds
+        );
+        
+        transfer(ctx, amount_b)
+    }
+}
@@ -8,4 +8,25 @@ pub mod withdraw;
 pub use withdraw::*;
 
 pub mod swap;
-pub use swap::*;
\ No newline at end of file
+pub use swap::*;
+
+pub mod initialize_cl_pool;
+pub use initialize_cl_pool::*;
+
+pub mod initialize_tick_array;
+pub use initialize_tick_array::*;
+
+pub mod open_position;
+pub use open_position::*;
+
+pub mod liquidity;
+pub use liquidity::*;
+
+pub mod cl_swap;
+pub use cl_swap::*;
+
+pub mod route_swap;
+pub use route_swap::*;
+
+pub mod repair_pool;
+pub use repair_pool::*;
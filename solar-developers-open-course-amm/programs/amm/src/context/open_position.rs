@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{mint_to, Mint, MintTo, Token, TokenAccount}};
+
+use crate::{error::AmmError, math::{MAX_TICK, MIN_TICK}, state::{ClPool, Position}, ANCHOR_DISCRIMINATOR_SIZE};
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    signer: Signer<'info>,
+    #[account(
+        seeds = [b"cl_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref(), pool.fee.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pool: Account<'info, ClPool>,
+    // The position NFT, a fresh keypair
+    #[account(
+        init,
+        payer = signer,
+        mint::decimals = 0,
+        mint::authority = pool
+    )]
+    position_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = signer,
+        associated_token::authority = signer,
+        associated_token::mint = position_mint
+    )]
+    signer_ata_position: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = signer,
+        space = ANCHOR_DISCRIMINATOR_SIZE + Position::INIT_SPACE,
+        seeds = [b"position", position_mint.key().as_ref()],
+        bump
+    )]
+    position: Account<'info, Position>,
+    token_program: Program<'info, Token>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> OpenPosition<'info> {
+    pub fn open_position(&mut self, tick_lower: i32, tick_upper: i32, bump: u8) -> Result<()> {
+        let spacing = self.pool.tick_spacing as i32;
+        require!(tick_lower < tick_upper, AmmError::InvalidTickRange);
+        require!(tick_lower >= MIN_TICK && tick_upper <= MAX_TICK, AmmError::InvalidTickRange);
+        require!(tick_lower % spacing == 0 && tick_upper % spacing == 0, AmmError::InvalidTickRange);
+
+        self.position.set_inner(Position {
+            pool: self.pool.key(),
+            position_mint: self.position_mint.key(),
+            tick_lower,
+            tick_upper,
+            liquidity: 0,
+            fee_growth_inside_a_last_x64: 0,
+            fee_growth_inside_b_last_x64: 0,
+            fees_owed_a: 0,
+            fees_owed_b: 0,
+            bump,
+        });
+
+        // Mint the position NFT
+        let accounts = MintTo {
+            mint: self.position_mint.to_account_info(),
+            to: self.signer_ata_position.to_account_info(),
+            authority: self.pool.to_account_info(),
+        };
+
+        let binding = self.pool.fee.to_le_bytes();
+
+        let signer_seeds: [&[&[u8]];1] = [&[&b"cl_pool"[..], self.pool.mint_a.as_ref(), self.pool.mint_b.as_ref(), binding.as_ref(), &[self.pool.bump]]];
+
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(), 
+            accounts, 
+            &signer_seeds
+        );
+
+        mint_to(ctx, 1)
+    }
+}
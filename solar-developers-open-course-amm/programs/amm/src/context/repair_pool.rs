@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::state::Pool;
+
+#[derive(Accounts)]
+pub struct RepairPool<'info> {
+    mint_a: Account<'info, Mint>,
+    mint_b: Account<'info, Mint>,
+    #[account(
+        mut,
+        has_one = mint_a,
+        seeds = [b"pool", mint_a.key().as_ref(), mint_b.key().as_ref(), pool.fee.to_le_bytes().as_ref()],
+        bump = pool.bump
+    )]
+    pool: Account<'info, Pool>,
+}
+
+impl<'info> RepairPool<'info> {
+    /*
+        Pools created before initialize stored mint_b correctly have mint_a in both fields,
+        so route_swap can not derive their address. The seeds prove which mint_b the pool
+        was created with, so anyone can write it back.
+    */
+    pub fn repair_pool(&mut self) -> Result<()> {
+        self.pool.mint_b = self.mint_b.key();
+        Ok(())
+    }
+}
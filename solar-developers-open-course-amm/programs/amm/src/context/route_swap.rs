@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::get_associated_token_address, token::{transfer, Token, TokenAccount, Transfer}};
+
+use crate::{error::AmmError, math::constant_product_amount_out, state::Pool};
+
+// Accounts per hop in remaining_accounts: pool, pool_ata_in, pool_ata_out, signer_ata_out
+pub const ROUTE_HOP_ACCOUNTS: usize = 4;
+
+#[derive(Accounts)]
+pub struct RouteSwap<'info> {
+    #[account(mut)]
+    signer: Signer<'info>,
+    #[account(
+        mut,
+        token::authority = signer
+    )]
+    signer_ata_in: Account<'info, TokenAccount>,
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> RouteSwap<'info> {
+    /*
+        Exact input swap through a chain of constant product pools in one transaction.
+
+        Each hop swaps the whole output of the previous hop. Intermediate tokens pass through
+        the signer's token accounts, and only the final output is checked for slippage.
+    */
+    pub fn route_swap(&mut self, amount_in: u64, min_amount_out: u64, hops: &'info [AccountInfo<'info>]) -> Result<()> {
+        let hops = hops.chunks_exact(ROUTE_HOP_ACCOUNTS);
+        require!(hops.len() > 0 && hops.remainder().is_empty(), AmmError::InvalidRoute);
+
+        let mut signer_in = self.signer_ata_in.to_account_info();
+        let mut mint_in = self.signer_ata_in.mint;
+        let mut amount = amount_in;
+
+        for hop in hops {
+            let pool = Account::<Pool>::try_from(&hop[0])?;
+            let pool_ata_in = Account::<TokenAccount>::try_from(&hop[1])?;
+            let pool_ata_out = Account::<TokenAccount>::try_from(&hop[2])?;
+            let signer_out = Account::<TokenAccount>::try_from(&hop[3])?;
+
+            // Same checks as the seeds and associated_token constraints on Swap
+            let binding = pool.fee.to_le_bytes();
+            let signer_seeds: [&[&[u8]];1] = [&[&b"pool"[..], pool.mint_a.as_ref(), pool.mint_b.as_ref(), binding.as_ref(), &[pool.bump]]];
+            let pool_key = Pubkey::create_program_address(signer_seeds[0], &crate::ID).map_err(|_| AmmError::InvalidRoute)?;
+            require_keys_eq!(pool_key, pool.key(), AmmError::InvalidRoute);
+
+            let mint_out = if mint_in == pool.mint_a {
+                pool.mint_b
+            } else if mint_in == pool.mint_b {
+                pool.mint_a
+            } else {
+                return err!(AmmError::InvalidRoute);
+            };
+            require_keys_eq!(pool_ata_in.key(), get_associated_token_address(&pool_key, &mint_in), AmmError::InvalidRoute);
+            require_keys_eq!(pool_ata_out.key(), get_associated_token_address(&pool_key, &mint_out), AmmError::InvalidRoute);
+            require_keys_eq!(signer_out.mint, mint_out, AmmError::InvalidRoute);
+            require_keys_eq!(signer_out.owner, self.signer.key(), AmmError::InvalidRoute);
+
+            let amount_out = constant_product_amount_out(pool_ata_in.amount, pool_ata_out.amount, amount, pool.fee)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            require_gt!(amount_out, 0);
+
+            // Deposit Token In Amount
+            let accounts = Transfer {
+                from: signer_in,
+                to: pool_ata_in.to_account_info(),
+                authority: self.signer.to_account_info()
+            };
+
+            let ctx = CpiContext::new(
+                self.token_program.to_account_info(), 
+                accounts
+            );
+            
+            transfer(ctx, amount)?;
+
+            // Withdraw Token Out Amount
+            let accounts = Transfer {
+                from: pool_ata_out.to_account_info(),
+                to: signer_out.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+
+            let ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(), 
+                accounts,
+                &signer_seeds
+            );
+            
+            transfer(ctx, amount_out)?;
+
+            signer_in = signer_out.to_account_info();
+            mint_in = mint_out;
+            amount = amount_out;
+        }
+
+        // Check slippage on the final output only
+        require_gte!(amount, min_amount_out);
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken, token::{transfer, Mint, Token, TokenAccount, Transfer}};
 
-use crate::state::Pool;
+use crate::{math::{constant_product_amount_in, constant_product_amount_out}, state::{Pool, SwapDirection}};
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
@@ -44,46 +44,59 @@ pub struct Swap<'info> {
 }
 
 impl<'info> Swap<'info> {
+    // Exact output: receive `amount` of token a (is_a) or token b, paying at most max_amount_in
     pub fn swap(&mut self, amount: u64, max_amount_in: u64, is_a: bool) -> Result<()> {
-        /*
-            k = ab
-            a2 = a - amount 
-            b2 = k2 / a2
-        */
-        let k = (self.pool_ata_a.amount as u128)
-            .checked_mul(self.pool_ata_b.amount.into()).ok_or(ProgramError::ArithmeticOverflow)?;
-
-        let (signer_in, signer_out, pool_in, pool_out, amount_in) = if is_a {
-            let a2 = self.pool_ata_a.amount.checked_sub(amount).ok_or(ProgramError::ArithmeticOverflow)?;
-            let b2: u64 = k.checked_div(a2.into()).ok_or(ProgramError::ArithmeticOverflow)?.try_into().map_err(|_| ProgramError::ArithmeticOverflow)?;    
-            (
-                self.signer_ata_b.to_account_info(),
+        let direction = if is_a { SwapDirection::BToA } else { SwapDirection::AToB };
+        let (reserve_in, reserve_out) = self.reserves(direction);
+
+        let amount_in = constant_product_amount_in(reserve_in, reserve_out, amount, self.pool.fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Check slippage
+        require_gte!(max_amount_in, amount_in);
+
+        self.transfer_swap(direction, amount_in, amount)
+    }
+
+    // Exact input: pay amount_in, receiving at least min_amount_out
+    pub fn swap_exact_in(&mut self, amount_in: u64, min_amount_out: u64, direction: SwapDirection) -> Result<()> {
+        let (reserve_in, reserve_out) = self.reserves(direction);
+
+        let amount_out = constant_product_amount_out(reserve_in, reserve_out, amount_in, self.pool.fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Check slippage
+        require_gte!(amount_out, min_amount_out);
+
+        self.transfer_swap(direction, amount_in, amount_out)
+    }
+
+    fn reserves(&self, direction: SwapDirection) -> (u64, u64) {
+        match direction {
+            SwapDirection::AToB => (self.pool_ata_a.amount, self.pool_ata_b.amount),
+            SwapDirection::BToA => (self.pool_ata_b.amount, self.pool_ata_a.amount),
+        }
+    }
+
+    // The whole input, fee included, goes into the pool
+    fn transfer_swap(&self, direction: SwapDirection, amount_in: u64, amount_out: u64) -> Result<()> {
+        require_gt!(amount_out, 0);
+
+        let (signer_in, signer_out, pool_in, pool_out) = match direction {
+            SwapDirection::AToB => (
                 self.signer_ata_a.to_account_info(),
-                self.pool_ata_b.to_account_info(),
+                self.signer_ata_b.to_account_info(),
                 self.pool_ata_a.to_account_info(),
-                b2 - self.pool_ata_b.amount
-            )
-        } else {
-            let b2 = self.pool_ata_b.amount.checked_sub(amount).ok_or(ProgramError::ArithmeticOverflow)?;
-            let a2: u64 = k.checked_div(b2.into()).ok_or(ProgramError::ArithmeticOverflow)?.try_into().map_err(|_| ProgramError::ArithmeticOverflow)?;
-            (
+                self.pool_ata_b.to_account_info(),
+            ),
+            SwapDirection::BToA => (
                 self.signer_ata_b.to_account_info(),
                 self.signer_ata_a.to_account_info(),
                 self.pool_ata_b.to_account_info(),
                 self.pool_ata_a.to_account_info(),
-                a2 - self.pool_ata_a.amount
-            )
+            ),
         };
 
-        let amount_in_with_fees: u64 = (amount_in as u128)
-            .checked_mul(10_000 + self.pool.fee as u128)
-            .ok_or(ProgramError::ArithmeticOverflow)?
-            .checked_div(10_000).ok_or(ProgramError::ArithmeticOverflow)?
-            .try_into().map_err(|_| ProgramError::ArithmeticOverflow)?;
-
-        // Check slippage
-        require_gte!(max_amount_in, amount_in_with_fees);
-
         // Deposit Token In Amount
         let accounts = Transfer {
             from: signer_in,
@@ -96,9 +109,9 @@ impl<'info> Swap<'info> {
             accounts
         );
         
-        transfer(ctx, amount_in_with_fees)?;
+        transfer(ctx, amount_in)?;
 
-        // Deposit Token B Amount
+        // Withdraw Token Out Amount
         let accounts = Transfer {
             from: pool_out,
             to: signer_out,
@@ -115,6 +128,6 @@ impl<'info> Swap<'info> {
             &signer_seeds
         );
         
-        transfer(ctx, amount)
+        transfer(ctx, amount_out)
     }
-}
\ No newline at end of file
+}
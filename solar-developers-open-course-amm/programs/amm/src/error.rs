@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Mints must be different")]
+    IdenticalMints,
+    #[msg("Fee must be below 10000 bps")]
+    InvalidFee,
+    #[msg("Invalid tick spacing")]
+    InvalidTickSpacing,
+    #[msg("Invalid tick range")]
+    InvalidTickRange,
+    #[msg("Sqrt price out of range")]
+    InvalidSqrtPrice,
+    #[msg("Tick array does not match the pool or tick")]
+    InvalidTickArray,
+    #[msg("Route accounts do not form a chain of pools")]
+    InvalidRoute,
+}
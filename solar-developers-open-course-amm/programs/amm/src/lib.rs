@@ -2,8 +2,13 @@ use anchor_lang::prelude::*;
 
 pub mod state;
 
+pub mod math;
+
+pub mod error;
+
 pub mod context;
 pub use context::*;
+use state::SwapDirection;
 
 declare_id!("2wmWHXHy6F3Yz2CaaWNp7Bfgz4c4kpPXuHiDyVajZARu");
 
@@ -26,6 +31,49 @@ pub mod amm {
     pub fn swap(ctx: Context<Swap>, amount: u64, max_amount_in: u64, is_a: bool) -> Result<()> {
         ctx.accounts.swap(amount, max_amount_in, is_a)
     }
+
+    pub fn swap_exact_in(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64, direction: SwapDirection) -> Result<()> {
+        ctx.accounts.swap_exact_in(amount_in, min_amount_out, direction)
+    }
+
+    // Each hop is pool, pool_ata_in, pool_ata_out, signer_ata_out in remaining_accounts
+    pub fn route_swap<'info>(ctx: Context<'_, '_, 'info, 'info, RouteSwap<'info>>, amount_in: u64, min_amount_out: u64) -> Result<()> {
+        ctx.accounts.route_swap(amount_in, min_amount_out, ctx.remaining_accounts)
+    }
+
+    // Writes back the mint_b of pools created before initialize stored it
+    pub fn repair_pool(ctx: Context<RepairPool>) -> Result<()> {
+        ctx.accounts.repair_pool()
+    }
+
+    pub fn initialize_cl_pool(ctx: Context<InitializeClPool>, fee: u16, tick_spacing: u16, sqrt_price_x64: u128) -> Result<()> {
+        ctx.accounts.initialize_cl_pool(fee, tick_spacing, sqrt_price_x64, ctx.bumps.pool)
+    }
+
+    pub fn initialize_tick_array(ctx: Context<InitializeTickArray>, start_tick_index: i32) -> Result<()> {
+        ctx.accounts.initialize_tick_array(start_tick_index)
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>, tick_lower: i32, tick_upper: i32) -> Result<()> {
+        ctx.accounts.open_position(tick_lower, tick_upper, ctx.bumps.position)
+    }
+
+    pub fn increase_liquidity(ctx: Context<ModifyLiquidity>, liquidity: u128, max_token_a: u64, max_token_b: u64) -> Result<()> {
+        ctx.accounts.increase_liquidity(liquidity, max_token_a, max_token_b)
+    }
+
+    pub fn decrease_liquidity(ctx: Context<ModifyLiquidity>, liquidity: u128, min_token_a: u64, min_token_b: u64) -> Result<()> {
+        ctx.accounts.decrease_liquidity(liquidity, min_token_a, min_token_b)
+    }
+
+    pub fn collect_fees(ctx: Context<ModifyLiquidity>) -> Result<()> {
+        ctx.accounts.collect_fees()
+    }
+
+    // Tick arrays in the swap direction go in remaining_accounts
+    pub fn cl_swap<'info>(ctx: Context<'_, '_, 'info, 'info, ClSwap<'info>>, amount_in: u64, min_amount_out: u64, a_to_b: bool) -> Result<()> {
+        ctx.accounts.cl_swap(amount_in, min_amount_out, a_to_b, ctx.remaining_accounts)
+    }
 }
 
 // deposit 应该有一个初始化流动性 和 追加流动性，先当它有一个初始化了
\ No newline at end of file
@@ -0,0 +1,453 @@
+// Swap math for the constant product pool, and fixed point helpers for the concentrated liquidity pool.
+// Concentrated liquidity prices are stored as sqrt(price) in Q64.64, where price = amount of b per unit of a.
+
+/*
+    Constant product swaps. The fee (in bps) is charged on the input and stays in the reserves,
+    so k only grows.
+
+    out = reserve_out * in_less_fee / (reserve_in + in_less_fee), in_less_fee = in * (10000 - fee) / 10000
+
+    Both round in the pool's favour.
+*/
+pub fn constant_product_amount_out(reserve_in: u64, reserve_out: u64, amount_in: u64, fee: u16) -> Option<u64> {
+    let amount_in_less_fee = (amount_in as u128)
+        .checked_mul(10_000u128.checked_sub(fee as u128)?)?
+        / 10_000;
+    let denominator = (reserve_in as u128).checked_add(amount_in_less_fee)?;
+    (reserve_out as u128)
+        .checked_mul(amount_in_less_fee)?
+        .checked_div(denominator)?
+        .try_into()
+        .ok()
+}
+
+// in = reserve_in * out / (reserve_out - out) / (1 - fee), rounded up
+pub fn constant_product_amount_in(reserve_in: u64, reserve_out: u64, amount_out: u64, fee: u16) -> Option<u64> {
+    let numerator = (reserve_in as u128)
+        .checked_mul(amount_out as u128)?
+        .checked_mul(10_000)?;
+    let denominator = (reserve_out.checked_sub(amount_out)? as u128)
+        .checked_mul(10_000u128.checked_sub(fee as u128)?)?;
+    if denominator == 0 {
+        return None;
+    }
+    numerator.div_ceil(denominator).try_into().ok()
+}
+
+pub const Q64: u128 = 1 << 64;
+
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+
+// sqrt_price_at_tick(MIN_TICK) and sqrt_price_at_tick(MAX_TICK)
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+// 2^64 / sqrt(1.0001)^(2^i)
+const TICK_RATIOS_X64: [u128; 19] = [
+    0xfffcb933bd6fad37,
+    0xfff97272373d4132,
+    0xfff2e50f5f656932,
+    0xffe5caca7e10e4e6,
+    0xffcb9843d60f6159,
+    0xff973b41fa98c081,
+    0xff2ea16466c96a38,
+    0xfe5dee046a99a2a8,
+    0xfcbe86c7900a88ae,
+    0xf987a7253ac41317,
+    0xf3392b0822b70005,
+    0xe7159475a2c29b74,
+    0xd097f3bdfd2022b8,
+    0xa9f746462d870fdf,
+    0x70d869a156d2a1b8,
+    0x31be135f97d08fd9,
+    0x09aa508b5b7a84e1,
+    0x005d6af8dedb8119,
+    0x00002216e584f5fa,
+];
+
+/*
+    sqrt(1.0001^tick) in Q64.64
+
+    Multiply the ratios for each bit of |tick|, which gives the price of -|tick|.
+    Every ratio is below 1.0, so the product always fits in a u128 before the shift.
+    Positive ticks are the inverse.
+*/
+pub fn sqrt_price_at_tick(tick: i32) -> Option<u128> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    let abs_tick = tick.unsigned_abs();
+
+    let mut ratio = Q64;
+    for (i, tick_ratio) in TICK_RATIOS_X64.iter().enumerate() {
+        if abs_tick & (1 << i) != 0 {
+            ratio = (ratio * tick_ratio) >> 64;
+        }
+    }
+
+    if tick > 0 {
+        Some(u128::MAX / ratio)
+    } else {
+        Some(ratio)
+    }
+}
+
+// log_sqrt(1.0001)(2) in Q32.32
+const LOG_B_2_X32: i128 = 59_543_866_431_248;
+// Fraction bits of log2 computed below
+const LOG2_PRECISION_BITS: u32 = 14;
+// Error margins of the tick estimate in Q64.64: 0.01 below, 2^-14 / log2(sqrt(1.0001)) + 0.01 above
+const LOG_B_ERR_MARGIN_LOWER_X64: i128 = 184_467_440_737_095_516;
+const LOG_B_ERR_MARGIN_UPPER_X64: i128 = 15_793_534_762_490_258_745;
+
+/*
+    Greatest tick whose sqrt price is less than or equal to sqrt_price_x64
+
+    tick = log_sqrt(1.0001)(sqrt_price) = log2(sqrt_price) * log_sqrt(1.0001)(2)
+
+    log2 is the position of the top bit plus a fraction found by repeated squaring.
+    The fraction is only 14 bits, so the estimate is one of two ticks and one
+    sqrt_price_at_tick call picks between them.
+*/
+pub fn tick_at_sqrt_price(sqrt_price_x64: u128) -> Option<i32> {
+    if !(MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64) {
+        return None;
+    }
+
+    // Integer part
+    let msb = 127 - sqrt_price_x64.leading_zeros();
+    let log2_integer_x32 = (msb as i128 - 64) << 32;
+
+    // Fractional part: r is sqrt_price / 2^msb in Q1.63, in [1, 2)
+    let mut r = if msb >= 63 {
+        sqrt_price_x64 >> (msb - 63)
+    } else {
+        sqrt_price_x64 << (63 - msb)
+    };
+    let mut bit: i128 = 1 << 63;
+    let mut log2_fraction_x64: i128 = 0;
+    for _ in 0..LOG2_PRECISION_BITS {
+        r *= r;
+        let is_r_at_least_two = (r >> 127) as u32;
+        r >>= 63 + is_r_at_least_two;
+        log2_fraction_x64 += bit * is_r_at_least_two as i128;
+        bit >>= 1;
+    }
+    let log2_x32 = log2_integer_x32 + (log2_fraction_x64 >> 32);
+
+    let log_b_x64 = log2_x32 * LOG_B_2_X32;
+    let tick_low = ((log_b_x64 - LOG_B_ERR_MARGIN_LOWER_X64) >> 64) as i32;
+    let tick_high = ((log_b_x64 + LOG_B_ERR_MARGIN_UPPER_X64) >> 64) as i32;
+
+    if tick_low == tick_high || sqrt_price_at_tick(tick_high)? > sqrt_price_x64 {
+        Some(tick_low)
+    } else {
+        Some(tick_high)
+    }
+}
+
+// (hi, lo) of the 256 bit product a * b
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let lo = (p00 & MASK) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+// a * b / denominator with a 256 bit intermediate, None if the result does not fit in a u128
+pub fn mul_div(a: u128, b: u128, denominator: u128, round_up: bool) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    let (hi, lo) = full_mul(a, b);
+    if hi >= denominator {
+        return None;
+    }
+
+    let (quotient, remainder) = if hi == 0 {
+        (lo / denominator, lo % denominator)
+    } else {
+        // Shift-subtract long division of (hi, lo) by the denominator
+        let mut remainder = hi;
+        let mut quotient = 0u128;
+        for i in (0..128).rev() {
+            let carry = remainder >> 127;
+            remainder = (remainder << 1) | ((lo >> i) & 1);
+            quotient <<= 1;
+            if carry == 1 || remainder >= denominator {
+                remainder = remainder.wrapping_sub(denominator);
+                quotient |= 1;
+            }
+        }
+        (quotient, remainder)
+    };
+
+    if round_up && remainder > 0 {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+/*
+    Token a between two prices: L / sqrt_lower - L / sqrt_upper
+*/
+pub fn amount_a_delta(sqrt_price_lower: u128, sqrt_price_upper: u128, liquidity: u128, round_up: bool) -> Option<u128> {
+    let (sqrt_price_lower, sqrt_price_upper) = if sqrt_price_lower > sqrt_price_upper {
+        (sqrt_price_upper, sqrt_price_lower)
+    } else {
+        (sqrt_price_lower, sqrt_price_upper)
+    };
+    let at_lower = mul_div(liquidity, Q64, sqrt_price_lower, round_up)?;
+    let at_upper = mul_div(liquidity, Q64, sqrt_price_upper, !round_up)?;
+    Some(at_lower.saturating_sub(at_upper))
+}
+
+/*
+    Token b between two prices: L * (sqrt_upper - sqrt_lower)
+*/
+pub fn amount_b_delta(sqrt_price_lower: u128, sqrt_price_upper: u128, liquidity: u128, round_up: bool) -> Option<u128> {
+    let diff = sqrt_price_lower.abs_diff(sqrt_price_upper);
+    mul_div(liquidity, diff, Q64, round_up)
+}
+
+/*
+    Price after adding amount_in of the input token.
+
+    a in: sqrt_next = L * sqrt_price / (L + amount * sqrt_price), rounded up
+    b in: sqrt_next = sqrt_price + amount / L, rounded down
+
+    Both round towards the current price, so the pool never gives out more than the input pays for.
+*/
+pub fn next_sqrt_price_from_input(sqrt_price_x64: u128, liquidity: u128, amount_in: u64, a_to_b: bool) -> Option<u128> {
+    if amount_in == 0 {
+        return Some(sqrt_price_x64);
+    }
+    if a_to_b {
+        let product = mul_div(amount_in as u128, sqrt_price_x64, Q64, false)?;
+        let denominator = liquidity.checked_add(product)?;
+        mul_div(liquidity, sqrt_price_x64, denominator, true)
+    } else {
+        let delta = mul_div(amount_in as u128, Q64, liquidity, false)?;
+        sqrt_price_x64.checked_add(delta)
+    }
+}
+
+pub struct SwapStep {
+    pub sqrt_price_next_x64: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/*
+    One exact input step inside a single liquidity range, moving at most to sqrt_price_target_x64.
+
+    The fee (in bps) comes off the input first. If the rest is enough to reach the target,
+    only the fee on the amount used is charged. Otherwise the whole remainder is used and
+    everything that did not move the price is the fee.
+*/
+pub fn compute_swap_step(
+    sqrt_price_x64: u128,
+    sqrt_price_target_x64: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee: u16,
+    a_to_b: bool,
+) -> Option<SwapStep> {
+    let fee = fee as u128;
+    let amount_less_fee = mul_div(amount_remaining as u128, 10_000 - fee, 10_000, false)?;
+
+    let max_amount_in = if a_to_b {
+        amount_a_delta(sqrt_price_target_x64, sqrt_price_x64, liquidity, true)?
+    } else {
+        amount_b_delta(sqrt_price_x64, sqrt_price_target_x64, liquidity, true)?
+    };
+
+    let reached_target = amount_less_fee >= max_amount_in;
+    let sqrt_price_next_x64 = if reached_target {
+        sqrt_price_target_x64
+    } else {
+        next_sqrt_price_from_input(sqrt_price_x64, liquidity, amount_less_fee as u64, a_to_b)?
+    };
+
+    let (amount_in, amount_out) = if a_to_b {
+        (
+            amount_a_delta(sqrt_price_next_x64, sqrt_price_x64, liquidity, true)?,
+            amount_b_delta(sqrt_price_next_x64, sqrt_price_x64, liquidity, false)?,
+        )
+    } else {
+        (
+            amount_b_delta(sqrt_price_x64, sqrt_price_next_x64, liquidity, true)?,
+            amount_a_delta(sqrt_price_x64, sqrt_price_next_x64, liquidity, false)?,
+        )
+    };
+    let amount_in = amount_in.min(amount_less_fee);
+
+    let fee_amount = if reached_target {
+        mul_div(amount_in, fee, 10_000 - fee, true)?
+    } else {
+        (amount_remaining as u128).checked_sub(amount_in)?
+    };
+
+    Some(SwapStep {
+        sqrt_price_next_x64,
+        amount_in: amount_in.try_into().ok()?,
+        amount_out: amount_out.try_into().ok()?,
+        fee_amount: fee_amount.try_into().ok()?,
+    })
+}
+
+// Apply a signed liquidity change
+pub fn add_liquidity_delta(liquidity: u128, delta: i128) -> Option<u128> {
+    if delta < 0 {
+        liquidity.checked_sub(delta.unsigned_abs())
+    } else {
+        liquidity.checked_add(delta as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_price_at_tick_bounds() {
+        assert_eq!(sqrt_price_at_tick(0), Some(Q64));
+        assert_eq!(sqrt_price_at_tick(MIN_TICK), Some(MIN_SQRT_PRICE_X64));
+        assert_eq!(sqrt_price_at_tick(MAX_TICK), Some(MAX_SQRT_PRICE_X64));
+        assert_eq!(sqrt_price_at_tick(MIN_TICK - 1), None);
+        assert_eq!(sqrt_price_at_tick(MAX_TICK + 1), None);
+
+        // sqrt(1.0001) = 1.0000499987...
+        assert_eq!(sqrt_price_at_tick(1), Some(18_447_666_387_855_959_851));
+        assert_eq!(sqrt_price_at_tick(-1), Some(18_445_821_805_675_392_311));
+
+        let mut previous = 0;
+        for tick in (MIN_TICK..=MAX_TICK).step_by(997) {
+            let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+            assert!(sqrt_price > previous);
+            previous = sqrt_price;
+        }
+    }
+
+    #[test]
+    fn tick_at_sqrt_price_round_trip() {
+        for tick in [MIN_TICK, MIN_TICK + 1, -200_000, -128, -1, 0, 1, 64, 128, 200_000, MAX_TICK - 1, MAX_TICK] {
+            let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+            assert_eq!(tick_at_sqrt_price(sqrt_price), Some(tick));
+            if tick > MIN_TICK {
+                assert_eq!(tick_at_sqrt_price(sqrt_price - 1), Some(tick - 1));
+            }
+            if tick < MAX_TICK {
+                assert_eq!(tick_at_sqrt_price(sqrt_price + 1), Some(tick));
+                let next = sqrt_price_at_tick(tick + 1).unwrap();
+                assert_eq!(tick_at_sqrt_price(next - 1), Some(tick));
+            }
+        }
+        assert_eq!(tick_at_sqrt_price(MIN_SQRT_PRICE_X64 - 1), None);
+        assert_eq!(tick_at_sqrt_price(MAX_SQRT_PRICE_X64 + 1), None);
+    }
+
+    #[test]
+    fn mul_div_rounding() {
+        assert_eq!(mul_div(7, 3, 2, false), Some(10));
+        assert_eq!(mul_div(7, 3, 2, true), Some(11));
+        assert_eq!(mul_div(6, 3, 2, true), Some(9));
+        assert_eq!(mul_div(1, 1, 0, false), None);
+    }
+
+    #[test]
+    fn mul_div_wide_product() {
+        // Products above 2^128 go through the long division
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, false), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, true), Some(u128::MAX));
+        // (2^100 * 3^40) / 7^30
+        assert_eq!(mul_div(1 << 100, 3u128.pow(40), 7u128.pow(30), false), Some(683_767_657_694_571_514_400_089));
+        assert_eq!(mul_div(1 << 100, 3u128.pow(40), 7u128.pow(30), true), Some(683_767_657_694_571_514_400_090));
+        // u128::MAX = 3 * 0x5555...5555, so 2 * u128::MAX / 3 is exact
+        assert_eq!(mul_div(u128::MAX, 2, 3, true), Some(u128::MAX / 3 * 2));
+        assert_eq!(mul_div(u128::MAX - 1, 2, 3, true), Some(u128::MAX / 3 * 2));
+        assert_eq!(mul_div(u128::MAX - 1, 2, 3, false), Some(u128::MAX / 3 * 2 - 1));
+        // Quotient does not fit
+        assert_eq!(mul_div(u128::MAX, 2, 1, false), None);
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX - 1, false), None);
+    }
+
+    #[test]
+    fn amount_deltas() {
+        let lower = sqrt_price_at_tick(-128).unwrap();
+        let upper = sqrt_price_at_tick(128).unwrap();
+        let liquidity = 1_000_000;
+
+        // L * (1 - 1.0001^-64) ~ 6379.6 on both sides of price 1
+        assert_eq!(amount_a_delta(Q64, upper, liquidity, false), Some(6379));
+        assert_eq!(amount_a_delta(Q64, upper, liquidity, true), Some(6380));
+        assert_eq!(amount_b_delta(lower, Q64, liquidity, false), Some(6379));
+        assert_eq!(amount_b_delta(lower, Q64, liquidity, true), Some(6380));
+
+        // Order of the prices does not matter
+        assert_eq!(amount_a_delta(upper, Q64, liquidity, true), amount_a_delta(Q64, upper, liquidity, true));
+        assert_eq!(amount_b_delta(Q64, lower, liquidity, true), amount_b_delta(lower, Q64, liquidity, true));
+        assert_eq!(amount_a_delta(Q64, Q64, liquidity, true), Some(0));
+    }
+
+    #[test]
+    fn swap_step_within_range() {
+        let liquidity = 1_000_000_000;
+        for a_to_b in [true, false] {
+            let target = if a_to_b { MIN_SQRT_PRICE_X64 } else { MAX_SQRT_PRICE_X64 };
+            let step = compute_swap_step(Q64, target, liquidity, 1_000, 30, a_to_b).unwrap();
+            // The whole input is used, 0.3% of it as fee
+            assert_eq!(step.amount_in + step.fee_amount, 1_000);
+            assert_eq!(step.amount_in, 997);
+            assert_eq!(step.amount_out, 996);
+            assert_ne!(step.sqrt_price_next_x64, target);
+            assert_eq!(step.sqrt_price_next_x64 < Q64, a_to_b);
+        }
+    }
+
+    #[test]
+    fn swap_step_reaches_target() {
+        let liquidity = 1_000_000_000;
+        let target = sqrt_price_at_tick(-10).unwrap();
+        let step = compute_swap_step(Q64, target, liquidity, 10_000_000, 30, true).unwrap();
+
+        assert_eq!(step.sqrt_price_next_x64, target);
+        assert_eq!(step.amount_in as u128, amount_a_delta(target, Q64, liquidity, true).unwrap());
+        // Fee only on the amount used: ceil(in * 30 / 9970)
+        assert_eq!(step.fee_amount as u128, (step.amount_in as u128 * 30).div_ceil(9_970));
+        assert!(step.amount_out < step.amount_in);
+
+        // Zero size step when the target is the current price
+        let step = compute_swap_step(target, target, liquidity, 10_000, 30, false).unwrap();
+        assert_eq!((step.amount_in, step.amount_out, step.fee_amount), (0, 0, 0));
+        assert_eq!(step.sqrt_price_next_x64, target);
+    }
+
+    #[test]
+    fn constant_product_fees_stay_in_reserves() {
+        // 10 in, 9 after the 5% fee: 60 * 9 / 50 = 10
+        assert_eq!(constant_product_amount_out(41, 60, 10, 500), Some(10));
+        // ceil(25 * 4 / 21 / 0.95) = 6
+        assert_eq!(constant_product_amount_in(25, 25, 4, 500), Some(6));
+        // Cannot take the whole reserve
+        assert_eq!(constant_product_amount_in(25, 25, 25, 500), None);
+
+        let (reserve_in, reserve_out) = (1_000_000u64, 2_000_000u64);
+        let amount_out = constant_product_amount_out(reserve_in, reserve_out, 50_000, 30).unwrap();
+        let k = reserve_in as u128 * reserve_out as u128;
+        assert!((reserve_in + 50_000) as u128 * (reserve_out - amount_out) as u128 > k);
+        assert!(constant_product_amount_in(reserve_in, reserve_out, amount_out, 30).unwrap() <= 50_000);
+    }
+}
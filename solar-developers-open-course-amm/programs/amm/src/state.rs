@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::AmmError, math::{add_liquidity_delta, mul_div, Q64}};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Pool {
@@ -8,4 +10,209 @@ pub struct Pool {
     pub fee: u16, // 100 1.00 2000 20.00
     pub bump: u8,
     pub lp_bump: u8,
-}
\ No newline at end of file
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SwapDirection {
+    AToB,
+    BToA,
+}
+
+pub const TICK_ARRAY_SIZE: usize = 64;
+
+// Concentrated liquidity pool. Liquidity is only active while the price is inside a position's tick range.
+#[account]
+#[derive(InitSpace)]
+pub struct ClPool {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub fee: u16, // bps of the input, same as Pool
+    pub tick_spacing: u16,
+    pub sqrt_price_x64: u128, // sqrt(b per a) in Q64.64
+    pub tick_current: i32,
+    pub liquidity: u128, // active liquidity at the current price
+    pub fee_growth_global_a_x64: u128, // fees per unit of liquidity, Q64.64, wraps around
+    pub fee_growth_global_b_x64: u128,
+    pub bump: u8,
+}
+
+impl ClPool {
+    /*
+        Fees earned per unit of liquidity between two ticks
+
+        below = fee growth under tick_lower, above = fee growth over tick_upper
+        inside = global - below - above
+
+        Every value can wrap around, only differences between snapshots matter.
+    */
+    pub fn fee_growth_inside(&self, lower: &Tick, tick_lower: i32, upper: &Tick, tick_upper: i32) -> (u128, u128) {
+        let (below_a, below_b) = if self.tick_current >= tick_lower {
+            (lower.fee_growth_outside_a_x64, lower.fee_growth_outside_b_x64)
+        } else {
+            (
+                self.fee_growth_global_a_x64.wrapping_sub(lower.fee_growth_outside_a_x64),
+                self.fee_growth_global_b_x64.wrapping_sub(lower.fee_growth_outside_b_x64),
+            )
+        };
+        let (above_a, above_b) = if self.tick_current < tick_upper {
+            (upper.fee_growth_outside_a_x64, upper.fee_growth_outside_b_x64)
+        } else {
+            (
+                self.fee_growth_global_a_x64.wrapping_sub(upper.fee_growth_outside_a_x64),
+                self.fee_growth_global_b_x64.wrapping_sub(upper.fee_growth_outside_b_x64),
+            )
+        };
+        (
+            self.fee_growth_global_a_x64.wrapping_sub(below_a).wrapping_sub(above_a),
+            self.fee_growth_global_b_x64.wrapping_sub(below_b).wrapping_sub(above_b),
+        )
+    }
+}
+
+#[zero_copy]
+pub struct Tick {
+    pub liquidity_net: i128, // added when the price crosses up, removed when it crosses down
+    pub liquidity_gross: u128, // total liquidity referencing this tick, 0 = uninitialized
+    pub fee_growth_outside_a_x64: u128, // fee growth on the other side of this tick from the current price
+    pub fee_growth_outside_b_x64: u128,
+}
+
+impl Tick {
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross > 0
+    }
+
+    // Add a position's liquidity change to one of its bounds
+    pub fn update(&mut self, tick: i32, pool: &ClPool, liquidity_delta: i128, is_upper: bool) -> Result<()> {
+        let liquidity_gross = add_liquidity_delta(self.liquidity_gross, liquidity_delta)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // By convention all fee growth so far happened below a new tick.
+        // A tick that drops back to 0 keeps its old values until it is used again.
+        if !self.is_initialized() {
+            (self.fee_growth_outside_a_x64, self.fee_growth_outside_b_x64) = if tick <= pool.tick_current {
+                (pool.fee_growth_global_a_x64, pool.fee_growth_global_b_x64)
+            } else {
+                (0, 0)
+            };
+        }
+
+        self.liquidity_gross = liquidity_gross;
+        self.liquidity_net = if is_upper {
+            self.liquidity_net.checked_sub(liquidity_delta)
+        } else {
+            self.liquidity_net.checked_add(liquidity_delta)
+        }.ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    // The price moved to the other side of this tick
+    pub fn cross(&mut self, fee_growth_global_a_x64: u128, fee_growth_global_b_x64: u128) -> i128 {
+        self.fee_growth_outside_a_x64 = fee_growth_global_a_x64.wrapping_sub(self.fee_growth_outside_a_x64);
+        self.fee_growth_outside_b_x64 = fee_growth_global_b_x64.wrapping_sub(self.fee_growth_outside_b_x64);
+        self.liquidity_net
+    }
+}
+
+// TICK_ARRAY_SIZE ticks, tick_spacing apart, starting at start_tick_index
+#[account(zero_copy)]
+pub struct TickArray {
+    pub ticks: [Tick; TICK_ARRAY_SIZE],
+    pub pool: Pubkey,
+    pub start_tick_index: i32,
+    pub padding: [u8; 12],
+}
+
+impl TickArray {
+    pub fn ticks_in_array(tick_spacing: u16) -> i32 {
+        TICK_ARRAY_SIZE as i32 * tick_spacing as i32
+    }
+
+    // Start of the array that holds tick
+    pub fn start_index_for(tick: i32, tick_spacing: u16) -> i32 {
+        let ticks_in_array = Self::ticks_in_array(tick_spacing);
+        tick.div_euclid(ticks_in_array) * ticks_in_array
+    }
+
+    pub fn contains(&self, tick: i32, tick_spacing: u16) -> bool {
+        tick >= self.start_tick_index && tick < self.start_tick_index + Self::ticks_in_array(tick_spacing)
+    }
+
+    pub fn tick_mut(&mut self, tick: i32, tick_spacing: u16) -> Result<&mut Tick> {
+        require!(self.contains(tick, tick_spacing), AmmError::InvalidTickArray);
+        require!(tick % tick_spacing as i32 == 0, AmmError::InvalidTickRange);
+        let offset = ((tick - self.start_tick_index) / tick_spacing as i32) as usize;
+        Ok(&mut self.ticks[offset])
+    }
+
+    /*
+        Next initialized tick in this array in the swap direction
+
+        a to b (price down): the highest tick <= tick_current
+        b to a (price up): the lowest tick > tick_current
+    */
+    pub fn next_initialized_tick(&self, tick_current: i32, tick_spacing: u16, a_to_b: bool) -> Option<i32> {
+        let spacing = tick_spacing as i32;
+        let offset = (tick_current - self.start_tick_index).div_euclid(spacing);
+        let tick_at = |offset: usize| self.start_tick_index + offset as i32 * spacing;
+
+        if a_to_b {
+            if offset < 0 {
+                return None;
+            }
+            let last = (offset as usize).min(TICK_ARRAY_SIZE - 1);
+            (0..=last).rev().find(|&i| self.ticks[i].is_initialized()).map(tick_at)
+        } else {
+            let first = (offset + 1).max(0) as usize;
+            (first..TICK_ARRAY_SIZE).find(|&i| self.ticks[i].is_initialized()).map(tick_at)
+        }
+    }
+}
+
+// One LP's liquidity in a tick range. Whoever holds the single token of position_mint owns it.
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub pool: Pubkey,
+    pub position_mint: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    pub fee_growth_inside_a_last_x64: u128,
+    pub fee_growth_inside_b_last_x64: u128,
+    pub fees_owed_a: u64,
+    pub fees_owed_b: u64,
+    pub bump: u8,
+}
+
+impl Position {
+    // Credit the fees earned since the last update, then apply the liquidity change
+    pub fn update(&mut self, liquidity_delta: i128, fee_growth_inside_a_x64: u128, fee_growth_inside_b_x64: u128) -> Result<()> {
+        let earned_a = mul_div(
+            fee_growth_inside_a_x64.wrapping_sub(self.fee_growth_inside_a_last_x64),
+            self.liquidity,
+            Q64,
+            false,
+        ).ok_or(ProgramError::ArithmeticOverflow)?;
+        let earned_b = mul_div(
+            fee_growth_inside_b_x64.wrapping_sub(self.fee_growth_inside_b_last_x64),
+            self.liquidity,
+            Q64,
+            false,
+        ).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        self.fees_owed_a = (self.fees_owed_a as u128)
+            .checked_add(earned_a)
+            .and_then(|owed| owed.try_into().ok())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.fees_owed_b = (self.fees_owed_b as u128)
+            .checked_add(earned_b)
+            .and_then(|owed| owed.try_into().ok())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        self.liquidity = add_liquidity_delta(self.liquidity, liquidity_delta).ok_or(ProgramError::ArithmeticOverflow)?;
+        self.fee_growth_inside_a_last_x64 = fee_growth_inside_a_x64;
+        self.fee_growth_inside_b_last_x64 = fee_growth_inside_b_x64;
+        Ok(())
+    }
+}
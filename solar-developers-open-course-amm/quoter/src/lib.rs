@@ -0,0 +1,256 @@
+//! Off-chain quoter for `route_swap`.
+//!
+//! Give it the constant product pools you know about with their current reserves, and it finds the
+//! route with the largest output using the same math as the program. The route then builds the
+//! remaining accounts for the instruction.
+
+use amm::{math::constant_product_amount_out, state::Pool};
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_spl::associated_token::get_associated_token_address;
+
+#[derive(Clone, Debug)]
+pub struct PoolInfo {
+    pub address: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub fee: u16,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+impl PoolInfo {
+    // reserve_a and reserve_b are the balances of the pool's associated token accounts
+    pub fn new(address: Pubkey, pool: &Pool, reserve_a: u64, reserve_b: u64) -> Self {
+        Self {
+            address,
+            mint_a: pool.mint_a,
+            mint_b: pool.mint_b,
+            fee: pool.fee,
+            reserve_a,
+            reserve_b,
+        }
+    }
+
+    // The other mint and the output for amount_in of mint_in, None if the pool does not trade mint_in
+    pub fn quote(&self, mint_in: &Pubkey, amount_in: u64) -> Option<(Pubkey, u64)> {
+        let (mint_out, reserve_in, reserve_out) = if *mint_in == self.mint_a {
+            (self.mint_b, self.reserve_a, self.reserve_b)
+        } else if *mint_in == self.mint_b {
+            (self.mint_a, self.reserve_b, self.reserve_a)
+        } else {
+            return None;
+        };
+        let amount_out = constant_product_amount_out(reserve_in, reserve_out, amount_in, self.fee)?;
+        (amount_out > 0).then_some((mint_out, amount_out))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hop {
+    pub pool: Pubkey,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Route {
+    pub hops: Vec<Hop>,
+}
+
+impl Route {
+    pub fn amount_in(&self) -> u64 {
+        self.hops.first().map_or(0, |hop| hop.amount_in)
+    }
+
+    pub fn amount_out(&self) -> u64 {
+        self.hops.last().map_or(0, |hop| hop.amount_out)
+    }
+
+    // The quoted output less a slippage tolerance in bps, for min_amount_out
+    pub fn min_amount_out(&self, slippage_bps: u16) -> u64 {
+        let slippage = (self.amount_out() as u128 * slippage_bps.min(10_000) as u128).div_ceil(10_000);
+        self.amount_out() - slippage as u64
+    }
+
+    /*
+        remaining_accounts for route_swap: pool, pool_ata_in, pool_ata_out, signer_ata_out per hop
+
+        The signer's associated token accounts for every mint after the first must already exist.
+    */
+    pub fn remaining_accounts(&self, signer: &Pubkey) -> Vec<AccountMeta> {
+        self.hops
+            .iter()
+            .flat_map(|hop| {
+                [
+                    AccountMeta::new_readonly(hop.pool, false),
+                    AccountMeta::new(get_associated_token_address(&hop.pool, &hop.mint_in), false),
+                    AccountMeta::new(get_associated_token_address(&hop.pool, &hop.mint_out), false),
+                    AccountMeta::new(get_associated_token_address(signer, &hop.mint_out), false),
+                ]
+            })
+            .collect()
+    }
+}
+
+pub struct Quoter {
+    pools: Vec<PoolInfo>,
+}
+
+impl Quoter {
+    pub fn new(pools: Vec<PoolInfo>) -> Self {
+        Self { pools }
+    }
+
+    /*
+        Best route from mint_in to mint_out with at most max_hops hops.
+
+        Tries every path that uses each pool and each mint at most once, so quotes are exact:
+        no hop sees reserves already moved by an earlier hop.
+    */
+    pub fn best_route(&self, mint_in: Pubkey, mint_out: Pubkey, amount_in: u64, max_hops: usize) -> Option<Route> {
+        if mint_in == mint_out || amount_in == 0 {
+            return None;
+        }
+        let mut best = None;
+        let mut path = Route::default();
+        let mut visited = vec![mint_in];
+        self.search(mint_in, amount_in, &mint_out, max_hops, &mut visited, &mut path, &mut best);
+        best
+    }
+
+    // map_or instead of Option::is_none_or, which needs rustc 1.82
+    #[allow(clippy::too_many_arguments, clippy::unnecessary_map_or)]
+    fn search(
+        &self,
+        mint: Pubkey,
+        amount: u64,
+        target: &Pubkey,
+        hops_left: usize,
+        visited: &mut Vec<Pubkey>,
+        path: &mut Route,
+        best: &mut Option<Route>,
+    ) {
+        if hops_left == 0 {
+            return;
+        }
+        for pool in &self.pools {
+            let Some((mint_out, amount_out)) = pool.quote(&mint, amount) else {
+                continue;
+            };
+            if visited.contains(&mint_out) {
+                continue;
+            }
+
+            path.hops.push(Hop {
+                pool: pool.address,
+                mint_in: mint,
+                mint_out,
+                amount_in: amount,
+                amount_out,
+            });
+            if mint_out == *target {
+                if best.as_ref().map_or(true, |route| amount_out > route.amount_out()) {
+                    *best = Some(path.clone());
+                }
+            } else {
+                visited.push(mint_out);
+                self.search(mint_out, amount_out, target, hops_left - 1, visited, path, best);
+                visited.pop();
+            }
+            path.hops.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(mint_a: Pubkey, mint_b: Pubkey, fee: u16, reserve_a: u64, reserve_b: u64) -> PoolInfo {
+        PoolInfo {
+            address: Pubkey::new_unique(),
+            mint_a,
+            mint_b,
+            fee,
+            reserve_a,
+            reserve_b,
+        }
+    }
+
+    #[test]
+    fn two_hops_beat_a_shallow_direct_pool() {
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let direct = pool(a, c, 30, 1_000, 1_000);
+        let ab = pool(a, b, 30, 1_000_000, 1_000_000);
+        // Stored as c/b, so the second hop trades mint_b for mint_a
+        let bc = pool(c, b, 30, 1_000_000, 1_000_000);
+        let quoter = Quoter::new(vec![direct.clone(), ab.clone(), bc.clone()]);
+
+        let route = quoter.best_route(a, c, 10_000, 2).unwrap();
+        assert_eq!(route.hops.len(), 2);
+        assert_eq!((route.hops[0].pool, route.hops[1].pool), (ab.address, bc.address));
+        assert_eq!(route.hops[0].amount_out, route.hops[1].amount_in);
+        assert_eq!(route.amount_in(), 10_000);
+        assert!(route.amount_out() > direct.quote(&a, 10_000).unwrap().1);
+
+        // 1% slippage rounds the tolerance up
+        let out = route.amount_out();
+        assert_eq!(route.min_amount_out(100), out - (out * 100).div_ceil(10_000));
+
+        let signer = Pubkey::new_unique();
+        let accounts = route.remaining_accounts(&signer);
+        assert_eq!(accounts.len(), 2 * amm::ROUTE_HOP_ACCOUNTS);
+        assert_eq!(accounts[4].pubkey, bc.address);
+        assert_eq!(accounts[5].pubkey, get_associated_token_address(&bc.address, &b));
+        assert_eq!(accounts[7].pubkey, get_associated_token_address(&signer, &c));
+    }
+
+    #[test]
+    fn max_hops_is_honored() {
+        let (a, b, c, d) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let quoter = Quoter::new(vec![
+            pool(a, c, 30, 1_000, 1_000),
+            pool(a, b, 30, 1_000_000, 1_000_000),
+            pool(b, c, 30, 1_000_000, 1_000_000),
+            pool(c, d, 30, 1_000_000, 1_000_000),
+        ]);
+
+        // The better two hop route is out of reach with one hop
+        let route = quoter.best_route(a, c, 10_000, 1).unwrap();
+        assert_eq!(route.hops.len(), 1);
+        assert!(route.amount_out() < quoter.best_route(a, c, 10_000, 2).unwrap().amount_out());
+
+        assert!(quoter.best_route(a, d, 10_000, 1).is_none());
+        assert_eq!(quoter.best_route(a, d, 10_000, 2).unwrap().hops.len(), 2);
+        assert_eq!(quoter.best_route(a, d, 10_000, 3).unwrap().hops.len(), 3);
+        assert!(quoter.best_route(a, d, 10_000, 0).is_none());
+    }
+
+    #[test]
+    fn quote_matches_route_swap() {
+        // The pools of the "Route swap a -> b -> c" test in tests/amm.ts, which receives 6 c on chain
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let ab = pool(a, b, 500, 48, 56);
+        let bc = pool(b, c, 500, 1_000, 1_000);
+        let quoter = Quoter::new(vec![ab.clone(), bc.clone()]);
+
+        let route = quoter.best_route(a, c, 10, 2).unwrap();
+        assert_eq!(route.hops[0].amount_out, 8);
+        assert_eq!(route.amount_out(), 6);
+
+        // route_swap runs each hop on the pool ATA balances with the previous hop's output
+        let mut amount = 10;
+        for (info, hop) in [ab, bc].iter().zip(&route.hops) {
+            let (reserve_in, reserve_out) = if hop.mint_in == info.mint_a {
+                (info.reserve_a, info.reserve_b)
+            } else {
+                (info.reserve_b, info.reserve_a)
+            };
+            amount = constant_product_amount_out(reserve_in, reserve_out, amount, info.fee).unwrap();
+            assert_eq!(amount, hop.amount_out);
+        }
+        assert_eq!(amount, route.amount_out());
+    }
+}
@@ -101,6 +101,20 @@ describe("amm", () => {
     associatedTokenProgram: ASSOCIATED_PROGRAM_ID
   }
 
+  const balance = async (ata: PublicKey) => new BN((await connection.getTokenAccountBalance(ata)).value.amount);
+
+  // Checks how much each of the signer's token accounts changed during fn
+  const expectChange = async (fn: () => Promise<unknown>, changeA: number, changeB: number) => {
+    const a = await balance(signerAtaA);
+    const b = await balance(signerAtaB);
+    await fn();
+    const diffA = (await balance(signerAtaA)).sub(a);
+    const diffB = (await balance(signerAtaB)).sub(b);
+    if (!diffA.eqn(changeA) || !diffB.eqn(changeB)) {
+      throw new Error(`expected ${changeA}/${changeB}, got ${diffA}/${diffB}`);
+    }
+  };
+
   it("Airdrop and create mints", async () => {
     let lamports = await getMinimumBalanceForRentExemptMint(connection);
     let tx = new Transaction();
@@ -171,8 +185,9 @@ describe("amm", () => {
   });
 
   it("Swap", async () => {
-    const tx = await program.methods.swap(
-      new BN(4), new BN(5), true
+    // Exact out: 4 a from 25/25 costs ceil(25 * 4 / 21 / 0.95) = 6 b
+    await expectChange(() => program.methods.swap(
+      new BN(4), new BN(6), true
     )
       // .preInstructions(
       //   [
@@ -193,7 +208,25 @@ describe("amm", () => {
     ])
     .rpc()
     .then(confirm)
-    .then(log);
+    .then(log), 4, -6);
+  });
+
+  it("Swap exact out fails above max amount in", async () => {
+    try {
+      await program.methods.swap(
+        new BN(4), new BN(5), true
+      )
+      .accountsStrict({
+        ...accounts
+      })
+      .signers([
+        signer
+      ])
+      .rpc();
+    } catch (e) {
+      return;
+    }
+    throw new Error("slippage check should fail");
   });
 
   it("Withdraw", async () => {
@@ -210,4 +243,484 @@ describe("amm", () => {
     .then(confirm)
     .then(log);
   });
+
+  it("Swap exact in a to b", async () => {
+    // 10 a, 9 after the 5% fee: 60 * 9 / (41 + 9) = 10 b
+    await expectChange(() => program.methods.swapExactIn(
+      new BN(10), new BN(10), { aToB: {} }
+    )
+    .accountsStrict({
+      ...accounts
+    })
+    .signers([
+      signer
+    ])
+    .rpc()
+    .then(confirm)
+    .then(log), -10, 10);
+  });
+
+  it("Swap exact in b to a", async () => {
+    // 10 b, 9 after the fee: 51 * 9 / (50 + 9) = 7 a
+    await expectChange(() => program.methods.swapExactIn(
+      new BN(10), new BN(7), { bToA: {} }
+    )
+    .accountsStrict({
+      ...accounts
+    })
+    .signers([
+      signer
+    ])
+    .rpc()
+    .then(confirm)
+    .then(log), 7, -10);
+  });
+
+  it("Swap exact out b", async () => {
+    // 4 b from 44/60 costs ceil(44 * 4 / 56 / 0.95) = 4 a
+    await expectChange(() => program.methods.swap(
+      new BN(4), new BN(4), false
+    )
+    .accountsStrict({
+      ...accounts
+    })
+    .signers([
+      signer
+    ])
+    .rpc()
+    .then(confirm)
+    .then(log), -4, 4);
+
+    // 48 * 56 > 41 * 60: the fees stayed in the reserves
+    const reserveA = await balance(poolAtaA);
+    const reserveB = await balance(poolAtaB);
+    if (!reserveA.eqn(48) || !reserveB.eqn(56)) throw new Error(`unexpected reserves ${reserveA}/${reserveB}`);
+  });
+
+  describe("route swap", () => {
+    // A second pool b/c, so a -> b -> c is a two hop route
+    const mintC = Keypair.generate();
+    const poolBC = PublicKey.findProgramAddressSync([
+      Buffer.from("pool"),
+      mintB.publicKey.toBuffer(),
+      mintC.publicKey.toBuffer(),
+      fee.toArrayLike(Buffer, "le", 2)
+    ],
+    program.programId)[0];
+    const mintLpBC = PublicKey.findProgramAddressSync([
+      Buffer.from("lp"),
+      poolBC.toBuffer()
+    ],
+    program.programId)[0];
+
+    const signerAtaC = getAssociatedTokenAddressSync(mintC.publicKey, signer.publicKey, false, tokenProgram);
+    const poolBCAtaB = getAssociatedTokenAddressSync(mintB.publicKey, poolBC, true, tokenProgram);
+    const poolBCAtaC = getAssociatedTokenAddressSync(mintC.publicKey, poolBC, true, tokenProgram);
+
+    const poolBCAccounts = {
+      ...accounts,
+      mintA: mintB.publicKey,
+      mintB: mintC.publicKey,
+      pool: poolBC,
+      mintLp: mintLpBC,
+      signerAtaA: signerAtaB,
+      signerAtaB: signerAtaC,
+      signerAtaLp: getAssociatedTokenAddressSync(mintLpBC, signer.publicKey, false, tokenProgram),
+      poolAtaA: poolBCAtaB,
+      poolAtaB: poolBCAtaC,
+    };
+
+    // pool, pool_ata_in, pool_ata_out, signer_ata_out per hop
+    const route = [pool, poolAtaA, poolAtaB, signerAtaB, poolBC, poolBCAtaB, poolBCAtaC, signerAtaC]
+      .map((pubkey, i) => ({ pubkey, isSigner: false, isWritable: i % 4 !== 0 }));
+
+    const routeSwap = (minAmountOut: number) => program.methods.routeSwap(
+      new BN(10), new BN(minAmountOut)
+    )
+    .accountsStrict({
+      signer: signer.publicKey,
+      signerAtaIn: signerAtaA,
+      tokenProgram,
+    })
+    .remainingAccounts(route)
+    .signers([
+      signer
+    ])
+    .rpc();
+
+    it("Create a b/c pool", async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection);
+      let tx = new Transaction();
+      tx.instructions = [
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: mintC.publicKey,
+          lamports,
+          space: MINT_SIZE,
+          programId: tokenProgram,
+        }),
+        createInitializeMint2Instruction(mintC.publicKey, 6, provider.publicKey!, null, tokenProgram),
+        createAssociatedTokenAccountIdempotentInstruction(provider.publicKey, signerAtaC, signer.publicKey, mintC.publicKey, tokenProgram),
+        createMintToInstruction(mintC.publicKey, signerAtaC, provider.publicKey!, 1e9, undefined, tokenProgram),
+      ];
+      await provider.sendAndConfirm(tx, [mintC]).then(log);
+
+      await program.methods.initialize(
+        fee.toNumber()
+      )
+      .accountsStrict({
+        ...poolBCAccounts
+      })
+      .signers([
+        signer
+      ])
+      .rpc()
+      .then(confirm)
+      .then(log);
+
+      await program.methods.deposit(
+        new BN(0), new BN(1000), new BN(1000)
+      )
+      .preInstructions([
+        createAssociatedTokenAccountIdempotentInstruction(
+          signer.publicKey,
+          poolBCAccounts.signerAtaLp,
+          signer.publicKey,
+          mintLpBC,
+          tokenProgram
+        )
+      ])
+      .accountsStrict({
+        ...poolBCAccounts
+      })
+      .signers([
+        signer
+      ])
+      .rpc()
+      .then(confirm)
+      .then(log);
+    });
+
+    it("Repair pool keeps the mint_b it was created with", async () => {
+      await program.methods.repairPool()
+      .accountsStrict({
+        mintA: mintA.publicKey,
+        mintB: mintB.publicKey,
+        pool,
+      })
+      .rpc()
+      .then(confirm)
+      .then(log);
+
+      const info = await program.account.pool.fetch(pool);
+      if (!info.mintB.equals(mintB.publicKey)) throw new Error("mint_b should match the pool seeds");
+    });
+
+    it("Route swap fails below the final min amount out", async () => {
+      try {
+        await routeSwap(7);
+      } catch (e) {
+        return;
+      }
+      throw new Error("slippage check should fail");
+    });
+
+    it("Route swap a -> b -> c", async () => {
+      // a/b is 48/56: 10 a, 9 after the fee, gives 56 * 9 / 57 = 8 b
+      // b/c is 1000/1000: 8 b, 7 after the fee, gives 1000 * 7 / 1007 = 6 c
+      const c = await balance(signerAtaC);
+      await expectChange(() => routeSwap(6).then(confirm).then(log), -10, 0);
+
+      const out = (await balance(signerAtaC)).sub(c);
+      if (!out.eqn(6)) throw new Error(`unexpected output ${out}`);
+    });
+  });
+
+  describe("concentrated liquidity", () => {
+    const clFee = new BN(30);
+    const tickSpacing = 64;
+    const ticksInArray = 64 * tickSpacing;
+    const tickLower = -128;
+    const tickUpper = 128;
+    const positionMint = Keypair.generate();
+
+    const clPool = PublicKey.findProgramAddressSync([
+      Buffer.from("cl_pool"),
+      mintA.publicKey.toBuffer(),
+      mintB.publicKey.toBuffer(),
+      clFee.toArrayLike(Buffer, "le", 2)
+    ],
+    program.programId)[0];
+
+    const tickArray = (start: number) => PublicKey.findProgramAddressSync([
+      Buffer.from("tick_array"),
+      clPool.toBuffer(),
+      new BN(start).toTwos(32).toArrayLike(Buffer, "le", 4)
+    ],
+    program.programId)[0];
+
+    const tickArrayUpper = tickArray(0);
+    const tickArrayLower = tickArray(-ticksInArray);
+
+    const position = PublicKey.findProgramAddressSync([
+      Buffer.from("position"),
+      positionMint.publicKey.toBuffer()
+    ],
+    program.programId)[0];
+
+    const clPoolAtaA = getAssociatedTokenAddressSync(mintA.publicKey, clPool, true, tokenProgram);
+    const clPoolAtaB = getAssociatedTokenAddressSync(mintB.publicKey, clPool, true, tokenProgram);
+    const signerAtaPosition = getAssociatedTokenAddressSync(positionMint.publicKey, signer.publicKey, false, tokenProgram);
+
+    const liquidityAccounts = {
+      signer: signer.publicKey,
+      mintA: mintA.publicKey,
+      mintB: mintB.publicKey,
+      pool: clPool,
+      position,
+      signerAtaPosition,
+      tickArrayLower,
+      tickArrayUpper,
+      signerAtaA,
+      signerAtaB,
+      poolAtaA: clPoolAtaA,
+      poolAtaB: clPoolAtaB,
+      tokenProgram,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    // A second, wider position [-4032, 4032], so liquidity changes when the price crosses -128
+    const positionMint2 = Keypair.generate();
+    const liquidityAccounts2 = {
+      ...liquidityAccounts,
+      position: PublicKey.findProgramAddressSync([
+        Buffer.from("position"),
+        positionMint2.publicKey.toBuffer()
+      ],
+      program.programId)[0],
+      signerAtaPosition: getAssociatedTokenAddressSync(positionMint2.publicKey, signer.publicKey, false, tokenProgram),
+    };
+
+    const openPosition = (mint: Keypair, accounts: typeof liquidityAccounts, lower: number, upper: number) =>
+      program.methods.openPosition(lower, upper)
+      .accountsStrict({
+        signer: signer.publicKey,
+        pool: clPool,
+        positionMint: mint.publicKey,
+        signerAtaPosition: accounts.signerAtaPosition,
+        position: accounts.position,
+        tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer, mint])
+      .rpc()
+      .then(confirm)
+      .then(log);
+
+    // Tick arrays go in the swap direction, starting with the one holding the current tick
+    const clSwap = (amountIn: number, minAmountOut: number, aToB: boolean, tickArrays: PublicKey[]) =>
+      program.methods.clSwap(
+        new BN(amountIn), new BN(minAmountOut), aToB
+      )
+      .accountsStrict({
+        signer: signer.publicKey,
+        mintA: mintA.publicKey,
+        mintB: mintB.publicKey,
+        signerAtaA,
+        signerAtaB,
+        poolAtaA: clPoolAtaA,
+        poolAtaB: clPoolAtaB,
+        pool: clPool,
+        tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(tickArrays.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .signers([signer])
+      .rpc({ commitment: "confirmed" })
+      .then(log);
+
+    const computeUnits = async (signature: string) => {
+      const tx = await connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+      return tx!.meta!.computeUnitsConsumed!;
+    };
+
+    // Tick -128 is slot 62 of the array starting at -4096
+    const tickMinus128 = async () => (await program.account.tickArray.fetch(tickArrayLower)).ticks[(tickLower + ticksInArray) / tickSpacing];
+
+    it("Initialize a pool at price 1", async () => {
+      await program.methods.initializeClPool(
+        clFee.toNumber(), tickSpacing, new BN(1).shln(64)
+      )
+      .accountsStrict({
+        signer: signer.publicKey,
+        mintA: mintA.publicKey,
+        mintB: mintB.publicKey,
+        poolAtaA: clPoolAtaA,
+        poolAtaB: clPoolAtaB,
+        pool: clPool,
+        tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc()
+      .then(confirm)
+      .then(log);
+
+      const pool = await program.account.clPool.fetch(clPool);
+      if (pool.tickCurrent !== 0) throw new Error("tick should start at 0");
+    });
+
+    it("Initialize tick arrays", async () => {
+      for (const start of [-ticksInArray, 0]) {
+        await program.methods.initializeTickArray(start)
+        .accountsStrict({
+          signer: signer.publicKey,
+          pool: clPool,
+          tickArray: tickArray(start),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc()
+        .then(confirm)
+        .then(log);
+      }
+    });
+
+    it("Open a position NFT", async () => {
+      await openPosition(positionMint, liquidityAccounts, tickLower, tickUpper);
+
+      if (!(await balance(signerAtaPosition)).eqn(1)) throw new Error("position NFT not minted");
+    });
+
+    it("Increase liquidity", async () => {
+      const a = await balance(signerAtaA);
+      const b = await balance(signerAtaB);
+
+      await program.methods.increaseLiquidity(
+        new BN(1_000_000), new BN(10_000), new BN(10_000)
+      )
+      .accountsStrict({ ...liquidityAccounts })
+      .signers([signer])
+      .rpc()
+      .then(confirm)
+      .then(log);
+
+      // L * (1 - 1 / sqrt(1.0001^128)) ~ 6380 of each token
+      const spentA = a.sub(await balance(signerAtaA));
+      const spentB = b.sub(await balance(signerAtaB));
+      if ([spentA, spentB].some((spent) => spent.ltn(6370) || spent.gtn(6390))) throw new Error(`unexpected deposit ${spentA} ${spentB}`);
+
+      const pool = await program.account.clPool.fetch(clPool);
+      if (!pool.liquidity.eqn(1_000_000)) throw new Error("liquidity should be active");
+    });
+
+    it("Swap a to b inside the range", async () => {
+      const b = await balance(signerAtaB);
+
+      await clSwap(1_000, 990, true, [tickArrayUpper, tickArrayLower]);
+
+      const out = (await balance(signerAtaB)).sub(b);
+      if (out.ltn(990)) throw new Error(`unexpected output ${out}`);
+
+      const pool = await program.account.clPool.fetch(clPool);
+      if (pool.tickCurrent >= 0 || pool.feeGrowthGlobalAX64.isZero()) throw new Error("price should move down and fees accrue");
+    });
+
+    it("Collect fees", async () => {
+      const a = await balance(signerAtaA);
+
+      await program.methods.collectFees()
+      .accountsStrict({ ...liquidityAccounts })
+      .signers([signer])
+      .rpc()
+      .then(confirm)
+      .then(log);
+
+      // 0.3% of 1000, rounded down
+      const fees = (await balance(signerAtaA)).sub(a);
+      if (fees.ltn(2) || fees.gtn(3)) throw new Error(`unexpected fees ${fees}`);
+    });
+
+    it("Open a second wider position", async () => {
+      await openPosition(positionMint2, liquidityAccounts2, -4032, 4032);
+
+      await program.methods.increaseLiquidity(
+        new BN(1_000_000), new BN(200_000), new BN(200_000)
+      )
+      .accountsStrict({ ...liquidityAccounts2 })
+      .signers([signer])
+      .rpc()
+      .then(confirm)
+      .then(log);
+
+      const pool = await program.account.clPool.fetch(clPool);
+      if (!pool.liquidity.eqn(2_000_000)) throw new Error("both positions should be active");
+    });
+
+    it("Swap a to b across tick -128", async () => {
+      let signature: string;
+      // 2e6 liquidity down to -128, then only the wide position's 1e6
+      await expectChange(async () => {
+        signature = await clSwap(20_000, 19_661, true, [tickArrayLower]);
+      }, -20_000, 19_661);
+
+      const pool = await program.account.clPool.fetch(clPool);
+      if (pool.tickCurrent !== -308) throw new Error(`unexpected tick ${pool.tickCurrent}`);
+      if (!pool.liquidity.eqn(1_000_000)) throw new Error("crossing -128 should remove the narrow position");
+
+      // Crossing flips the fee growth outside the tick to the growth above it
+      const tick = await tickMinus128();
+      if (!tick.liquidityNet.eqn(1_000_000)) throw new Error("unexpected liquidity net");
+      if (tick.feeGrowthOutsideAX64.isZero() || tick.feeGrowthOutsideAX64.gte(pool.feeGrowthGlobalAX64)) {
+        throw new Error("fee growth outside should be flipped");
+      }
+
+      const units = await computeUnits(signature!);
+      console.log(`cl_swap crossing one tick used ${units} compute units`);
+      if (units > 100_000) throw new Error(`cl_swap used ${units} compute units`);
+    });
+
+    it("Swap b to a back across tick -128", async () => {
+      // Crosses -128 up from the lower array, then searches the upper array for 128
+      await expectChange(
+        () => clSwap(15_000, 15_206, false, [tickArrayLower, tickArrayUpper]),
+        15_206, -15_000
+      );
+
+      const pool = await program.account.clPool.fetch(clPool);
+      if (pool.tickCurrent !== -68) throw new Error(`unexpected tick ${pool.tickCurrent}`);
+      if (!pool.liquidity.eqn(2_000_000)) throw new Error("crossing -128 up should add the narrow position back");
+    });
+
+    it("Fees accrue only inside each position's range", async () => {
+      const collect = (accounts: typeof liquidityAccounts) => program.methods.collectFees()
+        .accountsStrict({ ...accounts })
+        .signers([signer])
+        .rpc()
+        .then(confirm)
+        .then(log);
+
+      // The narrow position shares fees only while the price is above -128
+      await expectChange(() => collect(liquidityAccounts), 16, 9);
+      await expectChange(() => collect(liquidityAccounts2), 44, 36);
+    });
+
+    it("Decrease liquidity", async () => {
+      await program.methods.decreaseLiquidity(
+        new BN(1_000_000), new BN(0), new BN(0)
+      )
+      .accountsStrict({ ...liquidityAccounts })
+      .signers([signer])
+      .rpc()
+      .then(confirm)
+      .then(log);
+
+      const pool = await program.account.clPool.fetch(clPool);
+      if (!pool.liquidity.eqn(1_000_000)) throw new Error("only the wide position should be left");
+    });
+  });
 });